@@ -1,6 +1,23 @@
 pub const TEXTURE_SIZE: u32 = 20;
 pub const ZOOM_LEVEL: f32 = 1.0;
 
+// Map view camera
+pub const MIN_MAP_ZOOM: f32 = 0.25;
+pub const MAX_MAP_ZOOM: f32 = 4.0;
+pub const MAP_ZOOM_STEP: f32 = 0.25;
+pub const MIN_LABEL_ZOOM: f32 = 0.75;
+
+// World overview camera
+pub const MIN_WORLD_ZOOM: f32 = 0.05;
+pub const MAX_WORLD_ZOOM: f32 = 1.0;
+pub const WORLD_ZOOM_STEP: f32 = 0.05;
+
+// Render layers used by the map view, these are drawn with the map camera
+pub const RENDER_MAPVIEW_MAP: usize = 1;
+pub const RENDER_MAPVIEW: usize = 6;
+pub const RENDER_MAPVIEW_TEXT: usize = 7;
+pub const RENDER_WORLDVIEW: usize = 8;
+
 pub const ORDER_BG: f32 = 11.95;
 pub const ORDER_ALPHA_BG: f32 = 11.94;
 pub const ORDER_BG_BUTTON: f32 = 11.85;
@@ -27,15 +44,30 @@ pub const ORDER_TAB_LABEL: f32 = 11.5;
 pub const ORDER_MAP_ATTRIBUTE_BG: f32 = 4.9;
 pub const ORDER_MAP_ZONE: f32 = 4.9;
 pub const ORDER_MAP_DIRBLOCK: f32 = 4.9;
+pub const ORDER_MAP_WALK: f32 = 4.87;
+pub const ORDER_MAP_DIFF: f32 = 4.86;
+pub const ORDER_MAP_ENTITY: f32 = 4.85;
 pub const ORDER_MAP_ATTRIBUTE_TEXT: f32 = 4.8;
 pub const ORDER_MAP_SELECTION: f32 = 4.0;
 pub const ORDER_MAP_LINK_SELECT: f32 = 4.0;
+pub const ORDER_WORLD_MAP_HOVER: f32 = 4.0;
+pub const ORDER_WORLD_MAP_BORDER: f32 = 4.1;
 pub const ORDER_TILESET_SELECTION: f32 = 4.0;
 pub const ORDER_TILESETLIST: f32 = 3.9;
 pub const ORDER_TILESETLIST_SCROLL_BG: f32 = 3.8;
 pub const ORDER_TILESETLIST_BUTTON: f32 = 3.7;
 pub const ORDER_TILESETLIST_SCROLLBAR: f32 = 3.7;
 pub const ORDER_TILESETLIST_LABEL: f32 = 3.6;
+pub const ORDER_HISTORYLIST: f32 = 3.9;
+pub const ORDER_HISTORYLIST_SCROLL_BG: f32 = 3.8;
+pub const ORDER_HISTORYLIST_BUTTON: f32 = 3.7;
+pub const ORDER_HISTORYLIST_SCROLLBAR: f32 = 3.7;
+pub const ORDER_HISTORYLIST_LABEL: f32 = 3.6;
+pub const ORDER_VALIDATIONLIST: f32 = 3.9;
+pub const ORDER_VALIDATIONLIST_SCROLL_BG: f32 = 3.8;
+pub const ORDER_VALIDATIONLIST_BUTTON: f32 = 3.7;
+pub const ORDER_VALIDATIONLIST_SCROLLBAR: f32 = 3.7;
+pub const ORDER_VALIDATIONLIST_LABEL: f32 = 3.6;
 pub const ORDER_DIALOG_SHADOW: f32 = 2.9;
 pub const ORDER_DIALOG_WINDOW: f32 = 2.8;
 pub const ORDER_DIALOG_MSG: f32 = 2.7;
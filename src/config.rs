@@ -7,7 +7,10 @@ use std::path::Path;
 
 use winit::{event::*, keyboard::*};
 
-use crate::interface::preference::keybind::*;
+use crate::{
+    interface::preference::keybind::*,
+    map_data::{MapBinFormat, MapFileLayout},
+};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ConfigData {
@@ -18,6 +21,19 @@ pub struct ConfigData {
     pub hide_mapview_bg: bool,
     pub map_selection_color: [u8; 4],
     pub tile_selection_color: [u8; 4],
+    // Folder of the game data that holds the items, npcs and shops
+    #[serde(default = "default_game_data_path")]
+    pub game_data_path: String,
+    // Verbose, Rows or RunLength, the compact layouts are easier to diff
+    #[serde(default)]
+    pub map_file_layout: MapFileLayout,
+    // Raw or Compressed, the compressed binary starts with a header
+    #[serde(default)]
+    pub map_bin_format: MapBinFormat,
+}
+
+fn default_game_data_path() -> String {
+    "./gamedata".to_string()
 }
 
 impl ConfigData {
@@ -26,28 +42,8 @@ impl ConfigData {
         let mut key_code_modifier = Vec::new();
 
         for key in 0..EditorKey::Count as usize {
-            let keycode = match key {
-                1 => Key::Character(SmolStr::new("s")), // Save
-                2 => Key::Character(SmolStr::new("z")), // Undo
-                3 => Key::Character(SmolStr::new("y")), // Redo
-                4 => Key::Character(SmolStr::new("d")), // Draw
-                5 => Key::Character(SmolStr::new("e")), // Erase
-                6 => Key::Character(SmolStr::new("f")), // Fill
-                7 => Key::Character(SmolStr::new("y")), // Eyetool
-                _ => Key::Character(SmolStr::new("o")), // Load
-            };
-            let keycodemodifier = match key {
-                1 => [true, false, false],  // Save
-                2 => [true, false, false],  // Undo
-                3 => [true, false, false],  // Redo
-                4 => [false, false, false], // Draw
-                5 => [false, false, false], // Erase
-                6 => [false, false, false], // Fill
-                7 => [false, false, false], // Eyetool
-                _ => [true, false, false],  // Load
-            };
-            key_code.push(keycode);
-            key_code_modifier.push(keycodemodifier);
+            key_code.push(ConfigData::default_key(key));
+            key_code_modifier.push(ConfigData::default_key_modifier(key));
         }
 
         Self {
@@ -58,6 +54,55 @@ impl ConfigData {
             hide_mapview_bg: false,
             map_selection_color: [0, 0, 150, 150],
             tile_selection_color: [80, 0, 0, 150],
+            game_data_path: default_game_data_path(),
+            map_file_layout: MapFileLayout::default(),
+            map_bin_format: MapBinFormat::default(),
+        }
+    }
+
+    pub fn default_key(key: usize) -> Key {
+        match key {
+            1 => Key::Character(SmolStr::new("s")), // Save
+            2 => Key::Character(SmolStr::new("z")), // Undo
+            3 => Key::Character(SmolStr::new("y")), // Redo
+            4 => Key::Character(SmolStr::new("d")), // Draw
+            5 => Key::Character(SmolStr::new("e")), // Erase
+            6 => Key::Character(SmolStr::new("f")), // Fill
+            7 => Key::Character(SmolStr::new("y")), // Eyetool
+            8 => Key::Character(SmolStr::new("=")), // Zoom In
+            9 => Key::Character(SmolStr::new("-")), // Zoom Out
+            10 => Key::Character(SmolStr::new("0")), // Reset Zoom
+            11 => Key::Character(SmolStr::new("m")), // Map Diff
+            _ => Key::Character(SmolStr::new("o")), // Load
+        }
+    }
+
+    pub fn default_key_modifier(key: usize) -> [bool; 3] {
+        match key {
+            1 => [true, false, false],  // Save
+            2 => [true, false, false],  // Undo
+            3 => [true, false, false],  // Redo
+            4 => [false, false, false], // Draw
+            5 => [false, false, false], // Erase
+            6 => [false, false, false], // Fill
+            7 => [false, false, false], // Eyetool
+            8 => [true, false, false],  // Zoom In
+            9 => [true, false, false],  // Zoom Out
+            10 => [true, false, false], // Reset Zoom
+            11 => [true, false, false], // Map Diff
+            _ => [true, false, false],  // Load
+        }
+    }
+
+    // Config files saved by older versions may not have all of the keys
+    // so we fill the missing keys with their default value
+    pub fn fill_missing_keys(&mut self) {
+        for key in self.key_code.len()..EditorKey::Count as usize {
+            self.key_code.push(ConfigData::default_key(key));
+        }
+        for key in self.key_code_modifier.len()..EditorKey::Count as usize {
+            self.key_code_modifier
+                .push(ConfigData::default_key_modifier(key));
         }
     }
 
@@ -130,8 +175,11 @@ pub fn load_config() -> ConfigData {
         Ok(file) => {
             let reader = BufReader::new(file);
 
-            match serde_json::from_reader(reader) {
-                Ok(data) => data,
+            match serde_json::from_reader::<_, ConfigData>(reader) {
+                Ok(mut data) => {
+                    data.fill_missing_keys();
+                    data
+                }
                 Err(e) => {
                     println!("Error {:?}", e);
                     ConfigData::default()
@@ -1,18 +1,24 @@
 pub mod dialog_input;
+mod diff_input;
+mod history_input;
 mod interface_input;
 mod mapview_input;
 mod preference_input;
 mod tileset_input;
+mod validation_input;
 
 use cosmic_text::{Attrs, Metrics};
 use graphics::*;
 use winit::{event::*, keyboard::*};
 
 pub use dialog_input::*;
+use diff_input::*;
+use history_input::*;
 use interface_input::*;
 use mapview_input::*;
 use preference_input::*;
 use tileset_input::*;
+use validation_input::*;
 
 use crate::{
     collection::{TEXTURE_SIZE, ZOOM_LEVEL},
@@ -21,7 +27,10 @@ use crate::{
     interface::*,
     map::*,
     map_data::*,
+    map_render::TileIndex,
+    tiled::*,
     tileset::*,
+    validation::*,
     DrawSetting,
 };
 
@@ -30,6 +39,10 @@ pub enum MouseInputType {
     LeftDownMove,
     Move,
     Release,
+    MiddleDown,
+    MiddleDownMove,
+    MiddleRelease,
+    Scroll(f32),
 }
 
 #[derive(PartialEq, Eq)]
@@ -48,6 +61,13 @@ pub struct GameInput {
     pub selected_size: Vec2,
     // Map
     pub selected_link_map: Option<usize>,
+    pub in_pan: bool,
+    pub pan_pos: Vec2,
+    // Map of the warp that is waiting for its destination to be picked
+    pub warp_pick: Option<(i32, i32, u64)>,
+    pub hover_warp: bool,
+    // Start and end tile of the dir block rectangle that is being drawn
+    pub dir_block_rect: Option<(Vec2, Vec2)>,
     // Dialog
     pub dialog_button_press: bool,
     pub selected_dialog_type: DialogButtonType,
@@ -63,6 +83,11 @@ impl GameInput {
             tileset_end: Vec2::new(0.0, 0.0),
             selected_size: Vec2::new(1.0, 1.0),
             selected_link_map: None,
+            in_pan: false,
+            pan_pos: Vec2::new(0.0, 0.0),
+            warp_pick: None,
+            hover_warp: false,
+            dir_block_rect: None,
             dialog_button_press: false,
             selected_dialog_type: DialogButtonType::None,
             hold_key_modifier: [false; 3],
@@ -111,11 +136,39 @@ pub fn handle_input(
         return;
     }
 
+    // Handle History List, it is placed above the map view
+    if history_input(systems, &inputtype, screen_pos, gui, mapview, database) {
+        return;
+    }
+    if validation_input(systems, &inputtype, screen_pos, gui, mapview, database)
+    {
+        return;
+    }
+
+    // Handle Mapview zoom and panning
+    if mapview_camera_input(
+        systems,
+        &inputtype,
+        screen_pos,
+        gameinput,
+        gui,
+        mapview,
+        config_data,
+    ) {
+        return;
+    }
+
     // Handle Mapview Inputs
-    mapview_input(
-        systems, &inputtype, screen_pos, gameinput, gui, tileset, mapview,
-        database,
-    );
+    if mapview.world.is_open {
+        worldview_input(
+            systems, &inputtype, screen_pos, gui, mapview, database,
+        );
+    } else {
+        mapview_input(
+            systems, &inputtype, screen_pos, gameinput, gui, tileset, mapview,
+            database,
+        );
+    }
     // Handle tileset inputs
     tileset_input(
         systems, &inputtype, screen_pos, gameinput, gui, tileset, mapview,
@@ -205,27 +258,42 @@ pub fn access_shortcut(
             config_data.key_code[index] == keycode
                 && config_data.key_code_modifier[index] == key_modifier
         }) {
-            let button_index = match got_index {
-                1 => TOOL_SAVE,
-                2 => TOOL_UNDO,
-                3 => TOOL_REDO,
-                4 => TOOL_DRAW,
-                5 => TOOL_ERASE,
-                6 => TOOL_FILL,
-                7 => TOOL_EYEDROP,
-                _ => TOOL_LOAD,
-            };
+            match got_index {
+                8 => mapview.camera.zoom_in(MapCamera::center()),
+                9 => mapview.camera.zoom_out(MapCamera::center()),
+                10 => mapview.camera.reset(),
+                11 => {
+                    if mapview.diff.is_some() {
+                        close_map_diff(systems, gui, mapview, database);
+                    } else {
+                        gui.open_dialog(systems, DialogType::MapDiff, None);
+                    }
+                }
+                _ => {
+                    let button_index = match got_index {
+                        1 => TOOL_SAVE,
+                        2 => TOOL_UNDO,
+                        3 => TOOL_REDO,
+                        4 => TOOL_DRAW,
+                        5 => TOOL_ERASE,
+                        6 => TOOL_FILL,
+                        7 => TOOL_EYEDROP,
+                        _ => TOOL_LOAD,
+                    };
 
-            gui_button_select(
-                button_index,
-                systems,
-                gameinput,
-                gui,
-                tileset,
-                mapview,
-                database,
-                config_data,
-            );
+                    gui_button_select(
+                        button_index,
+                        systems,
+                        gameinput,
+                        gui,
+                        tileset,
+                        mapview,
+                        database,
+                        config_data,
+                    );
+                }
+            }
+            update_map_zoom(systems, gui, mapview, config_data);
         }
     }
 }
@@ -253,11 +321,17 @@ pub fn gui_button_select(
             database.save_map_data(mapview, None);
             update_map_name(systems, gui, database);
         }
-        TOOL_UNDO => {
-            mapview.apply_change(systems, true);
-        }
-        TOOL_REDO => {
-            mapview.apply_change(systems, false);
+        TOOL_UNDO | TOOL_REDO => {
+            let did_change = if button_index == TOOL_UNDO {
+                mapview.undo(systems)
+            } else {
+                mapview.redo(systems)
+            };
+            if did_change {
+                update_map_change(systems, gui, mapview, database);
+                refresh_map_setting(systems, gui, mapview);
+                update_map_diff(systems, gui, mapview, database);
+            }
         }
         TOOL_DRAW | TOOL_ERASE | TOOL_FILL | TOOL_EYEDROP => {
             gui.set_tool(systems, button_index);
@@ -308,9 +382,14 @@ pub fn update_map_name(
 }
 
 pub fn is_scrollbar_in_hold(gui: &mut Interface) -> bool {
-    if (gui.tileset_list.scrollbar.in_hold || gui.scrollbar.in_hold)
-        || (gui.current_tab == TAB_PROPERTIES
-            && gui.selected_dropbox >= 0
+    if (gui.tileset_list.scrollbar.in_hold
+        || gui.history_list.scrollbar.in_hold
+        || gui.validation_list.scrollbar.in_hold
+        || gui.scrollbar.in_hold)
+        || (matches!(
+            gui.current_tab,
+            TAB_PROPERTIES | TAB_ATTRIBUTE | TAB_ZONE
+        ) && gui.selected_dropbox >= 0
             && gui.editor_selectionbox[gui.selected_dropbox as usize]
                 .scrollbar
                 .in_hold)
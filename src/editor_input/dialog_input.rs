@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use crate::editor_input::*;
 
 // We will handle the dialog input upon the release state of the selected Input
@@ -39,11 +41,85 @@ fn dialog_release_input(
                         }
                     }
 
-                    database.init_map(x, y, group);
+                    // Keep the changes of the current map before switching
+                    if database.did_change(
+                        database.x,
+                        database.y,
+                        database.group,
+                    ) {
+                        database.save_map_data(
+                            mapview,
+                            Some(database.current_index.clone()),
+                        );
+                    }
+                    database.init_map(x, y, group, mapview);
                     database.load_map_data(systems, mapview);
                     database.load_link_maps(mapview);
                     update_map_name(systems, gui, database);
+                    update_map_diff(systems, gui, mapview, database);
+                    gui.close_dialog(systems);
+                }
+                DialogType::MapDiff => {
+                    let path = dialog.editor_textbox[0].data.trim().to_string();
+                    gui.close_dialog(systems);
+                    let source = if path.is_empty() {
+                        DiffSource::Saved
+                    } else {
+                        DiffSource::File(path)
+                    };
+                    open_map_diff(systems, gui, mapview, database, source);
+                }
+                DialogType::TiledImport => {
+                    let value = |index: usize| {
+                        dialog.editor_textbox[index]
+                            .data
+                            .parse::<i64>()
+                            .unwrap_or_default()
+                    };
+                    let start =
+                        (value(0) as i32, value(1) as i32, value(2) as u64);
+                    let path = dialog.editor_textbox[3].data.trim().to_string();
                     gui.close_dialog(systems);
+                    import_tiled_maps(
+                        systems, gui, database, mapview, &path, start,
+                    );
+                }
+                DialogType::TiledExport => {
+                    let value = |index: usize| {
+                        dialog.editor_textbox[index]
+                            .data
+                            .parse::<i64>()
+                            .unwrap_or_default()
+                    };
+                    let start =
+                        (value(0) as i32, value(1) as i32, value(2) as u64);
+                    let size = (value(4) as u32, value(5) as u32);
+                    let path = dialog.editor_textbox[3].data.trim().to_string();
+                    gui.close_dialog(systems);
+
+                    // Include the changes of the current map on the export
+                    database.save_map_data(
+                        mapview,
+                        Some(database.current_index.clone()),
+                    );
+                    let msg = match export_tiled(
+                        &TileIndex::new(&systems.resource),
+                        &systems.attribute_schema,
+                        Path::new(&path),
+                        start,
+                        size,
+                        &database.maps,
+                    ) {
+                        Ok(count) => {
+                            format!("Exported {} map(s) to {}", count, path)
+                        }
+                        Err(e) => format!("Tiled Export Failed: {:?}", e),
+                    };
+                    systems.gfx.set_text(
+                        &mut systems.renderer,
+                        gui.labels[LABEL_MAPNAME],
+                        &msg,
+                    );
                 }
                 DialogType::MapSave => {
                     database.save_all_maps(mapview);
@@ -51,11 +127,20 @@ fn dialog_release_input(
                 }
                 _ => {}
             },
-            DialogButtonType::Decline => {
-                if dialog.dialog_type == DialogType::MapSave {
-                    elwt.exit()
+            DialogButtonType::Decline => match dialog.dialog_type {
+                DialogType::MapSave => elwt.exit(),
+                DialogType::MapDiff => {
+                    gui.close_dialog(systems);
+                    open_map_diff(
+                        systems,
+                        gui,
+                        mapview,
+                        database,
+                        DiffSource::Backup,
+                    );
                 }
-            }
+                _ => {}
+            },
             DialogButtonType::Cancel => gui.close_dialog(systems),
             _ => {}
         }
@@ -117,6 +202,7 @@ pub fn dialog_input(
                     );
                 }
             }
+            _ => {}
         }
     }
 }
@@ -126,13 +212,97 @@ pub fn dialog_key_input(
     event: &KeyEvent,
     dialog: &mut Dialog,
 ) {
-    if dialog.dialog_type == DialogType::MapLoad {
-        if dialog.editing_index < 2 {
+    if matches!(
+        dialog.dialog_type,
+        DialogType::MapLoad | DialogType::TiledImport | DialogType::TiledExport
+    ) {
+        if dialog.editing_index == 3 {
+            dialog.editor_textbox[dialog.editing_index]
+                .enter_text(systems, event, 255);
+        } else if dialog.editing_index < 2 {
             dialog.editor_textbox[dialog.editing_index]
                 .enter_numeric(systems, event, 5, true);
         } else {
             dialog.editor_textbox[dialog.editing_index]
                 .enter_numeric(systems, event, 5, false);
         }
+    } else if dialog.dialog_type == DialogType::MapDiff {
+        dialog.editor_textbox[dialog.editing_index]
+            .enter_text(systems, event, 255);
+    }
+}
+
+// The imported maps are kept as unsaved changes, so they can still be
+// reviewed before they are written on the map files
+fn import_tiled_maps(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    database: &mut EditorData,
+    mapview: &mut MapView,
+    path: &str,
+    start: (i32, i32, u64),
+) {
+    if database.did_change(database.x, database.y, database.group) {
+        database.save_map_data(mapview, Some(database.current_index.clone()));
+    }
+    let result = import_tiled(
+        &TileIndex::new(&systems.resource),
+        &systems.attribute_schema,
+        Path::new(path),
+        start,
+        &database.maps,
+    );
+    let import = match result {
+        Ok(import) => import,
+        Err(e) => {
+            systems.gfx.set_text(
+                &mut systems.renderer,
+                gui.labels[LABEL_MAPNAME],
+                &format!("Tiled Import Failed: {:?}", e),
+            );
+            return;
+        }
+    };
+
+    let keys: Vec<String> = import
+        .maps
+        .iter()
+        .map(|mapdata| {
+            format!(
+                "{}_{}_{}",
+                mapdata.position.x, mapdata.position.y, mapdata.position.group
+            )
+        })
+        .collect();
+    for (key, mapdata) in keys.iter().zip(import.maps) {
+        database.maps.insert(key.clone(), mapdata);
+        database.did_map_change.insert(key.clone(), true);
+    }
+    if mapview.world.is_open {
+        mapview.close_world_view(systems);
+    }
+    database.init_map(start.0, start.1, start.2, mapview);
+    // The edit history of the replaced maps no longer matches their tiles
+    keys.iter().for_each(|key| {
+        database.histories.shift_remove(key);
+    });
+    refresh_map_view(systems, gui, mapview, database);
+
+    if !import.warnings.is_empty() {
+        let issues = import
+            .warnings
+            .into_iter()
+            .map(|reason| ValidationIssue {
+                x: start.0,
+                y: start.1,
+                group: start.2,
+                tile: None,
+                reason,
+            })
+            .collect();
+        gui.history_list.hide(systems);
+        gui.validation_list
+            .set_issues(systems, "Tiled Import", issues);
+        gui.validation_list.show(systems);
     }
 }
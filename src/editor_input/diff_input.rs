@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use crate::editor_input::*;
+
+// The map on the map view can be compared with its saved file, the backup
+// that is kept on each save or any other map file
+pub enum DiffSource {
+    Saved,
+    Backup,
+    File(String),
+}
+
+pub fn open_map_diff(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+    source: DiffSource,
+) {
+    let path = match source {
+        DiffSource::Saved => format!(
+            "./data/maps/{}_{}_{}.json",
+            database.x, database.y, database.group
+        ),
+        DiffSource::Backup => {
+            get_backup_path(database.x, database.y, database.group)
+        }
+        DiffSource::File(path) => path,
+    };
+    let other = match read_map_file(Path::new(&path)) {
+        Ok(other) => other,
+        Err(e) => {
+            systems.gfx.set_text(
+                &mut systems.renderer,
+                gui.labels[LABEL_MAPNAME],
+                &format!("Map Diff Failed: {:?}", e),
+            );
+            return;
+        }
+    };
+
+    // The map data is compared with the edits that are not saved yet
+    database.save_map_data(mapview, Some(database.current_index.clone()));
+    let current = match database.maps.get(&database.current_index) {
+        Some(current) => current,
+        None => return,
+    };
+    mapview.diff =
+        Some(MapDiff::new(&database.current_index, &path, current, other));
+
+    gui.history_list.hide(systems);
+    show_map_diff(systems, gui, mapview, database);
+    gui.validation_list.show(systems);
+}
+
+// Compare the map again after it was edited, the diff is closed once
+// another map is opened on the map view
+pub fn update_map_diff(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    let key = match &mapview.diff {
+        Some(diff) => diff.key.clone(),
+        None => return,
+    };
+    if key != database.current_index {
+        close_map_diff(systems, gui, mapview, database);
+        return;
+    }
+
+    database.save_map_data(mapview, Some(key.clone()));
+    if let (Some(diff), Some(current)) =
+        (&mut mapview.diff, database.maps.get(&key))
+    {
+        diff.update(current);
+    }
+    show_map_diff(systems, gui, mapview, database);
+}
+
+pub fn close_map_diff(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &EditorData,
+) {
+    if mapview.diff.take().is_none() {
+        return;
+    }
+    mapview.clear_diff_overlay(systems);
+    gui.validation_list.hide(systems);
+    update_map_name(systems, gui, database);
+}
+
+// Set the clicked tile back to the compared map
+pub fn revert_diff_tile(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+    pos: Vec2,
+) {
+    let diff = match mapview.diff.take() {
+        Some(diff) => diff,
+        None => return,
+    };
+    mapview.revert_tile(systems, pos, &diff.other);
+    mapview.diff = Some(diff);
+
+    update_map_change(systems, gui, mapview, database);
+    refresh_map_setting(systems, gui, mapview);
+    update_map_diff(systems, gui, mapview, database);
+}
+
+fn show_map_diff(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &EditorData,
+) {
+    let diff = match &mapview.diff {
+        Some(diff) => diff,
+        None => return,
+    };
+    let tiles = diff.overlay();
+    let issues = diff
+        .changes
+        .iter()
+        .map(|change| ValidationIssue {
+            x: database.x,
+            y: database.y,
+            group: database.group,
+            tile: change.tile,
+            reason: change.reason.clone(),
+        })
+        .collect();
+    let msg = format!(
+        "Diff with {}: {} change(s)",
+        diff.source,
+        diff.changes.len()
+    );
+
+    mapview.set_diff_overlay(systems, &tiles);
+    gui.validation_list.set_issues(systems, "Map Diff", issues);
+    systems.gfx.set_text(
+        &mut systems.renderer,
+        gui.labels[LABEL_MAPNAME],
+        &msg,
+    );
+}
@@ -0,0 +1,67 @@
+use crate::editor_input::*;
+
+// Return true if the input has been used by the history list
+pub fn history_input(
+    systems: &mut DrawSetting,
+    inputtype: &MouseInputType,
+    screen_pos: Vec2,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) -> bool {
+    if !gui.history_list.visible {
+        return false;
+    }
+
+    match inputtype {
+        MouseInputType::LeftDown => {
+            if gui.history_list.scrollbar.in_scrollbar(systems, screen_pos) {
+                gui.history_list
+                    .scrollbar
+                    .hold_scrollbar(systems, screen_pos.y);
+                return true;
+            }
+            if !gui.history_list.in_list(systems, screen_pos) {
+                return false;
+            }
+            if let Some(position) =
+                gui.history_list.select_list(systems, screen_pos)
+            {
+                if mapview.jump_history(systems, position) {
+                    update_map_change(systems, gui, mapview, database);
+                    refresh_map_setting(systems, gui, mapview);
+                    update_map_diff(systems, gui, mapview, database);
+                }
+            }
+            true
+        }
+        MouseInputType::LeftDownMove => {
+            if !gui.history_list.scrollbar.in_hold {
+                return false;
+            }
+            gui.history_list.scrollbar.move_scrollbar(
+                systems,
+                screen_pos.y,
+                false,
+            );
+            if gui
+                .history_list
+                .update_scroll(gui.history_list.scrollbar.cur_value)
+            {
+                gui.history_list.update_list(systems, &mapview.history);
+            }
+            gui.history_list.scrollbar.set_hover(systems, screen_pos);
+            true
+        }
+        MouseInputType::Move => {
+            gui.history_list.hover_selection(systems, screen_pos);
+            gui.history_list.scrollbar.set_hover(systems, screen_pos);
+            gui.history_list.in_list(systems, screen_pos)
+        }
+        MouseInputType::Release => {
+            gui.history_list.scrollbar.release_scrollbar(systems);
+            false
+        }
+        _ => false,
+    }
+}
@@ -20,8 +20,10 @@ pub fn interface_input(
                     .hold_scrollbar(systems, screen_pos.y);
             } else if gui.scrollbar.in_scrollbar(systems, screen_pos) {
                 gui.scrollbar.hold_scrollbar(systems, screen_pos.y);
-            } else if gui.current_tab == TAB_PROPERTIES
-                && gui.selected_dropbox >= 0
+            } else if matches!(
+                gui.current_tab,
+                TAB_PROPERTIES | TAB_ATTRIBUTE | TAB_ZONE
+            ) && gui.selected_dropbox >= 0
                 && gui.editor_selectionbox[gui.selected_dropbox as usize]
                     .scrollbar
                     .in_scrollbar(systems, screen_pos)
@@ -57,7 +59,7 @@ pub fn interface_input(
                         TAB_ATTRIBUTE => open_attribute_settings(
                             systems,
                             gui,
-                            gui.current_tab_data + 1,
+                            gui.current_tab_data as usize,
                             vec![],
                         ),
                         TAB_ZONE => {
@@ -65,6 +67,7 @@ pub fn interface_input(
                                 systems,
                                 gui.current_tab_data as usize,
                             );
+                            gui.zone_npc_index = 0;
                             gui.open_zone_settings(systems, mapview);
                         }
                         _ => {}
@@ -73,8 +76,165 @@ pub fn interface_input(
 
                 // Textbox / Buttons
                 match gui.current_tab {
-                    TAB_ATTRIBUTE | TAB_ZONE => {
-                        gui.select_textbox(systems, screen_pos)
+                    TAB_ATTRIBUTE => {
+                        // Dropdown List, it is placed above the other fields
+                        if gui.selected_dropbox >= 0 {
+                            let selected = gui.selected_dropbox as usize;
+                            if let Some(index) = gui.editor_selectionbox
+                                [selected]
+                                .click_list(systems, screen_pos)
+                            {
+                                gui.editor_selectionbox[selected]
+                                    .switch_list(systems, index);
+                                gui.editor_selectionbox[selected]
+                                    .hide_list(systems);
+                                gui.selected_dropbox = -1;
+                                return;
+                            }
+                        }
+
+                        gui.select_textbox(systems, screen_pos);
+                        gui.select_text_editor(systems, screen_pos);
+
+                        // Checkbox
+                        gui.editor_checkbox.iter_mut().for_each(|checkbox| {
+                            let (pos, size) = (
+                                systems.gfx.get_pos(checkbox.window[0]),
+                                systems.gfx.get_size(checkbox.window[0]),
+                            );
+                            if screen_pos.x >= pos.x
+                                && screen_pos.x <= pos.x + size.x
+                                && screen_pos.y >= pos.y
+                                && screen_pos.y <= pos.y + size.y
+                            {
+                                let is_select = !checkbox.is_select;
+                                checkbox.set_select(systems, is_select);
+                            }
+                        });
+                        // Checkbox that is not a field is the return warp option
+                        if let Some(index) =
+                            gui.editor_checkbox.len().checked_sub(1)
+                        {
+                            if !gui
+                                .attribute_fields
+                                .contains(&FieldWidget::Checkbox(index))
+                            {
+                                gui.return_warp =
+                                    gui.editor_checkbox[index].is_select;
+                            }
+                        }
+                        update_sign_preview(systems, gui);
+
+                        // Warp Buttons
+                        let click_button =
+                            gui.click_buttons(systems, screen_pos);
+                        if let Some(button_index) = click_button {
+                            if let MapAttribute::Warp(data) =
+                                gui.get_attribute_setting(systems)
+                            {
+                                if button_index == 0 {
+                                    gameinput.warp_pick = Some((
+                                        database.x,
+                                        database.y,
+                                        database.group,
+                                    ));
+                                }
+                                open_warp_destination(
+                                    systems, gui, mapview, database, &data,
+                                );
+                            }
+                        }
+
+                        // Selection box
+                        let click_button =
+                            gui.click_selectionbox(systems, screen_pos);
+                        if let Some(selection_index) = click_button {
+                            if gui.selected_dropbox >= 0 {
+                                gui.editor_selectionbox
+                                    [gui.selected_dropbox as usize]
+                                    .hide_list(systems);
+                            }
+                            if gui.selected_dropbox == selection_index as i32 {
+                                gui.selected_dropbox = -1;
+                            } else {
+                                gui.editor_selectionbox[selection_index]
+                                    .show_list(systems);
+                                gui.selected_dropbox = selection_index as i32;
+                            }
+                        }
+                    }
+                    TAB_ZONE => {
+                        // Dropdown List
+                        if gui.selected_dropbox >= 0 {
+                            let selected = gui.selected_dropbox as usize;
+                            if let Some(index) = gui.editor_selectionbox
+                                [selected]
+                                .click_list(systems, screen_pos)
+                            {
+                                gui.editor_selectionbox[selected]
+                                    .switch_list(systems, index);
+                                gui.editor_selectionbox[selected]
+                                    .hide_list(systems);
+                                gui.selected_dropbox = -1;
+
+                                if gui.is_placement_open(mapview) {
+                                    update_entity_setting(
+                                        systems, gui, mapview, database,
+                                    );
+                                } else if selected == 0 {
+                                    // Show the picked npc entry
+                                    gui.zone_npc_index = gui
+                                        .editor_selectionbox[0]
+                                        .selected_index;
+                                } else if let Some(setting) =
+                                    gui.get_zone_setting(mapview)
+                                {
+                                    mapview.set_zone_setting(
+                                        gui.current_tab_data as usize,
+                                        setting,
+                                    );
+                                    database.set_map_change();
+                                    update_map_name(systems, gui, database);
+                                }
+                                gui.open_zone_settings(systems, mapview);
+                                return;
+                            }
+                        }
+
+                        gui.select_textbox(systems, screen_pos);
+                        gui.select_text_editor(systems, screen_pos);
+
+                        // Zone and npc entry buttons
+                        let click_button =
+                            gui.click_buttons(systems, screen_pos);
+                        if let Some(button_index) = click_button {
+                            zone_button_select(
+                                button_index,
+                                systems,
+                                gui,
+                                mapview,
+                                database,
+                            );
+                            return;
+                        }
+
+                        // Selection box
+                        let click_button =
+                            gui.click_selectionbox(systems, screen_pos);
+                        if let Some(selection_index) = click_button {
+                            if gui.selected_dropbox >= 0 {
+                                gui.editor_selectionbox
+                                    [gui.selected_dropbox as usize]
+                                    .hide_list(systems);
+                            }
+                            if gui.selected_dropbox == selection_index as i32 {
+                                gui.selected_dropbox = -1;
+                            } else {
+                                gui.editor_selectionbox[selection_index]
+                                    .show_list(systems);
+                                gui.selected_dropbox = selection_index as i32;
+                            }
+                        }
                     }
                     TAB_PROPERTIES => {
                         // Buttons
@@ -84,7 +244,7 @@ pub fn interface_input(
                             match button_index {
                                 0 => database.save_all_maps(mapview),
                                 1 => {
-                                    database.reset_all_map();
+                                    database.reset_all_map(mapview);
                                     database.load_map_data(systems, mapview);
                                     database.load_link_maps(mapview);
                                     update_map_name(systems, gui, database);
@@ -97,15 +257,90 @@ pub fn interface_input(
                                         config_data,
                                     );
                                 }
+                                3 => {
+                                    if mapview.world.is_open {
+                                        mapview.close_world_view(systems);
+                                    } else {
+                                        mapview
+                                            .open_world_view(systems, database);
+                                    }
+                                }
+                                4 => {
+                                    if gui.history_list.visible {
+                                        gui.history_list.hide(systems);
+                                    } else {
+                                        gui.validation_list.hide(systems);
+                                        gui.history_list
+                                            .show(systems, &mapview.history);
+                                    }
+                                }
+                                5 => {
+                                    if gui.validation_list.visible {
+                                        gui.validation_list.hide(systems);
+                                    } else {
+                                        open_validation_report(
+                                            systems, gui, mapview, database,
+                                        );
+                                    }
+                                }
+                                6 => {
+                                    let properties = load_tile_properties();
+                                    mapview
+                                        .history
+                                        .begin_group("Generate Dir Blocks");
+                                    mapview.generate_dir_blocks(
+                                        systems,
+                                        &properties,
+                                    );
+                                    mapview.history.end_group();
+                                    database.set_map_change();
+                                    update_map_name(systems, gui, database);
+                                }
+                                7 => update_walk_analysis(
+                                    systems, gui, mapview, database,
+                                ),
+                                8 => {
+                                    if gui.validation_list.visible {
+                                        gui.validation_list.hide(systems);
+                                    } else {
+                                        open_seam_report(
+                                            systems, gui, mapview, database,
+                                        );
+                                    }
+                                }
+                                9 => gui.open_dialog(
+                                    systems,
+                                    DialogType::TiledImport,
+                                    None,
+                                ),
+                                10 => open_tiled_export(systems, gui, database),
                                 _ => {}
                             }
                         }
 
+                        // Seam checker option
+                        if let Some(checkbox) = gui.editor_checkbox.first_mut()
+                        {
+                            let (pos, size) = (
+                                systems.gfx.get_pos(checkbox.window[0]),
+                                systems.gfx.get_size(checkbox.window[0]),
+                            );
+                            if screen_pos.x >= pos.x
+                                && screen_pos.x <= pos.x + size.x
+                                && screen_pos.y >= pos.y
+                                && screen_pos.y <= pos.y + size.y
+                            {
+                                let is_select = !checkbox.is_select;
+                                checkbox.set_select(systems, is_select);
+                                gui.seam_check_tiles = is_select;
+                            }
+                        }
+
                         // Selection box
                         let click_button =
                             gui.click_selectionbox(systems, screen_pos);
                         if let Some(selection_index) = click_button {
-                            if matches!(selection_index, 0 | 1) {
+                            if matches!(selection_index, 0..=4) {
                                 if !gui.editor_selectionbox[selection_index]
                                     .is_list_visible
                                 {
@@ -118,7 +353,7 @@ pub fn interface_input(
                                         .hide_list(systems);
                                     gui.selected_dropbox = -1;
                                 }
-                            } // Weather & Music
+                            } // Weather, Music, Dir Block Mode & Walkability
                         }
 
                         // Dropdown List
@@ -133,12 +368,13 @@ pub fn interface_input(
 
                                 match gui.selected_dropbox {
                                     0 => {
-                                        mapview.fixed_weather = gui
-                                            .editor_selectionbox
-                                            [gui.selected_dropbox as usize]
-                                            .selected_index
-                                            as u8;
-                                        database.set_map_change(mapview);
+                                        mapview.set_weather(
+                                            gui.editor_selectionbox
+                                                [gui.selected_dropbox as usize]
+                                                .selected_index
+                                                as u8,
+                                        );
+                                        database.set_map_change();
                                         update_map_name(systems, gui, database);
                                     }
                                     1 => {
@@ -146,19 +382,40 @@ pub fn interface_input(
                                             [gui.selected_dropbox as usize]
                                             .selected_index;
                                         if index == 0 {
-                                            mapview.music = None;
+                                            mapview.set_music(None);
                                         } else {
                                             let list_name = gui
                                                 .editor_selectionbox
                                                 [gui.selected_dropbox as usize]
                                                 .list[index]
                                                 .clone();
-                                            mapview.music = Some(list_name);
-                                            database.set_map_change(mapview);
-                                            update_map_name(
-                                                systems, gui, database,
-                                            );
+                                            mapview.set_music(Some(list_name));
                                         }
+                                        database.set_map_change();
+                                        update_map_name(systems, gui, database);
+                                    }
+                                    2 => {
+                                        gui.dir_block_mode =
+                                            DirBlockMode::MODES[gui
+                                                .editor_selectionbox[2]
+                                                .selected_index];
+                                    }
+                                    3 => {
+                                        gui.walk_mode = WalkMode::MODES[gui
+                                            .editor_selectionbox[3]
+                                            .selected_index];
+                                        gui.walk_points.clear();
+                                        update_walk_analysis(
+                                            systems, gui, mapview, database,
+                                        );
+                                    }
+                                    4 => {
+                                        gui.walk_rule = WalkRule::RULES[gui
+                                            .editor_selectionbox[4]
+                                            .selected_index];
+                                        update_walk_analysis(
+                                            systems, gui, mapview, database,
+                                        );
                                     }
                                     _ => {}
                                 }
@@ -176,12 +433,24 @@ pub fn interface_input(
             }
         }
         MouseInputType::LeftDownMove => {
+            if matches!(gui.current_tab, TAB_ATTRIBUTE | TAB_ZONE) {
+                gui.editor_text_editor
+                    .iter_mut()
+                    .filter(|text_editor| text_editor.is_selected)
+                    .for_each(|text_editor| {
+                        text_editor.click_text(systems, screen_pos, true)
+                    });
+            }
+
             if gui.scrollbar.in_hold {
                 gui.scrollbar.move_scrollbar(systems, screen_pos.y, false);
-                gui.update_scroll(systems, gui.scrollbar.cur_value);
+                let names = gui.get_tab_names(systems, mapview);
+                gui.update_scroll(systems, &names, gui.scrollbar.cur_value);
                 gui.scrollbar.set_hover(systems, screen_pos);
-            } else if gui.current_tab == TAB_PROPERTIES
-                && gui.selected_dropbox >= 0
+            } else if matches!(
+                gui.current_tab,
+                TAB_PROPERTIES | TAB_ATTRIBUTE | TAB_ZONE
+            ) && gui.selected_dropbox >= 0
                 && gui.editor_selectionbox[gui.selected_dropbox as usize]
                     .scrollbar
                     .in_hold
@@ -206,7 +475,11 @@ pub fn interface_input(
             gui.hover_selectionbox(systems, screen_pos);
             gui.hover_tab_option(systems, screen_pos);
             gui.scrollbar.set_hover(systems, screen_pos);
-            if gui.current_tab == TAB_PROPERTIES && gui.selected_dropbox >= 0 {
+            if matches!(
+                gui.current_tab,
+                TAB_PROPERTIES | TAB_ATTRIBUTE | TAB_ZONE
+            ) && gui.selected_dropbox >= 0
+            {
                 gui.editor_selectionbox[gui.selected_dropbox as usize]
                     .hover_list(systems, screen_pos);
                 gui.editor_selectionbox[gui.selected_dropbox as usize]
@@ -219,12 +492,17 @@ pub fn interface_input(
             gui.release_click(systems);
             gui.release_selectionbox_click(systems);
             gui.scrollbar.release_scrollbar(systems);
-            if gui.current_tab == TAB_PROPERTIES && gui.selected_dropbox >= 0 {
+            if matches!(
+                gui.current_tab,
+                TAB_PROPERTIES | TAB_ATTRIBUTE | TAB_ZONE
+            ) && gui.selected_dropbox >= 0
+            {
                 gui.editor_selectionbox[gui.selected_dropbox as usize]
                     .scrollbar
                     .release_scrollbar(systems);
             }
         }
+        _ => {}
     }
 }
 
@@ -235,88 +513,100 @@ pub fn interface_key_input(
     database: &mut EditorData,
     systems: &mut DrawSetting,
 ) -> bool {
+    // Typing on an open dropdown list will search the list
+    if gui.selected_dropbox >= 0 {
+        if let Some(selection_box) = gui
+            .editor_selectionbox
+            .get_mut(gui.selected_dropbox as usize)
+        {
+            if selection_box.is_list_visible {
+                selection_box.enter_search(systems, event);
+                return true;
+            }
+        }
+    }
+
     let mut result = false;
     match gui.current_tab {
         TAB_ATTRIBUTE => {
-            let attribute =
-                MapAttribute::convert_to_plain_enum(gui.current_tab_data + 1);
-            match attribute {
-                MapAttribute::Warp(_) => {
-                    if gui.selected_textbox >= 0 {
-                        if gui.selected_textbox < 2 {
-                            gui.editor_textbox[gui.selected_textbox as usize]
-                                .enter_numeric(systems, event, 5, true);
-                        } else {
-                            gui.editor_textbox[gui.selected_textbox as usize]
-                                .enter_numeric(systems, event, 5, false);
-                        }
-                        result = true;
-                    }
-                }
-                MapAttribute::Sign(_) => {
-                    if gui.selected_textbox >= 0 {
-                        gui.editor_textbox[gui.selected_textbox as usize]
-                            .enter_text(systems, event, 100);
-                        result = true;
-                    }
-                }
-                MapAttribute::ItemSpawn(_) => {
-                    if gui.selected_textbox >= 0 {
-                        gui.editor_textbox[gui.selected_textbox as usize]
-                            .enter_numeric(systems, event, 6, false);
-                        result = true;
-                    }
-                }
-                MapAttribute::Shop(_) => {
-                    if gui.selected_textbox >= 0 {
-                        gui.editor_textbox[gui.selected_textbox as usize]
-                            .enter_numeric(systems, event, 6, false);
-                        result = true;
-                    }
+            if let Some(text_editor) = gui
+                .editor_text_editor
+                .iter_mut()
+                .find(|text_editor| text_editor.is_selected)
+            {
+                text_editor.enter_text(systems, event);
+                update_sign_preview(systems, gui);
+                return true;
+            }
+
+            if gui.selected_textbox >= 0 {
+                let textbox_index = gui.selected_textbox as usize;
+                let kind = systems
+                    .attribute_schema
+                    .attributes
+                    .get(gui.current_tab_data as usize)
+                    .and_then(|attribute| {
+                        attribute
+                            .fields
+                            .iter()
+                            .zip(gui.attribute_fields.iter())
+                            .find(|(_, widget)| {
+                                **widget == FieldWidget::Textbox(textbox_index)
+                            })
+                            .map(|(field, _)| field.kind.clone())
+                    });
+                match kind {
+                    Some(FieldKind::Int) => gui.editor_textbox[textbox_index]
+                        .enter_numeric(systems, event, 9, true),
+                    Some(FieldKind::UInt) => gui.editor_textbox[textbox_index]
+                        .enter_numeric(systems, event, 9, false),
+                    Some(FieldKind::String) => gui.editor_textbox
+                        [textbox_index]
+                        .enter_text(systems, event, 100),
+                    _ => {}
                 }
-                _ => {}
+                result = true;
+            }
+        }
+        TAB_ZONE if gui.is_placement_open(mapview) => {
+            if let Some(text_editor) = gui
+                .editor_text_editor
+                .iter_mut()
+                .find(|text_editor| text_editor.is_selected)
+            {
+                text_editor.enter_text(systems, event);
+                update_entity_setting(systems, gui, mapview, database);
+                return true;
+            }
+
+            // Object ID
+            if gui.selected_textbox == 0 {
+                gui.editor_textbox[0].enter_numeric(systems, event, 9, false);
+                update_entity_setting(systems, gui, mapview, database);
+                result = true;
             }
         }
         TAB_ZONE => {
             if gui.selected_textbox >= 0 {
-                gui.editor_textbox[gui.selected_textbox as usize]
-                    .enter_numeric(systems, event, 5, false);
-                match gui.selected_textbox {
-                    0 => {
-                        let value = gui.editor_textbox
-                            [gui.selected_textbox as usize]
-                            .data
-                            .parse::<i64>()
-                            .unwrap_or_default();
-                        mapview.map_zone_setting
-                            [gui.current_tab_data as usize]
-                            .max_npc = value as u64
-                    } // Max NPC
-                    _ => {
-                        if !gui.editor_textbox[gui.selected_textbox as usize]
-                            .data
-                            .is_empty()
-                        {
-                            let value = gui.editor_textbox
-                                [gui.selected_textbox as usize]
-                                .data
-                                .parse::<i64>()
-                                .unwrap_or_default();
-                            mapview.map_zone_setting
-                                [gui.current_tab_data as usize]
-                                .npc_id
-                                [(gui.selected_textbox - 1) as usize] =
-                                Some(value as u64);
-                        } else {
-                            mapview.map_zone_setting
-                                [gui.current_tab_data as usize]
-                                .npc_id
-                                [(gui.selected_textbox - 1) as usize] = None;
-                        }
-                    } // Npc ID
+                let textbox_index = gui.selected_textbox as usize;
+                match textbox_index {
+                    0 => gui.editor_textbox[0].enter_text(systems, event, 30),
+                    1 => gui.editor_textbox[1]
+                        .enter_numeric(systems, event, 5, false),
+                    _ => gui.editor_textbox[textbox_index]
+                        .enter_numeric(systems, event, 9, false),
+                }
+                if let Some(setting) = gui.get_zone_setting(mapview) {
+                    if textbox_index == 0 {
+                        gui.update_zone_label(systems, &setting.name);
+                    }
+                    mapview.set_zone_setting(
+                        gui.current_tab_data as usize,
+                        setting,
+                    );
+                    database.set_map_change();
+                    update_map_name(systems, gui, database);
                 }
-                database.set_map_change(mapview);
-                update_map_name(systems, gui, database);
                 result = true;
             }
         }
@@ -325,6 +615,108 @@ pub fn interface_key_input(
     result
 }
 
+// Add Zone, Remove Zone, Add NPC, Remove NPC
+fn zone_button_select(
+    button_index: usize,
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    if gui.is_placement_open(mapview) {
+        placement_button_select(button_index, systems, gui, mapview, database);
+        return;
+    }
+
+    let zone_index = gui.current_tab_data as usize;
+    match button_index {
+        0 => {
+            gui.current_tab_data = mapview.add_zone() as u32;
+            gui.zone_npc_index = 0;
+            gui.open_zone_list(systems, mapview);
+        }
+        1 => {
+            if zone_index >= mapview.map_zone_setting.len() {
+                return;
+            }
+            mapview.remove_zone(systems, zone_index);
+            gui.zone_npc_index = 0;
+            gui.open_zone_list(systems, mapview);
+        }
+        2 | 3 => {
+            let mut setting = match mapview.map_zone_setting.get(zone_index) {
+                Some(setting) => setting.clone(),
+                None => return,
+            };
+            if button_index == 2 {
+                let npc_id = systems
+                    .game_data
+                    .npcs
+                    .first()
+                    .map(|entry| entry.id)
+                    .unwrap_or_default();
+                setting.npcs.push(ZoneNpc::new(npc_id));
+                gui.zone_npc_index = setting.npcs.len() - 1;
+            } else if gui.zone_npc_index < setting.npcs.len() {
+                setting.npcs.remove(gui.zone_npc_index);
+            } else {
+                return;
+            }
+            mapview.set_zone_setting(zone_index, setting);
+        }
+        _ => return,
+    }
+    mapview.update_map_zone(systems, gui.current_tab_data as usize);
+    gui.open_zone_settings(systems, mapview);
+    database.set_map_change();
+    update_map_name(systems, gui, database);
+}
+
+// New Placement, Delete Placement
+fn placement_button_select(
+    button_index: usize,
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    match button_index {
+        0 => gui.selected_entity = None,
+        1 => {
+            if let Some(tile_pos) = gui.selected_entity.take() {
+                mapview.set_entity(systems, tile_pos, None);
+                database.set_map_change();
+                update_map_name(systems, gui, database);
+            }
+        }
+        _ => return,
+    }
+    gui.open_zone_settings(systems, mapview);
+}
+
+// Apply the placement settings, the selected placement is updated with them
+fn update_entity_setting(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    let entity = gui.get_entity_setting();
+    // A different type needs another ID widget
+    let kind_changed = entity.kind != gui.entity_setting.kind;
+    gui.entity_setting = entity.clone();
+    if let Some(tile_pos) = gui.selected_entity {
+        if mapview.get_entity(tile_pos) != Some(&entity) {
+            mapview.set_entity(systems, tile_pos, Some(entity));
+            database.set_map_change();
+            update_map_name(systems, gui, database);
+        }
+    }
+    if kind_changed {
+        gui.open_zone_settings(systems, mapview);
+    }
+}
+
 // This function help us switch the map setting tab that the editor is using
 pub fn set_tab(
     systems: &mut DrawSetting,
@@ -343,6 +735,7 @@ pub fn set_tab(
         gui.scrollbar.hide(systems);
         gui.current_tab_data = 0;
         gui.current_selected_area = 0;
+        gameinput.warp_pick = None;
 
         // Switch selected tab
         gui.buttons[gui.current_tab].set_state(systems, ButtonState::Normal);
@@ -358,6 +751,10 @@ pub fn set_tab(
         mapview.map_zone.iter_mut().for_each(|zone| {
             systems.gfx.set_visible(*zone, false);
         });
+        mapview.set_entity_visible(systems, false);
+        mapview.clear_walk_overlay(systems);
+        gui.walk_mode = WalkMode::Off;
+        gui.walk_points.clear();
 
         mapview.map_attributes.iter_mut().for_each(|attribute| {
             systems.gfx.set_visible(attribute.text, false);
@@ -390,18 +787,20 @@ pub fn set_tab(
 
         gui.editor_selectionbox
             .iter_mut()
-            .for_each(|selection_box| {
-                selection_box.scrollbar.unload(systems);
-                systems.gfx.remove_gfx(selection_box.button);
-                systems.gfx.remove_gfx(selection_box.text);
-                systems.gfx.remove_gfx(selection_box.rect[0]);
-                systems.gfx.remove_gfx(selection_box.rect[1]);
-                selection_box.list_text.iter_mut().for_each(|list| {
-                    systems.gfx.remove_gfx(list.rect);
-                    systems.gfx.remove_gfx(list.text);
-                });
-            });
+            .for_each(|selection_box| selection_box.unload(systems));
         gui.editor_selectionbox = vec![];
+        gui.selected_dropbox = -1;
+
+        gui.editor_checkbox
+            .iter_mut()
+            .for_each(|checkbox| checkbox.unload(systems));
+        gui.editor_checkbox = vec![];
+        gui.editor_text_editor
+            .iter_mut()
+            .for_each(|text_editor| text_editor.unload(systems));
+        gui.editor_text_editor = vec![];
+        gui.attribute_fields = vec![];
+        gui.sign_preview = None;
 
         gui.editor_textbox.iter_mut().for_each(|textbox| {
             systems.gfx.remove_gfx(textbox.image);
@@ -443,22 +842,31 @@ pub fn set_tab(
                 gui.start_view = 0;
                 for index in 0..MAX_TAB_LABEL {
                     let sel_index = gui.start_view + index;
-                    if sel_index < MAX_ATTRIBUTE - 1 {
-                        gui.tab_labels[index].init(
-                            systems,
-                            MapAttribute::as_str(sel_index as u32 + 1),
-                            180.0,
-                        );
+                    if let Some(attribute) =
+                        systems.attribute_schema.attributes.get(sel_index)
+                    {
+                        let name = attribute.name.clone();
+                        gui.tab_labels[index].init(systems, &name, 180.0);
                     }
                 }
                 gui.tab_labels[0].set_select(systems, true);
+                open_attribute_settings(systems, gui, 0, vec![]);
 
+                let show_labels = mapview.camera.show_labels();
                 mapview.map_attributes.iter_mut().for_each(|attribute| {
-                    systems.gfx.set_visible(attribute.text, true);
+                    systems.gfx.set_visible(attribute.text, show_labels);
                     systems.gfx.set_visible(attribute.image, true);
                 });
 
-                reset_scrollbar(systems, &mut gui.scrollbar);
+                // The zone tab uses the same scrollbar for its zone list
+                gui.scrollbar.update_scroll_max_value(
+                    systems,
+                    systems
+                        .attribute_schema
+                        .attributes
+                        .len()
+                        .saturating_sub(MAX_TAB_LABEL),
+                );
                 systems.gfx.set_visible(gui.scrollbar_bg, true);
                 gui.scrollbar.show(systems);
 
@@ -481,16 +889,10 @@ pub fn set_tab(
                 );
             }
             TAB_ZONE => {
-                for index in 0..MAX_TAB_LABEL {
-                    if index < 5 {
-                        gui.tab_labels[index].init(
-                            systems,
-                            &format!("Zone {}", index + 1),
-                            194.0,
-                        );
-                    }
-                }
-                gui.tab_labels[0].set_select(systems, true);
+                gui.zone_npc_index = 0;
+                gui.selected_entity = None;
+                gui.open_zone_list(systems, mapview);
+                mapview.set_entity_visible(systems, true);
 
                 mapview.map_zone.iter_mut().for_each(|zone| {
                     systems.gfx.set_visible(*zone, true);
@@ -516,76 +918,7 @@ pub fn set_tab(
                     Vec2::new(1.0, 1.0),
                 );
 
-                let text_start_pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
-                for i in 0..7 {
-                    let mut ajdust_pos =
-                        Vec2::new(text_start_pos.x, text_start_pos.y);
-                    let msg = match i {
-                        1 => {
-                            ajdust_pos += Vec2::new(10.0, 338.0);
-                            "NPC ID".to_string()
-                        }
-                        2..=6 => {
-                            ajdust_pos +=
-                                Vec2::new(10.0, 312.0 - ((i - 2) * 23) as f32);
-                            format!("{}", i - 1)
-                        }
-                        _ => {
-                            ajdust_pos += Vec2::new(10.0, 368.0);
-                            "Max NPC".to_string()
-                        }
-                    };
-                    let mut text = create_basic_label(
-                        systems,
-                        Vec3::new(
-                            ajdust_pos.x,
-                            ajdust_pos.y,
-                            ORDER_ATTRIBUTE_LABEL,
-                        ),
-                        Vec2::new(100.0, 20.0),
-                        Color::rgba(180, 180, 180, 255),
-                    );
-                    text.set_text(
-                        &mut systems.renderer,
-                        &msg,
-                        Attrs::new(),
-                        Shaping::Advanced,
-                    );
-                    gui.editor_label.push(systems.gfx.add_text(text, 1));
-
-                    if i != 1 {
-                        let add_pos = match i {
-                            0 => 85.0,
-                            _ => 35.0,
-                        };
-                        let text_box = Textbox::new(
-                            systems,
-                            Vec3::new(
-                                ajdust_pos.x + add_pos,
-                                ajdust_pos.y,
-                                ORDER_ATTRIBUTE_TEXTBOX,
-                            ),
-                            Vec2::new(60.0, 22.0),
-                            false,
-                            [0, 1],
-                        );
-                        gui.editor_textbox.push(text_box);
-                    }
-                }
-                gui.editor_textbox[0].input_text(
-                    systems,
-                    mapview.map_zone_setting[0].max_npc.to_string(),
-                ); // Max Npc
-                for i in 0..5 {
-                    if mapview.map_zone_setting[0].npc_id[i].is_some() {
-                        gui.editor_textbox[i + 1].input_text(
-                            systems,
-                            mapview.map_zone_setting[0].npc_id[i]
-                                .unwrap()
-                                .to_string(),
-                        );
-                    }
-                }
+                gui.open_zone_settings(systems, mapview);
             }
             TAB_PROPERTIES => {
                 systems.gfx.set_visible(gui.tab_opt_bg[0], true);
@@ -630,6 +963,86 @@ pub fn set_tab(
                         8.0,
                         [0, 1],
                     ),
+                    Button::new(
+                        systems,
+                        systems.resource.option_button.allocation,
+                        "World View",
+                        Vec2::new(pos.x + 14.0, pos.y + 252.0),
+                        Vec2::new(172.0, 36.0),
+                        [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                        8.0,
+                        [0, 1],
+                    ),
+                    Button::new(
+                        systems,
+                        systems.resource.option_button.allocation,
+                        "History",
+                        Vec2::new(pos.x + 14.0, pos.y + 212.0),
+                        Vec2::new(172.0, 36.0),
+                        [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                        8.0,
+                        [0, 1],
+                    ),
+                    Button::new(
+                        systems,
+                        systems.resource.option_button.allocation,
+                        "Validate Maps",
+                        Vec2::new(pos.x + 14.0, pos.y + 172.0),
+                        Vec2::new(172.0, 36.0),
+                        [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                        8.0,
+                        [0, 1],
+                    ),
+                    Button::new(
+                        systems,
+                        systems.resource.preference_button.allocation,
+                        "Generate",
+                        Vec2::new(pos.x + 118.0, pos.y - 322.0),
+                        Vec2::new(80.0, 22.0),
+                        [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                        2.0,
+                        [0, 1],
+                    ),
+                    Button::new(
+                        systems,
+                        systems.resource.preference_button.allocation,
+                        "Analyze",
+                        Vec2::new(pos.x + 106.0, pos.y + 93.0),
+                        Vec2::new(80.0, 22.0),
+                        [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                        2.0,
+                        [0, 1],
+                    ),
+                    Button::new(
+                        systems,
+                        systems.resource.preference_button.allocation,
+                        "Seams",
+                        Vec2::new(pos.x + 106.0, pos.y + 8.0),
+                        Vec2::new(80.0, 22.0),
+                        [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                        2.0,
+                        [0, 1],
+                    ),
+                    Button::new(
+                        systems,
+                        systems.resource.preference_button.allocation,
+                        "Import Tiled",
+                        Vec2::new(pos.x + 14.0, pos.y + 36.0),
+                        Vec2::new(84.0, 22.0),
+                        [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                        2.0,
+                        [0, 1],
+                    ),
+                    Button::new(
+                        systems,
+                        systems.resource.preference_button.allocation,
+                        "Export Tiled",
+                        Vec2::new(pos.x + 106.0, pos.y + 36.0),
+                        Vec2::new(80.0, 22.0),
+                        [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                        2.0,
+                        [0, 1],
+                    ),
                 ];
 
                 let content_pos = Vec2::new(25.0, 295.0);
@@ -671,7 +1084,7 @@ pub fn set_tab(
                     0,
                 );
                 selectionbox
-                    .switch_list(systems, mapview.fixed_weather as usize);
+                    .set_selected(systems, mapview.fixed_weather as usize);
                 gui.editor_selectionbox.push(selectionbox);
 
                 let mut audio_list = systems.audio_list.audio.clone();
@@ -714,7 +1127,7 @@ pub fn set_tab(
                     if let Some(index) =
                         audio_list.iter().position(|name| *name == *data)
                     {
-                        selectionbox.switch_list(systems, index);
+                        selectionbox.set_selected(systems, index);
                     }
                 }
                 gui.editor_selectionbox.push(selectionbox);
@@ -776,296 +1189,455 @@ pub fn set_tab(
                     }
                     gui.editor_rect.push(systems.gfx.add_rect(bg_rect, 0));
                 }
-            }
-            _ => {}
-        }
-    }
-}
 
-pub fn open_attribute_settings(
-    systems: &mut DrawSetting,
-    gui: &mut Interface,
-    attribute: u32,
-    data: Vec<InsertTypes>,
-) {
-    let attr = MapAttribute::convert_to_plain_enum(attribute);
-    // We will make it default that no textbox is selected
-    gui.selected_textbox = -1;
-    gui.selected_dropbox = -1;
-
-    if !gui.editor_label.is_empty() {
-        gui.editor_label.iter().for_each(|label| {
-            systems.gfx.remove_gfx(*label);
-        });
-    }
-    gui.editor_label = vec![];
-    if !gui.editor_textbox.is_empty() {
-        gui.editor_textbox.iter_mut().for_each(|textbox| {
-            systems.gfx.remove_gfx(textbox.image);
-            systems.gfx.remove_gfx(textbox.text);
-        });
-    }
-    gui.editor_textbox = vec![];
-
-    match attr {
-        MapAttribute::Warp(_) => {
-            gui.editor_label = Vec::with_capacity(7);
-            for i in 0..7 {
-                let mut ajdust_pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
-                let msg = match i {
-                    1 => {
-                        ajdust_pos += Vec3::new(45.0, 340.0, 0.0);
-                        "X"
-                    }
-                    2 => {
-                        ajdust_pos += Vec3::new(45.0, 316.0, 0.0);
-                        "Y"
-                    }
-                    3 => {
-                        ajdust_pos += Vec3::new(10.0, 292.0, 0.0);
-                        "Group"
-                    }
-                    4 => {
-                        ajdust_pos += Vec3::new(10.0, 260.0, 0.0);
-                        "Tile Location"
-                    }
-                    5 => {
-                        ajdust_pos += Vec3::new(45.0, 232.0, 0.0);
-                        "X"
-                    }
-                    6 => {
-                        ajdust_pos += Vec3::new(45.0, 208.0, 0.0);
-                        "Y"
-                    }
-                    _ => {
-                        ajdust_pos += Vec3::new(10.0, 368.0, 0.0);
-                        "Map Location"
-                    }
-                };
                 let mut text = create_basic_label(
                     systems,
                     Vec3::new(
-                        ajdust_pos.x,
-                        ajdust_pos.y,
+                        content_pos.x + 105.0,
+                        content_pos.y - 164.0,
                         ORDER_ATTRIBUTE_LABEL,
                     ),
-                    Vec2::new(100.0, 20.0),
+                    Vec2::new(78.0, 20.0),
                     Color::rgba(180, 180, 180, 255),
                 );
                 text.set_text(
                     &mut systems.renderer,
-                    msg,
+                    "Mode",
                     Attrs::new(),
                     Shaping::Advanced,
                 );
                 gui.editor_label.push(systems.gfx.add_text(text, 1));
-            }
 
-            gui.editor_textbox = Vec::with_capacity(5);
-            for i in 0..5 {
-                let pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
-                let textbox_pos = match i {
-                    1 => Vec3::new(
-                        pos.x + 65.0,
-                        pos.y + 316.0,
-                        ORDER_ATTRIBUTE_TEXTBOX,
-                    ),
-                    2 => Vec3::new(
-                        pos.x + 65.0,
-                        pos.y + 292.0,
-                        ORDER_ATTRIBUTE_TEXTBOX,
-                    ),
-                    3 => Vec3::new(
-                        pos.x + 65.0,
-                        pos.y + 232.0,
-                        ORDER_ATTRIBUTE_TEXTBOX,
-                    ),
-                    4 => Vec3::new(
-                        pos.x + 65.0,
-                        pos.y + 208.0,
-                        ORDER_ATTRIBUTE_TEXTBOX,
-                    ),
-                    _ => Vec3::new(
-                        pos.x + 65.0,
-                        pos.y + 340.0,
-                        ORDER_ATTRIBUTE_TEXTBOX,
-                    ),
-                };
-                gui.editor_textbox.push(Textbox::new(
+                let mut selectionbox = SelectionBox::new(
                     systems,
-                    textbox_pos,
-                    Vec2::new(60.0, 22.0),
-                    false,
-                    [0, 1],
-                ));
-            }
-            // If data exist, place the data on textbox
-            if !data.is_empty() {
-                gui.editor_textbox[0]
-                    .input_text(systems, data[0].get_int().to_string());
-                gui.editor_textbox[1]
-                    .input_text(systems, data[1].get_int().to_string());
-                gui.editor_textbox[2]
-                    .input_text(systems, data[2].get_uint().to_string());
-                gui.editor_textbox[3]
-                    .input_text(systems, data[3].get_uint().to_string());
-                gui.editor_textbox[4]
-                    .input_text(systems, data[4].get_uint().to_string());
-            } else {
-                gui.editor_textbox[0].input_text(systems, "0".to_string());
-                gui.editor_textbox[1].input_text(systems, "0".to_string());
-                gui.editor_textbox[2].input_text(systems, "0".to_string());
-                gui.editor_textbox[3].input_text(systems, "0".to_string());
-                gui.editor_textbox[4].input_text(systems, "0".to_string());
-            }
-        }
-        MapAttribute::Sign(_) => {
-            let pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
-            let text = create_basic_label(
-                systems,
-                Vec3::new(pos.x + 10.0, pos.y + 368.0, ORDER_ATTRIBUTE_LABEL),
-                Vec2::new(100.0, 20.0),
-                Color::rgba(180, 180, 180, 255),
-            );
-            gui.editor_label = vec![systems.gfx.add_text(text, 1)];
-            systems.gfx.set_text(
-                &mut systems.renderer,
-                gui.editor_label[0],
-                "Sign Text",
-            );
-            gui.editor_textbox = vec![Textbox::new(
-                systems,
-                Vec3::new(pos.x + 10.0, pos.y + 115.0, ORDER_ATTRIBUTE_TEXTBOX),
-                Vec2::new(180.0, 250.0),
-                true,
-                [0, 1],
-            )];
-            // If data exist, place the data on textbox
-            if !data.is_empty() {
-                gui.editor_textbox[0].input_text(systems, data[0].get_string());
-            } else {
-                gui.editor_textbox[0].input_text(systems, String::new());
-            }
-        }
-        MapAttribute::ItemSpawn(_) => {
-            gui.editor_label = Vec::with_capacity(3);
-            for i in 0..3 {
-                let mut ajdust_pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
-                let msg = match i {
-                    1 => {
-                        ajdust_pos += Vec3::new(10.0, 340.0, 0.0);
-                        "Value"
-                    }
-                    2 => {
-                        ajdust_pos += Vec3::new(10.0, 312.0, 0.0);
-                        "Timer"
-                    }
-                    _ => {
-                        ajdust_pos += Vec3::new(10.0, 368.0, 0.0);
-                        "Index"
-                    }
-                };
+                    Vec2::new(content_pos.x + 105.0, content_pos.y - 190.0),
+                    [
+                        ORDER_PROPERTIES_BUTTON,
+                        ORDER_PROPERTIES_BUTTON_TEXT,
+                        ORDER_DROPDOWN_WINDOW,
+                        ORDER_DROPDOWN_SELECTION,
+                        ORDER_DROPDOWN_TEXT,
+                        ORDER_DROPDOWN_SCROLLBAR,
+                    ],
+                    78.0,
+                    DirBlockMode::MODES
+                        .iter()
+                        .map(|mode| mode.as_str().to_string())
+                        .collect(),
+                    0,
+                );
+                let mode_index = DirBlockMode::MODES
+                    .iter()
+                    .position(|mode| *mode == gui.dir_block_mode)
+                    .unwrap_or_default();
+                selectionbox.set_selected(systems, mode_index);
+                gui.editor_selectionbox.push(selectionbox);
+
                 let mut text = create_basic_label(
                     systems,
                     Vec3::new(
-                        ajdust_pos.x,
-                        ajdust_pos.y,
+                        pos.x + 14.0,
+                        pos.y + 146.0,
                         ORDER_ATTRIBUTE_LABEL,
                     ),
-                    Vec2::new(100.0, 20.0),
+                    Vec2::new(172.0, 20.0),
                     Color::rgba(180, 180, 180, 255),
                 );
                 text.set_text(
                     &mut systems.renderer,
-                    msg,
+                    "Walkability",
                     Attrs::new(),
                     Shaping::Advanced,
                 );
                 gui.editor_label.push(systems.gfx.add_text(text, 1));
-            }
 
-            gui.editor_textbox = Vec::with_capacity(3);
-            for i in 0..3 {
-                let pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
-                let textbox_pos = match i {
-                    1 => Vec3::new(
-                        pos.x + 65.0,
-                        pos.y + 340.0,
-                        ORDER_ATTRIBUTE_TEXTBOX,
+                let selectionbox = SelectionBox::new(
+                    systems,
+                    Vec2::new(pos.x + 14.0, pos.y + 122.0),
+                    [
+                        ORDER_PROPERTIES_BUTTON,
+                        ORDER_PROPERTIES_BUTTON_TEXT,
+                        ORDER_DROPDOWN_WINDOW,
+                        ORDER_DROPDOWN_SELECTION,
+                        ORDER_DROPDOWN_TEXT,
+                        ORDER_DROPDOWN_SCROLLBAR,
+                    ],
+                    172.0,
+                    WalkMode::MODES
+                        .iter()
+                        .map(|mode| mode.as_str().to_string())
+                        .collect(),
+                    0,
+                );
+                gui.editor_selectionbox.push(selectionbox);
+
+                let mut selectionbox = SelectionBox::new(
+                    systems,
+                    Vec2::new(pos.x + 14.0, pos.y + 92.0),
+                    [
+                        ORDER_PROPERTIES_BUTTON,
+                        ORDER_PROPERTIES_BUTTON_TEXT,
+                        ORDER_DROPDOWN_WINDOW,
+                        ORDER_DROPDOWN_SELECTION,
+                        ORDER_DROPDOWN_TEXT,
+                        ORDER_DROPDOWN_SCROLLBAR,
+                    ],
+                    84.0,
+                    WalkRule::RULES
+                        .iter()
+                        .map(|rule| rule.as_str().to_string())
+                        .collect(),
+                    0,
+                );
+                let rule_index = WalkRule::RULES
+                    .iter()
+                    .position(|rule| *rule == gui.walk_rule)
+                    .unwrap_or_default();
+                selectionbox.set_selected(systems, rule_index);
+                gui.editor_selectionbox.push(selectionbox);
+
+                // Result of the walkability analysis
+                let text = create_basic_label(
+                    systems,
+                    Vec3::new(
+                        pos.x + 14.0,
+                        pos.y + 62.0,
+                        ORDER_ATTRIBUTE_LABEL,
                     ),
-                    2 => Vec3::new(
-                        pos.x + 65.0,
-                        pos.y + 312.0,
+                    Vec2::new(172.0, 20.0),
+                    Color::rgba(180, 180, 180, 255),
+                );
+                gui.editor_label.push(systems.gfx.add_text(text, 1));
+
+                let checkbox = Checkbox::new(
+                    systems,
+                    Vec2::new(pos.x + 14.0, pos.y + 9.0),
+                    "Tiles",
+                    Vec2::new(84.0, 20.0),
+                    [
                         ORDER_ATTRIBUTE_TEXTBOX,
-                    ),
-                    _ => Vec3::new(
-                        pos.x + 65.0,
-                        pos.y + 368.0,
                         ORDER_ATTRIBUTE_TEXTBOX,
-                    ),
-                };
-                gui.editor_textbox.push(Textbox::new(
+                        ORDER_ATTRIBUTE_LABEL,
+                    ],
+                    gui.seam_check_tiles,
+                    [0, 1],
+                );
+                checkbox.window.iter().for_each(|window| {
+                    systems.gfx.set_visible(*window, true);
+                });
+                systems.gfx.set_visible(checkbox.text, true);
+                gui.editor_checkbox.push(checkbox);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Generate the settings of the attribute type based on its schema fields
+pub fn open_attribute_settings(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    attribute_index: usize,
+    data: Vec<InsertTypes>,
+) {
+    // We will make it default that no textbox is selected
+    gui.selected_textbox = -1;
+    gui.selected_dropbox = -1;
+
+    gui.editor_label.iter().for_each(|label| {
+        systems.gfx.remove_gfx(*label);
+    });
+    gui.editor_label = vec![];
+    gui.editor_textbox.iter_mut().for_each(|textbox| {
+        systems.gfx.remove_gfx(textbox.image);
+        systems.gfx.remove_gfx(textbox.text);
+    });
+    gui.editor_textbox = vec![];
+    gui.editor_checkbox
+        .iter_mut()
+        .for_each(|checkbox| checkbox.unload(systems));
+    gui.editor_checkbox = vec![];
+    gui.editor_selectionbox
+        .iter_mut()
+        .for_each(|selection_box| selection_box.unload(systems));
+    gui.editor_selectionbox = vec![];
+    gui.editor_button.iter_mut().for_each(|button| {
+        systems.gfx.remove_gfx(button.image);
+        systems.gfx.remove_gfx(button.text);
+    });
+    gui.editor_button = vec![];
+    gui.editor_text_editor
+        .iter_mut()
+        .for_each(|text_editor| text_editor.unload(systems));
+    gui.editor_text_editor = vec![];
+    gui.attribute_fields = vec![];
+    gui.sign_preview = None;
+
+    let attribute =
+        match systems.attribute_schema.attributes.get(attribute_index) {
+            Some(attribute) => attribute.clone(),
+            None => return,
+        };
+
+    let pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
+    let mut y = pos.y + 368.0;
+    for (index, field) in attribute.fields.iter().enumerate() {
+        let value = data.get(index).cloned().unwrap_or_else(|| {
+            systems.attribute_schema.default_value(&field.kind)
+        });
+
+        // Checkbox holds its own label
+        if field.kind == FieldKind::Bool {
+            let checkbox = Checkbox::new(
+                systems,
+                Vec2::new(pos.x + 10.0, y),
+                &field.name,
+                Vec2::new(180.0, 20.0),
+                [
+                    ORDER_ATTRIBUTE_TEXTBOX,
+                    ORDER_ATTRIBUTE_TEXTBOX,
+                    ORDER_ATTRIBUTE_LABEL,
+                ],
+                value.get_bool(),
+                [0, 1],
+            );
+            checkbox.window.iter().for_each(|window| {
+                systems.gfx.set_visible(*window, true);
+            });
+            systems.gfx.set_visible(checkbox.text, true);
+            gui.attribute_fields
+                .push(FieldWidget::Checkbox(gui.editor_checkbox.len()));
+            gui.editor_checkbox.push(checkbox);
+            y -= 26.0;
+            continue;
+        }
+
+        let mut text = create_basic_label(
+            systems,
+            Vec3::new(pos.x + 10.0, y, ORDER_ATTRIBUTE_LABEL),
+            Vec2::new(90.0, 20.0),
+            Color::rgba(180, 180, 180, 255),
+        );
+        text.set_text(
+            &mut systems.renderer,
+            &field.name,
+            Attrs::new(),
+            Shaping::Advanced,
+        );
+        gui.editor_label.push(systems.gfx.add_text(text, 1));
+
+        match &field.kind {
+            FieldKind::Int | FieldKind::UInt => {
+                let mut textbox = Textbox::new(
                     systems,
-                    textbox_pos,
-                    Vec2::new(60.0, 22.0),
+                    Vec3::new(pos.x + 100.0, y, ORDER_ATTRIBUTE_TEXTBOX),
+                    Vec2::new(90.0, 22.0),
                     false,
                     [0, 1],
+                );
+                let text = match value {
+                    InsertTypes::Int(data) => data.to_string(),
+                    _ => value.get_uint().to_string(),
+                };
+                textbox.input_text(systems, text);
+                gui.attribute_fields
+                    .push(FieldWidget::Textbox(gui.editor_textbox.len()));
+                gui.editor_textbox.push(textbox);
+                y -= 28.0;
+            }
+            FieldKind::String => {
+                let lines = field.lines.max(1);
+                let height = (lines * 20 + 2) as f32;
+                y -= height + 2.0;
+                if lines > 1 {
+                    let mut text_editor = TextEditor::new(
+                        systems,
+                        Vec3::new(pos.x + 10.0, y, ORDER_ATTRIBUTE_TEXTBOX),
+                        Vec2::new(180.0, height),
+                        1000,
+                        [0, 1],
+                    );
+                    text_editor.input_text(systems, value.get_string());
+                    gui.attribute_fields.push(FieldWidget::TextEditor(
+                        gui.editor_text_editor.len(),
+                    ));
+                    gui.editor_text_editor.push(text_editor);
+                } else {
+                    let mut textbox = Textbox::new(
+                        systems,
+                        Vec3::new(pos.x + 10.0, y, ORDER_ATTRIBUTE_TEXTBOX),
+                        Vec2::new(180.0, height),
+                        false,
+                        [0, 1],
+                    );
+                    textbox.input_text(systems, value.get_string());
+                    gui.attribute_fields
+                        .push(FieldWidget::Textbox(gui.editor_textbox.len()));
+                    gui.editor_textbox.push(textbox);
+                }
+                y -= 28.0;
+            }
+            FieldKind::Enum(name) => {
+                y -= 26.0;
+                let list = systems.attribute_schema.get_enum(name);
+                let mut selectionbox = SelectionBox::new(
+                    systems,
+                    Vec2::new(pos.x + 10.0, y),
+                    [
+                        ORDER_PROPERTIES_BUTTON,
+                        ORDER_PROPERTIES_BUTTON_TEXT,
+                        ORDER_DROPDOWN_WINDOW,
+                        ORDER_DROPDOWN_SELECTION,
+                        ORDER_DROPDOWN_TEXT,
+                        ORDER_DROPDOWN_SCROLLBAR,
+                    ],
+                    180.0,
+                    list.clone(),
+                    0,
+                );
+                if let Some(index) =
+                    list.iter().position(|name| *name == value.get_string())
+                {
+                    selectionbox.set_selected(systems, index);
+                }
+                gui.attribute_fields.push(FieldWidget::SelectionBox(
+                    gui.editor_selectionbox.len(),
                 ));
+                gui.editor_selectionbox.push(selectionbox);
+                y -= 30.0;
             }
-            // If data exist, place the data on textbox
-            if !data.is_empty() {
-                gui.editor_textbox[0]
-                    .input_text(systems, data[0].get_uint().to_string());
-                gui.editor_textbox[1]
-                    .input_text(systems, data[1].get_uint().to_string());
-                gui.editor_textbox[2]
-                    .input_text(systems, data[2].get_uint().to_string());
-            } else {
-                gui.editor_textbox[0].input_text(systems, "0".to_string());
-                gui.editor_textbox[1].input_text(systems, "0".to_string());
-                gui.editor_textbox[2].input_text(systems, "0".to_string());
+            FieldKind::GameData(kind) => {
+                y -= 26.0;
+                let selectionbox = create_game_data_box(
+                    systems,
+                    Vec2::new(pos.x + 10.0, y),
+                    180.0,
+                    *kind,
+                    Some(value.get_uint()),
+                    false,
+                );
+                gui.attribute_fields.push(FieldWidget::SelectionBox(
+                    gui.editor_selectionbox.len(),
+                ));
+                gui.editor_selectionbox.push(selectionbox);
+                y -= 30.0;
             }
+            FieldKind::Bool => {}
         }
-        MapAttribute::Shop(_) => {
-            gui.editor_label = Vec::with_capacity(1);
-            let mut ajdust_pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
-            ajdust_pos += Vec3::new(10.0, 368.0, 0.0);
-            let mut text = create_basic_label(
-                systems,
-                Vec3::new(ajdust_pos.x, ajdust_pos.y, ORDER_ATTRIBUTE_LABEL),
-                Vec2::new(100.0, 20.0),
-                Color::rgba(180, 180, 180, 255),
-            );
-            text.set_text(
-                &mut systems.renderer,
-                "Index",
-                Attrs::new(),
-                Shaping::Advanced,
-            );
-            gui.editor_label.push(systems.gfx.add_text(text, 1));
+    }
 
-            gui.editor_textbox = Vec::with_capacity(1);
-            let pos = systems.gfx.get_pos(gui.tab_opt_bg[0]);
-            let textbox_pos =
-                Vec3::new(pos.x + 65.0, pos.y + 368.0, ORDER_ATTRIBUTE_TEXTBOX);
-            gui.editor_textbox.push(Textbox::new(
+    // Sign that uses a localization key will show the text of the key
+    if attribute.name == "Sign" {
+        let mut text = create_basic_label(
+            systems,
+            Vec3::new(pos.x + 10.0, pos.y + 4.0, ORDER_ATTRIBUTE_LABEL),
+            Vec2::new(180.0, (y - pos.y - 4.0).max(20.0)),
+            Color::rgba(180, 180, 180, 255),
+        );
+        text.set_buffer_size(
+            &mut systems.renderer,
+            180,
+            systems.size.height as i32,
+        );
+        text.set_wrap(&mut systems.renderer, cosmic_text::Wrap::Word);
+        let preview = systems.gfx.add_text(text, 1);
+        gui.editor_label.push(preview);
+        gui.sign_preview = Some(preview);
+        update_sign_preview(systems, gui);
+    }
+
+    // Warp destination can be picked on the map instead of typing it
+    if attribute.name == "Warp" {
+        let checkbox = Checkbox::new(
+            systems,
+            Vec2::new(pos.x + 10.0, y),
+            "Create Return Warp",
+            Vec2::new(180.0, 20.0),
+            [
+                ORDER_ATTRIBUTE_TEXTBOX,
+                ORDER_ATTRIBUTE_TEXTBOX,
+                ORDER_ATTRIBUTE_LABEL,
+            ],
+            gui.return_warp,
+            [0, 1],
+        );
+        checkbox.window.iter().for_each(|window| {
+            systems.gfx.set_visible(*window, true);
+        });
+        systems.gfx.set_visible(checkbox.text, true);
+        gui.editor_checkbox.push(checkbox);
+
+        gui.editor_button = vec![
+            Button::new(
                 systems,
-                textbox_pos,
-                Vec2::new(60.0, 22.0),
-                false,
+                systems.resource.option_button.allocation,
+                "Pick Destination",
+                Vec2::new(pos.x + 14.0, y - 42.0),
+                Vec2::new(172.0, 36.0),
+                [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                8.0,
                 [0, 1],
-            ));
+            ),
+            Button::new(
+                systems,
+                systems.resource.option_button.allocation,
+                "Jump to Destination",
+                Vec2::new(pos.x + 14.0, y - 82.0),
+                Vec2::new(172.0, 36.0),
+                [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                8.0,
+                [0, 1],
+            ),
+        ];
+    }
+}
 
-            // If data exist, place the data on textbox
-            if !data.is_empty() {
-                gui.editor_textbox[0]
-                    .input_text(systems, data[0].get_uint().to_string());
-            } else {
-                gui.editor_textbox[0].input_text(systems, "0".to_string());
+// Show the text of the string table that the sign key is pointing to
+fn update_sign_preview(systems: &mut DrawSetting, gui: &mut Interface) {
+    let preview = match gui.sign_preview {
+        Some(preview) => preview,
+        None => return,
+    };
+    let (msg, color) = match gui.get_attribute_setting(systems) {
+        MapAttribute::LocalizedSign(key) => {
+            match systems.game_data.get_string(&key) {
+                Some(text) => (
+                    format!("Preview: {}", text),
+                    Color::rgba(180, 180, 180, 255),
+                ),
+                None => (
+                    format!("Missing key: {}", key),
+                    Color::rgba(220, 80, 80, 255),
+                ),
             }
         }
-        _ => {}
-    }
+        _ => (String::new(), Color::rgba(180, 180, 180, 255)),
+    };
+    systems.gfx.set_color(preview, color);
+    systems.gfx.set_text(&mut systems.renderer, preview, &msg);
+}
+
+// Switch the map view to the destination map of the warp
+fn open_warp_destination(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+    data: &WarpData,
+) {
+    switch_map(
+        systems,
+        gui,
+        mapview,
+        database,
+        data.map_x,
+        data.map_y,
+        data.map_group,
+    );
+    mapview.hover_selection_preview(
+        systems,
+        Vec2::new(data.tile_x as f32, data.tile_y as f32),
+    );
 }
 
 fn click_dir_block(
@@ -1077,6 +1649,7 @@ fn click_dir_block(
         return;
     }
 
+    let mut dir_select = gui.dir_select;
     for (index, rect) in gui.editor_rect.iter().enumerate() {
         let pos = systems.gfx.get_pos(*rect);
         if screen_pos.x >= pos.x
@@ -1084,14 +1657,128 @@ fn click_dir_block(
             && screen_pos.y >= pos.y
             && screen_pos.y <= pos.y + 32.0
         {
-            gui.dir_select[index] = !gui.dir_select[index];
-            if gui.dir_select[index] {
-                systems.gfx.set_color(*rect, Color::rgba(220, 50, 50, 255));
+            dir_select[index] = !dir_select[index];
+        }
+    }
+    set_dir_select(systems, gui, dir_select);
+}
+
+pub fn set_dir_select(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    dir_select: [bool; 4],
+) {
+    gui.dir_select = dir_select;
+    for (rect, is_select) in gui.editor_rect.iter().zip(dir_select) {
+        if is_select {
+            systems.gfx.set_color(*rect, Color::rgba(220, 50, 50, 255));
+        } else {
+            systems
+                .gfx
+                .set_color(*rect, Color::rgba(100, 100, 100, 255));
+        }
+    }
+}
+
+// Label that shows the result of the walkability analysis
+const LABEL_WALK_STATUS: usize = 5;
+
+// Run the analysis of the selected walkability mode, only the tiles of the
+// current map are highlighted while the result covers the whole group
+pub fn update_walk_analysis(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    if gui.current_tab != TAB_PROPERTIES {
+        return;
+    }
+    if gui.walk_mode == WalkMode::Off {
+        mapview.clear_walk_overlay(systems);
+        set_walk_status(systems, gui, "");
+        return;
+    }
+
+    if database.did_change(database.x, database.y, database.group) {
+        database.save_map_data(mapview, Some(database.current_index.clone()));
+    }
+    let grid = WalkGrid::new(&database.maps, database.group, gui.walk_rule);
+    let current = (database.x, database.y);
+    let on_current_map = |tile: &&WalkTile| (tile.map_x, tile.map_y) == current;
+
+    let mut tiles = Vec::new();
+    let status = match gui.walk_mode {
+        WalkMode::Path => match gui.walk_points[..] {
+            [start, end] => {
+                let path = grid.find_path(start, end);
+                let list = path.clone().unwrap_or_else(|| vec![start, end]);
+                tiles.extend(list.iter().filter(on_current_map).map(|tile| {
+                    (tile.tile_num(), Color::rgba(60, 140, 240, 150))
+                }));
+                match path {
+                    Some(path) => format!("Path of {} steps", path.len() - 1),
+                    None => "No path found".to_string(),
+                }
+            }
+            [_] => "Click on the end tile".to_string(),
+            _ => "Click on the start tile".to_string(),
+        },
+        _ => {
+            let seeds = if gui.walk_mode == WalkMode::FromWarps {
+                grid.warp_entries()
             } else {
-                systems
-                    .gfx
-                    .set_color(*rect, Color::rgba(100, 100, 100, 255));
+                gui.walk_points.clone()
+            };
+            if seeds.is_empty() {
+                if gui.walk_mode == WalkMode::FromWarps {
+                    "No warp leads to this group".to_string()
+                } else {
+                    "Click on a walkable tile".to_string()
+                }
+            } else {
+                let unreachable = grid.find_unreachable(&seeds);
+                if let Some(list) = unreachable.get(&current) {
+                    tiles.extend(list.iter().map(|tile_num| {
+                        (*tile_num, Color::rgba(220, 40, 40, 120))
+                    }));
+                }
+                tiles.extend(seeds.iter().filter(on_current_map).map(|tile| {
+                    (tile.tile_num(), Color::rgba(40, 200, 80, 160))
+                }));
+                let total: usize = unreachable.values().map(Vec::len).sum();
+                format!("{} unreachable on {} map(s)", total, unreachable.len())
             }
         }
+    };
+    mapview.set_walk_overlay(systems, &tiles);
+    set_walk_status(systems, gui, &status);
+}
+
+fn set_walk_status(systems: &mut DrawSetting, gui: &Interface, msg: &str) {
+    if let Some(label) = gui.editor_label.get(LABEL_WALK_STATUS) {
+        systems.gfx.set_text(&mut systems.renderer, *label, msg);
+    }
+}
+
+// The export starts on the current map with a single map selected
+fn open_tiled_export(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    database: &EditorData,
+) {
+    gui.open_dialog(systems, DialogType::TiledExport, None);
+    if let Some(dialog) = &mut gui.dialog {
+        let values = [
+            database.x.to_string(),
+            database.y.to_string(),
+            database.group.to_string(),
+            format!("{}_{}_{}.json", database.x, database.y, database.group),
+            "1".to_string(),
+            "1".to_string(),
+        ];
+        for (textbox, value) in dialog.editor_textbox.iter_mut().zip(values) {
+            textbox.input_text(systems, value);
+        }
     }
 }
@@ -1,5 +1,21 @@
 use crate::editor_input::*;
 
+// Mark the maps that had their tiles changed, an edit on the linked map
+// strips does not change the current map
+pub fn update_map_change(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    if mapview.map_changed {
+        mapview.map_changed = false;
+        database.set_map_change();
+    }
+    database.save_link_maps(mapview);
+    update_map_name(systems, gui, database);
+}
+
 fn interact_with_map(
     systems: &mut DrawSetting,
     tile_pos: Vec2,
@@ -20,8 +36,7 @@ fn interact_with_map(
                         tileset.select_start,
                         tileset.select_size,
                     );
-                    database.set_map_change(mapview);
-                    update_map_name(systems, gui, database);
+                    update_map_change(systems, gui, mapview, database);
                 }
                 TOOL_ERASE => {
                     mapview.delete_tile_group(
@@ -29,8 +44,7 @@ fn interact_with_map(
                         gui.get_tab_option_data(),
                         tileset.select_size,
                     );
-                    database.set_map_change(mapview);
-                    update_map_name(systems, gui, database);
+                    update_map_change(systems, gui, mapview, database);
                 }
                 TOOL_FILL => {
                     mapview.set_tile_fill(
@@ -39,8 +53,7 @@ fn interact_with_map(
                         &tileset.map,
                         tileset.select_start,
                     );
-                    database.set_map_change(mapview);
-                    update_map_name(systems, gui, database);
+                    update_map_change(systems, gui, mapview, database);
                 }
                 TOOL_EYEDROP => {
                     let tiledata = mapview.get_tile_data(tile_pos);
@@ -100,104 +113,118 @@ fn interact_with_map(
         }
         TAB_ATTRIBUTE => match gui.current_tool {
             TOOL_DRAW => {
-                let attribute = gui.get_attribute_setting();
+                let attribute = gui.get_attribute_setting(systems);
+                if let MapAttribute::Warp(data) = &attribute {
+                    if gui.return_warp {
+                        set_return_warp(
+                            systems, tile_pos, data, mapview, database,
+                        );
+                    }
+                }
                 mapview.set_attribute(systems, tile_pos, attribute);
-                database.set_map_change(mapview);
+                database.set_map_change();
                 update_map_name(systems, gui, database);
             }
             TOOL_ERASE => {
-                mapview.set_attribute(
-                    systems,
-                    tile_pos,
-                    MapAttribute::Walkable,
-                );
-                database.set_map_change(mapview);
+                // Only the selected attribute type is erased from the tile
+                let name =
+                    gui.get_attribute_setting(systems).name().to_string();
+                mapview.remove_attribute(systems, tile_pos, &name);
+                database.set_map_change();
                 update_map_name(systems, gui, database);
             }
             TOOL_EYEDROP => {
-                let attribute = mapview.get_attribute(tile_pos);
-                if attribute != MapAttribute::Walkable {
-                    let attribute_index =
-                        MapAttribute::convert_to_num(&attribute);
-                    let data = match attribute {
-                        MapAttribute::Warp(warpdata) => {
-                            vec![
-                                InsertTypes::Int(warpdata.map_x as i64),
-                                InsertTypes::Int(warpdata.map_y as i64),
-                                InsertTypes::UInt(warpdata.map_group),
-                                InsertTypes::UInt(warpdata.tile_x as u64),
-                                InsertTypes::UInt(warpdata.tile_y as u64),
-                            ]
-                        }
-                        MapAttribute::Sign(text) => {
-                            vec![InsertTypes::Str(text)]
-                        }
-                        MapAttribute::ItemSpawn(itemdata) => {
-                            vec![
-                                InsertTypes::UInt(itemdata.index as u64),
-                                InsertTypes::UInt(itemdata.amount as u64),
-                                InsertTypes::UInt(itemdata.timer),
-                            ]
-                        }
-                        MapAttribute::Shop(index) => {
-                            vec![InsertTypes::UInt(index as u64)]
-                        }
-                        _ => vec![],
-                    };
-                    gui.select_tab_option(
-                        systems,
-                        attribute_index as usize - 1,
-                    );
-                    open_attribute_settings(
-                        systems,
-                        gui,
-                        attribute_index,
-                        data,
-                    );
+                // Pick the selected attribute type if the tile has it,
+                // otherwise the attribute that was placed last
+                let name =
+                    gui.get_attribute_setting(systems).name().to_string();
+                let attributes = mapview.get_attributes(tile_pos);
+                if let Some(attribute) = attributes
+                    .iter()
+                    .find(|attribute| attribute.name() == name)
+                    .or(attributes.last())
+                {
+                    select_attribute_setting(systems, gui, mapview, attribute);
                 }
             }
             TOOL_FILL => {
-                let attribute = gui.get_attribute_setting();
+                let attribute = gui.get_attribute_setting(systems);
                 mapview.set_attribute_fill(systems, tile_pos, attribute);
-                database.set_map_change(mapview);
+                database.set_map_change();
                 update_map_name(systems, gui, database);
             }
             _ => {}
         },
-        TAB_ZONE => match gui.current_tool {
-            TOOL_DRAW => {
-                mapview.add_map_zone(
-                    systems,
-                    gui.current_tab_data as usize,
-                    tile_pos,
-                );
-                database.set_map_change(mapview);
-                update_map_name(systems, gui, database);
-            }
-            TOOL_ERASE => {
-                mapview.delete_map_zone(
-                    systems,
-                    gui.current_tab_data as usize,
-                    tile_pos,
-                );
-                database.set_map_change(mapview);
-                update_map_name(systems, gui, database);
+        // Zones can only be drawn once one has been added to the map
+        TAB_ZONE
+            if (gui.current_tab_data as usize) < mapview.map_zone_loc.len() =>
+        {
+            match gui.current_tool {
+                TOOL_DRAW => {
+                    mapview.add_map_zone(
+                        systems,
+                        gui.current_tab_data as usize,
+                        tile_pos,
+                    );
+                    database.set_map_change();
+                    update_map_name(systems, gui, database);
+                }
+                TOOL_ERASE => {
+                    mapview.delete_map_zone(
+                        systems,
+                        gui.current_tab_data as usize,
+                        tile_pos,
+                    );
+                    database.set_map_change();
+                    update_map_name(systems, gui, database);
+                }
+                TOOL_FILL => {
+                    mapview.set_zone_fill(
+                        systems,
+                        tile_pos,
+                        gui.current_tab_data as usize,
+                    );
+                    database.set_map_change();
+                    update_map_name(systems, gui, database);
+                }
+                _ => {}
             }
-            TOOL_FILL => {
-                mapview.set_zone_fill(
-                    systems,
-                    tile_pos,
-                    gui.current_tab_data as usize,
-                );
-                database.set_map_change(mapview);
-                update_map_name(systems, gui, database);
+        }
+        TAB_ZONE if gui.is_placement_open(mapview) => {
+            // The press type is only set to map once the click has been done
+            let is_drag = gameinput.presstype == PressType::Map;
+            interact_with_placement(
+                systems, tile_pos, gui, mapview, database, is_drag,
+            );
+        }
+        // Clicks are used to pick the tiles of the walkability analysis
+        TAB_PROPERTIES if gui.walk_mode != WalkMode::Off => {
+            if gameinput.presstype != PressType::Map {
+                pick_walk_point(systems, tile_pos, gui, mapview, database);
             }
-            _ => {}
-        },
+        }
         TAB_PROPERTIES => match gui.current_tool {
+            // The rectangle is drawn once the mouse is released
+            TOOL_DRAW | TOOL_ERASE
+                if gui.dir_block_mode != DirBlockMode::Tile =>
+            {
+                if tile_pos.x < 0.0
+                    || tile_pos.x >= 32.0
+                    || tile_pos.y < 0.0
+                    || tile_pos.y >= 32.0
+                {
+                    return;
+                }
+                let start = match gameinput.dir_block_rect {
+                    Some((start, _)) => start,
+                    None => tile_pos,
+                };
+                gameinput.dir_block_rect = Some((start, tile_pos));
+                mapview.set_selection_rect(systems, start, tile_pos);
+            }
             TOOL_DRAW => {
                 mapview.set_dir_block(systems, tile_pos, gui.dir_select);
-                database.set_map_change(mapview);
+                database.set_map_change();
                 update_map_name(systems, gui, database);
             }
             TOOL_ERASE => {
@@ -206,15 +233,275 @@ fn interact_with_map(
                     tile_pos,
                     [false, false, false, false],
                 );
-                database.set_map_change(mapview);
+                database.set_map_change();
+                update_map_name(systems, gui, database);
+            }
+            TOOL_FILL => {
+                mapview.set_dir_block_fill(systems, tile_pos, gui.dir_select);
+                database.set_map_change();
                 update_map_name(systems, gui, database);
             }
+            TOOL_EYEDROP => {
+                let tilepos =
+                    get_tile_pos(tile_pos.x as i32, tile_pos.y as i32);
+                let dir_data = mapview.map_dir_block[tilepos].dir_data;
+                set_dir_select(systems, gui, get_dir_visible(dir_data));
+            }
             _ => {}
         },
         _ => {}
     }
 }
 
+// Draw will place the entity or select the clicked one, dragging will
+// move the selected entity to an empty tile
+fn interact_with_placement(
+    systems: &mut DrawSetting,
+    tile_pos: Vec2,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+    is_drag: bool,
+) {
+    match gui.current_tool {
+        TOOL_DRAW | TOOL_EYEDROP => {
+            if let Some(entity) = mapview.get_entity(tile_pos) {
+                if is_drag || gui.selected_entity == Some(tile_pos) {
+                    return;
+                }
+                gui.entity_setting = entity.clone();
+                gui.selected_entity = Some(tile_pos);
+            } else if gui.current_tool == TOOL_EYEDROP {
+                return;
+            } else if let Some(selected) =
+                gui.selected_entity.filter(|_| is_drag)
+            {
+                mapview.move_entity(systems, selected, tile_pos);
+                gui.selected_entity = Some(tile_pos);
+            } else if !is_drag {
+                mapview.set_entity(
+                    systems,
+                    tile_pos,
+                    Some(gui.entity_setting.clone()),
+                );
+                gui.selected_entity = Some(tile_pos);
+            } else {
+                return;
+            }
+        }
+        TOOL_ERASE => {
+            if mapview.get_entity(tile_pos).is_none() {
+                return;
+            }
+            mapview.set_entity(systems, tile_pos, None);
+            if gui.selected_entity == Some(tile_pos) {
+                gui.selected_entity = None;
+            }
+        }
+        _ => return,
+    }
+    database.set_map_change();
+    update_map_name(systems, gui, database);
+    gui.open_zone_settings(systems, mapview);
+}
+
+// The picked tile starts the flood fill, or is one of the path ends
+fn pick_walk_point(
+    systems: &mut DrawSetting,
+    tile_pos: Vec2,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    let tile = WalkTile::new(
+        (database.x, database.y),
+        tile_pos.x as i32,
+        tile_pos.y as i32,
+    );
+    match gui.walk_mode {
+        WalkMode::FromTile => gui.walk_points = vec![tile],
+        WalkMode::Path => {
+            if gui.walk_points.len() >= 2 {
+                gui.walk_points.clear();
+            }
+            gui.walk_points.push(tile);
+        }
+        _ => return,
+    }
+    update_walk_analysis(systems, gui, mapview, database);
+}
+
+// Name of the edit shown on the history list
+fn get_edit_name(gui: &Interface, mapview: &MapView) -> &'static str {
+    if gui.is_placement_open(mapview) {
+        return match gui.current_tool {
+            TOOL_ERASE => "Remove Placement",
+            _ => "Place Entity",
+        };
+    }
+    match (gui.current_tab, gui.current_tool) {
+        (TAB_LAYER, TOOL_DRAW) => "Draw Tiles",
+        (TAB_LAYER, TOOL_ERASE) => "Erase Tiles",
+        (TAB_LAYER, TOOL_FILL) => "Fill Tiles",
+        (TAB_ATTRIBUTE, TOOL_ERASE) => "Erase Attribute",
+        (TAB_ATTRIBUTE, TOOL_FILL) => "Fill Attribute",
+        (TAB_ATTRIBUTE, _) => "Set Attribute",
+        (TAB_ZONE, TOOL_ERASE) => "Erase Zone",
+        (TAB_ZONE, TOOL_FILL) => "Fill Zone",
+        (TAB_ZONE, _) => "Draw Zone",
+        (TAB_PROPERTIES, TOOL_ERASE) => "Erase Dir Block",
+        (TAB_PROPERTIES, TOOL_FILL) => "Fill Dir Block",
+        (TAB_PROPERTIES, _) => "Set Dir Block",
+        _ => "Edit Map",
+    }
+}
+
+// Open the settings of the attribute with its data filled in
+fn select_attribute_setting(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &MapView,
+    attribute: &MapAttribute,
+) {
+    if let Some(attribute_index) =
+        systems.attribute_schema.get_index(attribute.name())
+    {
+        // Scroll the tab list so that the attribute option is in view
+        if attribute_index < gui.start_view
+            || attribute_index >= gui.start_view + MAX_TAB_LABEL
+        {
+            gui.scrollbar.set_value(systems, attribute_index);
+            let names = gui.get_tab_names(systems, mapview);
+            gui.update_scroll(systems, &names, gui.scrollbar.cur_value);
+        }
+        if attribute_index >= gui.start_view
+            && attribute_index < gui.start_view + MAX_TAB_LABEL
+        {
+            gui.select_tab_option(systems, attribute_index - gui.start_view);
+            open_attribute_settings(
+                systems,
+                gui,
+                attribute_index,
+                attribute.get_data(),
+            );
+        }
+    }
+}
+
+// Place a warp on the destination that leads back to the drawn warp
+fn set_return_warp(
+    systems: &mut DrawSetting,
+    tile_pos: Vec2,
+    data: &WarpData,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    if data.tile_x >= 32 || data.tile_y >= 32 {
+        return;
+    }
+    let dest_pos = Vec2::new(data.tile_x as f32, data.tile_y as f32);
+    let return_warp = MapAttribute::Warp(WarpData {
+        map_x: database.x,
+        map_y: database.y,
+        map_group: database.group,
+        tile_x: tile_pos.x as u32,
+        tile_y: tile_pos.y as u32,
+    });
+
+    if data.map_x == database.x
+        && data.map_y == database.y
+        && data.map_group == database.group
+    {
+        if dest_pos != tile_pos {
+            mapview.set_attribute(systems, dest_pos, return_warp);
+        }
+    } else {
+        // Edits on other maps are not part of the map view history
+        database.set_map_attribute(
+            data.map_x,
+            data.map_y,
+            data.map_group,
+            dest_pos,
+            return_warp,
+            &systems.attribute_schema,
+        );
+    }
+}
+
+// Record the clicked tile as the warp destination and go back to the warp map
+fn pick_warp_destination(
+    systems: &mut DrawSetting,
+    tile_pos: Vec2,
+    source: (i32, i32, u64),
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    let attribute = MapAttribute::Warp(WarpData {
+        map_x: database.x,
+        map_y: database.y,
+        map_group: database.group,
+        tile_x: tile_pos.x as u32,
+        tile_y: tile_pos.y as u32,
+    });
+    switch_map(
+        systems, gui, mapview, database, source.0, source.1, source.2,
+    );
+    select_attribute_setting(systems, gui, mapview, &attribute);
+}
+
+// Show the destination of the warp that is being hovered on the map name
+fn hover_warp(
+    systems: &mut DrawSetting,
+    tile_pos: Vec2,
+    gameinput: &mut GameInput,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &EditorData,
+) {
+    if gui.current_tab != TAB_ATTRIBUTE {
+        return;
+    }
+    let warp =
+        mapview
+            .get_attributes(tile_pos)
+            .into_iter()
+            .find_map(|attribute| match attribute {
+                MapAttribute::Warp(data) => Some(data),
+                _ => None,
+            });
+    if let Some(data) = warp {
+        gameinput.hover_warp = true;
+        systems.gfx.set_text(
+            &mut systems.renderer,
+            gui.labels[LABEL_MAPNAME],
+            &format!(
+                "Warp To Map [ X: {} Y: {} Group: {} ] Tile [ X: {} Y: {} ]",
+                data.map_x,
+                data.map_y,
+                data.map_group,
+                data.tile_x,
+                data.tile_y
+            ),
+        );
+    } else if gameinput.hover_warp {
+        gameinput.hover_warp = false;
+        update_map_name(systems, gui, database);
+    }
+}
+
+// The linked map strips can only be painted on with the layer tools,
+// otherwise clicking on them will move to the linked map
+fn can_edit_link_map(gui: &Interface) -> bool {
+    gui.current_tab == TAB_LAYER
+        && matches!(gui.current_tool, TOOL_DRAW | TOOL_ERASE | TOOL_FILL)
+}
+
+fn in_edit_area(screen_pos: Vec2, gui: &Interface, mapview: &MapView) -> bool {
+    in_map(screen_pos, mapview)
+        || (can_edit_link_map(gui) && in_link_map(screen_pos, mapview))
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn mapview_input(
     systems: &mut DrawSetting,
@@ -230,8 +517,32 @@ pub fn mapview_input(
         MouseInputType::LeftDown => {
             if !is_scrollbar_in_hold(gui) {
                 // Check if mouse position is pointing to our map view
-                if in_map(screen_pos, mapview) {
-                    mapview.record.set_undo_record();
+                if in_edit_area(screen_pos, gui, mapview) {
+                    if let Some(source) = gameinput.warp_pick.take() {
+                        pick_warp_destination(
+                            systems,
+                            get_map_pos(screen_pos, mapview),
+                            source,
+                            gui,
+                            mapview,
+                            database,
+                        );
+                        return;
+                    }
+                    // While comparing, a click reverts the tile
+                    if mapview.diff.is_some() {
+                        gameinput.presstype = PressType::None;
+                        revert_diff_tile(
+                            systems,
+                            gui,
+                            mapview,
+                            database,
+                            get_map_pos(screen_pos, mapview),
+                        );
+                        return;
+                    }
+                    mapview.history.begin_group(get_edit_name(gui, mapview));
+                    gameinput.presstype = PressType::None;
                     interact_with_map(
                         systems,
                         get_map_pos(screen_pos, mapview),
@@ -245,42 +556,26 @@ pub fn mapview_input(
                 }
 
                 // Linked Map
-                if gameinput.selected_link_map.is_some() {
+                if gameinput.selected_link_map.is_some()
+                    && !(can_edit_link_map(gui)
+                        && in_link_map(screen_pos, mapview))
+                {
                     let direction =
                         convert_to_dir(gameinput.selected_link_map.unwrap());
-                    let temp_key = database.move_map(direction);
+                    let temp_key = database.move_map(direction, mapview);
                     if temp_key.is_some() {
                         // We will store a temporary map data when changes happen
                         database.save_map_data(mapview, temp_key);
                     };
                     // Load the initial map
-                    database.load_map_data(systems, mapview);
-                    database.load_link_maps(mapview);
-                    update_map_name(systems, gui, database);
-
-                    match gui.current_tab {
-                        TAB_ZONE => {
-                            mapview.update_map_zone(
-                                systems,
-                                gui.current_tab_data as usize,
-                            );
-                            gui.open_zone_settings(systems, mapview);
-                        }
-                        TAB_PROPERTIES => {
-                            gui.editor_selectionbox[0].switch_list(
-                                systems,
-                                mapview.fixed_weather as usize,
-                            );
-                        }
-                        _ => {}
-                    }
+                    refresh_map_view(systems, gui, mapview, database);
                 }
             }
         }
         MouseInputType::LeftDownMove => {
             if !is_scrollbar_in_hold(gui) {
                 // Check if mouse position is pointing to our map view
-                if in_map(screen_pos, mapview)
+                if in_edit_area(screen_pos, gui, mapview)
                     && gameinput.presstype == PressType::Map
                 {
                     // Calculate the tile position on the map based on mouse position
@@ -305,7 +600,9 @@ pub fn mapview_input(
                         gameinput,
                     );
 
-                    mapview.hover_selection_preview(systems, tile_map_pos);
+                    if gameinput.dir_block_rect.is_none() {
+                        mapview.hover_selection_preview(systems, tile_map_pos);
+                    }
                 }
             }
         }
@@ -314,7 +611,7 @@ pub fn mapview_input(
             gameinput.selected_link_map =
                 mapview.hover_linked_selection(systems, screen_pos);
             // Calculate the tile position on the map based on mouse position
-            if in_map(screen_pos, mapview) {
+            if in_edit_area(screen_pos, gui, mapview) {
                 let tile_map_pos = get_map_pos(screen_pos, mapview);
 
                 systems.gfx.set_text(
@@ -327,10 +624,201 @@ pub fn mapview_input(
                 );
 
                 mapview.hover_selection_preview(systems, tile_map_pos);
+                if in_map(screen_pos, mapview) {
+                    hover_warp(
+                        systems,
+                        tile_map_pos,
+                        gameinput,
+                        gui,
+                        mapview,
+                        database,
+                    );
+                }
             }
         }
         MouseInputType::Release => {
-            mapview.record.stop_record();
+            if let Some((start, end)) = gameinput.dir_block_rect.take() {
+                let dir_select = if gui.current_tool == TOOL_ERASE {
+                    [false; 4]
+                } else {
+                    gui.dir_select
+                };
+                // Erasing with the outline will clear the whole rectangle
+                let mode = if gui.current_tool == TOOL_ERASE {
+                    DirBlockMode::Rect
+                } else {
+                    gui.dir_block_mode
+                };
+                mapview
+                    .set_dir_block_rect(systems, start, end, dir_select, mode);
+                mapview.change_selection_preview_size(systems, Vec2::ONE);
+                database.set_map_change();
+                update_map_name(systems, gui, database);
+            }
+            mapview.history.end_group();
+        }
+        _ => {}
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn mapview_camera_input(
+    systems: &mut DrawSetting,
+    inputtype: &MouseInputType,
+    screen_pos: Vec2,
+    gameinput: &mut GameInput,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    config_data: &mut ConfigData,
+) -> bool {
+    let camera = mapview.camera_mut();
+    match inputtype {
+        MouseInputType::MiddleDown => {
+            if camera.in_view(screen_pos) {
+                gameinput.in_pan = true;
+                gameinput.pan_pos = screen_pos;
+            }
+        }
+        MouseInputType::MiddleDownMove => {
+            if gameinput.in_pan {
+                camera.move_pan(screen_pos - gameinput.pan_pos);
+                gameinput.pan_pos = screen_pos;
+            }
         }
+        MouseInputType::MiddleRelease => {
+            gameinput.in_pan = false;
+        }
+        MouseInputType::Scroll(amount) => {
+            if !camera.in_view(screen_pos) {
+                return true;
+            }
+            if *amount > 0.0 {
+                camera.zoom_in(screen_pos);
+            } else if *amount < 0.0 {
+                camera.zoom_out(screen_pos);
+            }
+            update_map_zoom(systems, gui, mapview, config_data);
+        }
+        _ => return false,
+    }
+    true
+}
+
+pub fn worldview_input(
+    systems: &mut DrawSetting,
+    inputtype: &MouseInputType,
+    screen_pos: Vec2,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    match inputtype {
+        MouseInputType::LeftDown => {
+            if is_scrollbar_in_hold(gui) {
+                return;
+            }
+            if let Some((x, y)) = mapview.world.find_map(screen_pos) {
+                let group = database.group;
+                switch_map(systems, gui, mapview, database, x, y, group);
+            }
+        }
+        MouseInputType::Move => {
+            if let Some((x, y)) = mapview.world.hover_map(systems, screen_pos) {
+                systems.gfx.set_text(
+                    &mut systems.renderer,
+                    gui.labels[LABEL_TILEPOS],
+                    &format!("Map [ X: {} Y: {} ]", x, y),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+// Open another map on the map view, the changes of the current map are kept
+pub fn switch_map(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+    x: i32,
+    y: i32,
+    group: u64,
+) {
+    if database.did_change(database.x, database.y, database.group) {
+        database.save_map_data(mapview, Some(database.current_index.clone()));
+    }
+    if mapview.world.is_open {
+        mapview.close_world_view(systems);
+    }
+
+    database.init_map(x, y, group, mapview);
+    refresh_map_view(systems, gui, mapview, database);
+}
+
+// Reload the map view after the center map has been switched
+pub fn refresh_map_view(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    database.load_map_data(systems, mapview);
+    database.load_link_maps(mapview);
+    update_map_name(systems, gui, database);
+    gui.selected_entity = None;
+    refresh_map_setting(systems, gui, mapview);
+    update_walk_analysis(systems, gui, mapview, database);
+    update_map_diff(systems, gui, mapview, database);
+}
+
+// Update the settings shown on the current tab with the map view data
+pub fn refresh_map_setting(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+) {
+    match gui.current_tab {
+        TAB_ZONE => {
+            // The zones can be added or removed by the history
+            gui.open_zone_list(systems, mapview);
+            mapview.update_map_zone(systems, gui.current_tab_data as usize);
+            gui.open_zone_settings(systems, mapview);
+        }
+        TAB_PROPERTIES => {
+            gui.editor_selectionbox[0]
+                .set_selected(systems, mapview.fixed_weather as usize);
+            let music_index = mapview
+                .music
+                .as_ref()
+                .and_then(|music| {
+                    gui.editor_selectionbox[1]
+                        .list
+                        .iter()
+                        .position(|name| name == music)
+                })
+                .unwrap_or(0);
+            gui.editor_selectionbox[1].set_selected(systems, music_index);
+        }
+        _ => {}
+    }
+}
+
+// Attribute letters and the map view grid are hidden when zoomed out too far
+pub fn update_map_zoom(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    config_data: &mut ConfigData,
+) {
+    let show_labels = mapview.camera.show_labels();
+    if gui.current_tab == TAB_ATTRIBUTE {
+        mapview.map_attributes.iter().for_each(|attribute| {
+            systems.gfx.set_visible(attribute.text, show_labels);
+        });
     }
+    systems.gfx.set_visible(
+        gui.bg_layout[1],
+        !config_data.hide_mapview_bg && show_labels,
+    );
 }
@@ -180,7 +180,10 @@ pub fn preference_input(
                                                 checkbox.is_select;
                                             systems.gfx.set_visible(
                                                 gui.bg_layout[1],
-                                                !checkbox.is_select,
+                                                !checkbox.is_select
+                                                    && mapview
+                                                        .camera
+                                                        .show_labels(),
                                             );
                                         }
                                         _ => {}
@@ -248,6 +251,7 @@ pub fn preference_input(
             gui.preference.keywindow.release_click(systems);
             gui.preference.scrollbar.release_scrollbar(systems);
         }
+        _ => {}
     }
 }
 
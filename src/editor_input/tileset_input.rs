@@ -98,5 +98,6 @@ pub fn tileset_input(
         MouseInputType::Release => {
             gui.tileset_list.scrollbar.release_scrollbar(systems);
         }
+        _ => {}
     }
 }
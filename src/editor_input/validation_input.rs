@@ -0,0 +1,119 @@
+use crate::{editor_input::*, map_merge::load_merge_conflicts};
+
+// Return true if the input has been used by the validation list
+pub fn validation_input(
+    systems: &mut DrawSetting,
+    inputtype: &MouseInputType,
+    screen_pos: Vec2,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) -> bool {
+    if !gui.validation_list.visible {
+        return false;
+    }
+
+    match inputtype {
+        MouseInputType::LeftDown => {
+            if gui
+                .validation_list
+                .scrollbar
+                .in_scrollbar(systems, screen_pos)
+            {
+                gui.validation_list
+                    .scrollbar
+                    .hold_scrollbar(systems, screen_pos.y);
+                return true;
+            }
+            if !gui.validation_list.in_list(systems, screen_pos) {
+                return false;
+            }
+            // Open the map of the problem and point at the tile
+            if let Some(issue) =
+                gui.validation_list.select_list(systems, screen_pos)
+            {
+                switch_map(
+                    systems,
+                    gui,
+                    mapview,
+                    database,
+                    issue.x,
+                    issue.y,
+                    issue.group,
+                );
+                if let Some((tile_x, tile_y)) = issue.tile {
+                    mapview.hover_selection_preview(
+                        systems,
+                        Vec2::new(tile_x as f32, tile_y as f32),
+                    );
+                }
+            }
+            true
+        }
+        MouseInputType::LeftDownMove => {
+            if !gui.validation_list.scrollbar.in_hold {
+                return false;
+            }
+            gui.validation_list.scrollbar.move_scrollbar(
+                systems,
+                screen_pos.y,
+                false,
+            );
+            if gui
+                .validation_list
+                .update_scroll(gui.validation_list.scrollbar.cur_value)
+            {
+                gui.validation_list.update_list(systems);
+            }
+            gui.validation_list.scrollbar.set_hover(systems, screen_pos);
+            true
+        }
+        MouseInputType::Move => {
+            gui.validation_list.hover_selection(systems, screen_pos);
+            gui.validation_list.scrollbar.set_hover(systems, screen_pos);
+            gui.validation_list.in_list(systems, screen_pos)
+        }
+        MouseInputType::Release => {
+            gui.validation_list.scrollbar.release_scrollbar(systems);
+            false
+        }
+        _ => false,
+    }
+}
+
+// Validate every map, including the changes that are not saved yet
+pub fn open_validation_report(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    database.save_map_data(mapview, Some(database.current_index.clone()));
+    let mut issues = validate_maps(&database.maps, &systems.audio_list.audio);
+    // Conflicts of the merges that were not reviewed yet
+    issues.extend(
+        load_merge_conflicts()
+            .iter()
+            .map(|conflict| conflict.to_issue()),
+    );
+    gui.history_list.hide(systems);
+    gui.validation_list
+        .set_issues(systems, "Validation Report", issues);
+    gui.validation_list.show(systems);
+}
+
+// Compare the edges of the maps within the current group
+pub fn open_seam_report(
+    systems: &mut DrawSetting,
+    gui: &mut Interface,
+    mapview: &mut MapView,
+    database: &mut EditorData,
+) {
+    database.save_map_data(mapview, Some(database.current_index.clone()));
+    let issues =
+        check_seams(&database.maps, database.group, gui.seam_check_tiles);
+    gui.history_list.hide(systems);
+    gui.validation_list
+        .set_issues(systems, "Seam Report", issues);
+    gui.validation_list.show(systems);
+}
@@ -0,0 +1,222 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::BufReader;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GameDataKind {
+    Item,
+    Npc,
+    Shop,
+}
+
+impl GameDataKind {
+    // Name of the json file or folder on the game data path
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            GameDataKind::Item => "items",
+            GameDataKind::Npc => "npcs",
+            GameDataKind::Shop => "shops",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GameDataEntry {
+    pub id: u64,
+    pub name: String,
+}
+
+// Item, Npc and Shop definitions of the game, so that the IDs used on the
+// maps can be shown with their name
+#[derive(Default)]
+pub struct GameDatabase {
+    pub items: Vec<GameDataEntry>,
+    pub npcs: Vec<GameDataEntry>,
+    pub shops: Vec<GameDataEntry>,
+    // Localized strings of the project, used by the signs with a key
+    pub strings: IndexMap<String, String>,
+}
+
+impl GameDatabase {
+    pub fn load(path: &str) -> Self {
+        Self {
+            items: load_entries(path, GameDataKind::Item),
+            npcs: load_entries(path, GameDataKind::Npc),
+            shops: load_entries(path, GameDataKind::Shop),
+            strings: load_strings(path),
+        }
+    }
+
+    pub fn get_string(&self, key: &str) -> Option<&str> {
+        self.strings.get(key).map(|text| text.as_str())
+    }
+
+    pub fn get_list(&self, kind: GameDataKind) -> &[GameDataEntry] {
+        match kind {
+            GameDataKind::Item => &self.items,
+            GameDataKind::Npc => &self.npcs,
+            GameDataKind::Shop => &self.shops,
+        }
+    }
+
+    pub fn get_name(&self, kind: GameDataKind, id: u64) -> Option<&str> {
+        self.get_list(kind)
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.name.as_str())
+    }
+
+    // Build the selection list of the data, an ID that does not resolve is
+    // kept on the list so that it will not be lost when the list is not used
+    // Return the list, the ID of each entry, the selected entry and if the ID
+    // could not be resolved, the None entry has no ID as 0 can be a valid ID
+    pub fn get_selection_list(
+        &self,
+        kind: GameDataKind,
+        id: Option<u64>,
+        allow_none: bool,
+    ) -> (Vec<String>, Vec<Option<u64>>, usize, bool) {
+        let mut list = Vec::new();
+        let mut values = Vec::new();
+        if allow_none {
+            list.push("None".to_string());
+            values.push(None);
+        }
+        for entry in self.get_list(kind) {
+            list.push(format!("{} ({})", entry.name, entry.id));
+            values.push(Some(entry.id));
+        }
+
+        let id = match id {
+            Some(id) => id,
+            None => return (list, values, 0, false),
+        };
+        match values.iter().position(|value| *value == Some(id)) {
+            Some(index) => (list, values, index, false),
+            None => {
+                list.push(format!("Unknown ({})", id));
+                values.push(Some(id));
+                let index = list.len() - 1;
+                (list, values, index, true)
+            }
+        }
+    }
+}
+
+// The id field of the entry comes first, the entries without one are
+// skipped when their key or position gives no ID either
+fn get_entry(value: &Value, default_id: Option<u64>) -> Option<GameDataEntry> {
+    let name = value
+        .get("name")
+        .or_else(|| value.get("Name"))
+        .and_then(|name| name.as_str())?;
+    let id = value
+        .get("id")
+        .or_else(|| value.get("Id"))
+        .and_then(|id| id.as_u64())
+        .or(default_id)?;
+    Some(GameDataEntry {
+        id,
+        name: name.to_string(),
+    })
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let file = OpenOptions::new().read(true).open(path).ok()?;
+    match serde_json::from_reader(BufReader::new(file)) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            println!("Error {:?}", e);
+            None
+        }
+    }
+}
+
+// The data can either be a single json file with a list of entries,
+// or a folder with a json file for each entry named by their ID
+fn load_entries(path: &str, kind: GameDataKind) -> Vec<GameDataEntry> {
+    let mut entries = Vec::new();
+
+    let file_path = Path::new(path).join(format!("{}.json", kind.file_name()));
+    if let Some(data) = read_json(&file_path) {
+        match data {
+            Value::Array(list) => {
+                for (index, value) in list.iter().enumerate() {
+                    if let Some(entry) = get_entry(value, Some(index as u64)) {
+                        entries.push(entry);
+                    }
+                }
+            }
+            Value::Object(list) => {
+                for (key, value) in list.iter() {
+                    if let Some(entry) = get_entry(value, key.parse().ok()) {
+                        entries.push(entry);
+                    }
+                }
+            }
+            _ => {}
+        }
+    } else if let Ok(dir) = fs::read_dir(Path::new(path).join(kind.file_name()))
+    {
+        for file in dir.flatten() {
+            let file_path = file.path();
+            if file_path.extension().and_then(|ext| ext.to_str())
+                != Some("json")
+            {
+                continue;
+            }
+            let id = match file_path
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.parse::<u64>().ok())
+            {
+                Some(id) => id,
+                None => continue,
+            };
+            if let Some(entry) = read_json(&file_path)
+                .and_then(|data| get_entry(&data, Some(id)))
+            {
+                entries.push(entry);
+            }
+        }
+    }
+
+    entries.sort_by_key(|entry| entry.id);
+    entries
+}
+
+// Nested objects of the string table are joined into a single key,
+// such as "signs.town.welcome"
+fn add_strings(
+    strings: &mut IndexMap<String, String>,
+    prefix: &str,
+    value: &Value,
+) {
+    match value {
+        Value::Object(list) => {
+            for (key, value) in list.iter() {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                add_strings(strings, &key, value);
+            }
+        }
+        Value::String(text) => {
+            strings.insert(prefix.to_string(), text.clone());
+        }
+        _ => {}
+    }
+}
+
+fn load_strings(path: &str) -> IndexMap<String, String> {
+    let mut strings = IndexMap::new();
+    if let Some(data) = read_json(&Path::new(path).join("strings.json")) {
+        add_strings(&mut strings, "", &data);
+    }
+    strings
+}
@@ -155,6 +155,20 @@ impl GfxCollection {
         }
     }
 
+    pub fn set_text_attrs(
+        &mut self,
+        renderer: &mut GpuRenderer,
+        index: usize,
+        msg: &str,
+        attrs: Attrs,
+    ) {
+        if let Some(data) = self.collection.get_mut(index) {
+            if let GfxType::Text(text) = &mut data.gfx {
+                text.set_text(renderer, msg, attrs, Shaping::Advanced);
+            }
+        }
+    }
+
     pub fn center_text(&mut self, index: usize) {
         if let Some(data) = self.collection.get_mut(index) {
             if let GfxType::Text(text) = &mut data.gfx {
@@ -215,6 +229,14 @@ impl GfxCollection {
         }
     }
 
+    pub fn get_visible(&mut self, index: usize) -> bool {
+        if let Some(data) = self.collection.get(index) {
+            data.visible
+        } else {
+            false
+        }
+    }
+
     pub fn get_measure(&mut self, index: usize) -> Vec2 {
         if let Some(data) = self.collection.get(index) {
             match &data.gfx {
@@ -1,6 +1,8 @@
 pub mod dialog;
+mod history_list;
 pub mod preference;
 mod tileset_list;
+mod validation_list;
 pub mod widgets;
 
 use cosmic_text::{Attrs, Metrics};
@@ -8,13 +10,15 @@ use graphics::*;
 use indexmap::IndexMap;
 
 pub use dialog::*;
+use history_list::*;
 pub use preference::*;
 use tileset_list::*;
+use validation_list::*;
 pub use widgets::*;
 
 use crate::{
-    collection::*, config, map::*, tileset::*, ConfigData, DrawSetting,
-    GameInput,
+    collection::*, config, game_data::*, map::*, tileset::*, ConfigData,
+    DrawSetting, GameInput,
 };
 
 // Labels
@@ -45,12 +49,57 @@ const MAX_EXTRA_BUTTON: usize = 1;
 pub const MAX_TAB_LABEL: usize = 14;
 pub const MAX_LABEL: usize = 5;
 
+// Widget used by each field of the attribute settings, with its index
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FieldWidget {
+    Textbox(usize),
+    Checkbox(usize),
+    SelectionBox(usize),
+    TextEditor(usize),
+}
+
+// Searchable selection box of the game data, showing the name and ID
+pub fn create_game_data_box(
+    systems: &mut DrawSetting,
+    pos: Vec2,
+    width: f32,
+    kind: GameDataKind,
+    id: Option<u64>,
+    allow_none: bool,
+) -> SelectionBox {
+    let (list, values, selected, is_error) =
+        systems.game_data.get_selection_list(kind, id, allow_none);
+    let mut selectionbox = SelectionBox::new(
+        systems,
+        pos,
+        [
+            ORDER_PROPERTIES_BUTTON,
+            ORDER_PROPERTIES_BUTTON_TEXT,
+            ORDER_DROPDOWN_WINDOW,
+            ORDER_DROPDOWN_SELECTION,
+            ORDER_DROPDOWN_TEXT,
+            ORDER_DROPDOWN_SCROLLBAR,
+        ],
+        width,
+        list,
+        0,
+    );
+    selectionbox.values = values;
+    selectionbox.set_selected(systems, selected);
+    if is_error {
+        selectionbox.set_invalid_entry(systems, Some(selected));
+    }
+    selectionbox
+}
+
 pub struct Interface {
     pub bg_layout: Vec<usize>,
     pub labels: Vec<usize>,
     pub buttons: Vec<ToolButton>,
     pub current_tool: usize,
     pub tileset_list: TilesetList,
+    pub history_list: HistoryList,
+    pub validation_list: ValidationList,
     pub current_tab: usize,
     reset_tool_button: bool,
     reset_button: bool,
@@ -70,9 +119,29 @@ pub struct Interface {
     pub editor_textbox: Vec<Textbox>,
     pub editor_button: Vec<Button>,
     pub editor_selectionbox: Vec<SelectionBox>,
+    pub editor_checkbox: Vec<Checkbox>,
+    pub editor_text_editor: Vec<TextEditor>,
+    // Widgets of the attribute settings, in the order of the schema fields
+    pub attribute_fields: Vec<FieldWidget>,
+    // Place the way back on the destination when drawing a warp
+    pub return_warp: bool,
+    // Label showing the resolved text of a sign localization key
+    pub sign_preview: Option<usize>,
+    // NPC entry of the selected zone that is shown on the zone settings
+    pub zone_npc_index: usize,
+    // Entity that will be placed, or the data of the selected placement
+    pub entity_setting: MapEntity,
+    pub selected_entity: Option<Vec2>,
     pub selected_textbox: i32,
     pub selected_dropbox: i32,
     pub dir_select: [bool; 4],
+    pub dir_block_mode: DirBlockMode,
+    pub walk_mode: WalkMode,
+    pub walk_rule: WalkRule,
+    // Tile picked for the flood fill, or the start and end of the path
+    pub walk_points: Vec<WalkTile>,
+    // Tiles are also compared by the seam checker
+    pub seam_check_tiles: bool,
 }
 
 impl Interface {
@@ -97,6 +166,7 @@ impl Interface {
         mapviewbg.pos = Vec3::new(215.0, 35.0, ORDER_ALPHA_BG);
         mapviewbg.hw = Vec2::new(724.0, 724.0);
         mapviewbg.uv = Vec4::new(0.0, 0.0, 724.0, 724.0);
+        mapviewbg.use_camera = true;
         let mut tilesetbg = Image::new(
             Some(systems.resource.tileset_bg.allocation),
             &mut systems.renderer,
@@ -107,7 +177,7 @@ impl Interface {
         tilesetbg.uv = Vec4::new(0.0, 0.0, 200.0, 400.0);
         let bg_layout = vec![
             systems.gfx.add_image(bglayout, 0),
-            systems.gfx.add_image(mapviewbg, 0),
+            systems.gfx.add_image(mapviewbg, RENDER_MAPVIEW),
             systems.gfx.add_image(tilesetbg, 0),
         ];
         // Set starting visibility
@@ -216,10 +286,11 @@ impl Interface {
         }
 
         // This calculate the scrollable value that the scrollbar will have
-        let mut scroll_amount = 0;
-        if MAX_TAB_LABEL < MAX_ATTRIBUTE - 1 {
-            scroll_amount = MAX_ATTRIBUTE - MAX_TAB_LABEL - 1;
-        }
+        let scroll_amount = systems
+            .attribute_schema
+            .attributes
+            .len()
+            .saturating_sub(MAX_TAB_LABEL);
         // This will create the visual image of the scrollable area
         let mut scrollbarbg = Rect::new(&mut systems.renderer, 0);
         scrollbarbg
@@ -242,6 +313,12 @@ impl Interface {
         // Tileset List
         let tileset_list = TilesetList::new(systems);
 
+        // History List
+        let history_list = HistoryList::new(systems);
+
+        // Validation Report
+        let validation_list = ValidationList::new(systems);
+
         // Attributes Properties Window
         let mut tab_opt_bg0 = Rect::new(&mut systems.renderer, 0);
         tab_opt_bg0
@@ -306,6 +383,8 @@ impl Interface {
             tab_labels,
             current_tab_data: 0,
             tileset_list,
+            history_list,
+            validation_list,
             dialog: None,
             preference,
             scrollbar_bg,
@@ -318,9 +397,22 @@ impl Interface {
             editor_textbox: Vec::new(),
             editor_button: Vec::new(),
             editor_selectionbox: Vec::new(),
+            editor_checkbox: Vec::new(),
+            editor_text_editor: Vec::new(),
+            attribute_fields: Vec::new(),
+            return_warp: false,
+            sign_preview: None,
+            zone_npc_index: 0,
+            entity_setting: MapEntity::default(),
+            selected_entity: None,
             selected_textbox: -1,
             selected_dropbox: -1,
             dir_select: [false; 4],
+            dir_block_mode: DirBlockMode::Tile,
+            walk_mode: WalkMode::Off,
+            walk_rule: WalkRule::Player,
+            walk_points: Vec::new(),
+            seam_check_tiles: false,
         }
     }
 
@@ -585,7 +677,7 @@ impl Interface {
     ) {
         if self.current_selected_area != tab_index as i32 {
             match self.current_tab {
-                TAB_LAYER | TAB_ZONE => {
+                TAB_LAYER => {
                     if self.tab_labels[tab_index].visible {
                         // We will unselect the previous selection and select the current selection
                         self.tab_labels[self.current_selected_area as usize]
@@ -595,7 +687,7 @@ impl Interface {
                         self.current_selected_area = tab_index as i32;
                     }
                 }
-                TAB_ATTRIBUTE => {
+                TAB_ATTRIBUTE | TAB_ZONE => {
                     if self.tab_labels[tab_index].visible {
                         // We will unselect the previous selection and select the current selection
                         // Note: On this part, since a scrollbar is available on Tab_attribute
@@ -626,9 +718,34 @@ impl Interface {
         }
     }
 
+    // Names shown on the tab labels that can be scrolled
+    pub fn get_tab_names(
+        &self,
+        systems: &DrawSetting,
+        mapview: &MapView,
+    ) -> Vec<String> {
+        match self.current_tab {
+            TAB_ATTRIBUTE => systems
+                .attribute_schema
+                .attributes
+                .iter()
+                .map(|attribute| attribute.name.clone())
+                .collect(),
+            // The fixed placements are listed after the zones
+            TAB_ZONE => mapview
+                .map_zone_setting
+                .iter()
+                .map(|setting| setting.name.clone())
+                .chain(std::iter::once("Placements".to_string()))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn update_scroll(
         &mut self,
         systems: &mut DrawSetting,
+        names: &[String],
         cur_value: usize,
     ) {
         if self.start_view == cur_value {
@@ -640,52 +757,475 @@ impl Interface {
         self.current_selected_area = -1;
         for index in 0..MAX_TAB_LABEL {
             let sel_index = self.start_view + index;
-            if sel_index < MAX_ATTRIBUTE - 1 {
+            if let Some(name) = names.get(sel_index) {
                 if self.current_tab_data == sel_index as u32 {
-                    self.tab_labels[index].update(
-                        systems,
-                        MapAttribute::as_str(sel_index as u32 + 1),
-                        true,
-                    );
+                    self.tab_labels[index].update(systems, name, true);
                     self.current_selected_area = index as i32;
                 } else {
-                    self.tab_labels[index].update(
-                        systems,
-                        MapAttribute::as_str(sel_index as u32 + 1),
-                        false,
-                    );
+                    self.tab_labels[index].update(systems, name, false);
                 }
             }
         }
     }
 
+    // Rebuild the zone labels, the scrollbar is only shown when the zones
+    // do not fit on the labels
+    pub fn open_zone_list(
+        &mut self,
+        systems: &mut DrawSetting,
+        mapview: &MapView,
+    ) {
+        let names = self.get_tab_names(systems, mapview);
+        if self.current_tab_data as usize >= names.len() {
+            self.current_tab_data = names.len().saturating_sub(1) as u32;
+        }
+
+        for label in self.tab_labels.iter_mut() {
+            label.close(systems);
+        }
+        let scroll_amount = names.len().saturating_sub(MAX_TAB_LABEL);
+        let width = if scroll_amount > 0 { 180.0 } else { 194.0 };
+        self.start_view = 0;
+        self.current_selected_area = -1;
+        for (index, name) in names.iter().take(MAX_TAB_LABEL).enumerate() {
+            self.tab_labels[index].init(systems, name, width);
+            if self.current_tab_data as usize == index {
+                self.tab_labels[index].set_select(systems, true);
+                self.current_selected_area = index as i32;
+            }
+        }
+
+        self.scrollbar
+            .update_scroll_max_value(systems, scroll_amount);
+        if scroll_amount > 0 {
+            systems.gfx.set_visible(self.scrollbar_bg, true);
+            self.scrollbar.show(systems);
+        } else {
+            systems.gfx.set_visible(self.scrollbar_bg, false);
+            self.scrollbar.hide(systems);
+        }
+    }
+
+    // Update the label of the selected zone after its name was changed
+    pub fn update_zone_label(&mut self, systems: &mut DrawSetting, name: &str) {
+        if self.current_selected_area >= 0 {
+            self.tab_labels[self.current_selected_area as usize]
+                .update(systems, name, true);
+        }
+    }
+
     pub fn open_zone_settings(
         &mut self,
         systems: &mut DrawSetting,
         mapview: &mut MapView,
     ) {
-        let zone_index = self.current_tab_data;
-        // Max NPC
-        self.editor_textbox[0].input_text(
+        // The widgets depend on the zone and npc entry, so they are rebuilt
+        self.editor_label.iter().for_each(|label| {
+            systems.gfx.remove_gfx(*label);
+        });
+        self.editor_label.clear();
+        self.editor_textbox.iter_mut().for_each(|textbox| {
+            systems.gfx.remove_gfx(textbox.image);
+            systems.gfx.remove_gfx(textbox.text);
+        });
+        self.editor_textbox = vec![];
+        self.selected_textbox = -1;
+        self.editor_button.iter_mut().for_each(|button| {
+            systems.gfx.remove_gfx(button.image);
+            systems.gfx.remove_gfx(button.text);
+        });
+        self.editor_button = vec![];
+        self.editor_selectionbox
+            .iter_mut()
+            .for_each(|selection_box| selection_box.unload(systems));
+        self.editor_selectionbox = vec![];
+        self.selected_dropbox = -1;
+        self.editor_text_editor
+            .iter_mut()
+            .for_each(|text_editor| text_editor.unload(systems));
+        self.editor_text_editor = vec![];
+
+        let pos = systems.gfx.get_pos(self.tab_opt_bg[0]);
+        let pos = Vec2::new(pos.x, pos.y);
+
+        if self.is_placement_open(mapview) {
+            self.open_placement_settings(systems, mapview, pos);
+            return;
+        }
+
+        // Add Zone, Remove Zone
+        for (index, msg) in ["Add Zone", "Remove"].iter().enumerate() {
+            self.editor_button.push(Button::new(
+                systems,
+                systems.resource.preference_button.allocation,
+                msg,
+                Vec2::new(pos.x + 14.0 + (index * 92) as f32, pos.y + 304.0),
+                Vec2::new(80.0, 22.0),
+                [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                2.0,
+                [0, 1],
+            ));
+        }
+
+        let zone_index = self.current_tab_data as usize;
+        let setting = match mapview.map_zone_setting.get(zone_index) {
+            Some(setting) => setting.clone(),
+            None => return,
+        };
+
+        // Name, Max NPC
+        for (index, (msg, value)) in [
+            ("Name", setting.name.clone()),
+            ("Max NPC", setting.max_npc.to_string()),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let y = pos.y + 368.0 - (index * 28) as f32;
+            let label =
+                create_zone_label(systems, Vec2::new(pos.x + 10.0, y), msg);
+            self.editor_label.push(label);
+            let mut textbox = Textbox::new(
+                systems,
+                Vec3::new(pos.x + 85.0, y, ORDER_ATTRIBUTE_TEXTBOX),
+                Vec2::new(105.0, 22.0),
+                false,
+                [0, 1],
+            );
+            textbox.input_text(systems, value);
+            self.editor_textbox.push(textbox);
+        }
+
+        // NPC entries of the zone
+        let label = create_zone_label(
             systems,
-            mapview.map_zone_setting[zone_index as usize]
-                .max_npc
-                .to_string(),
-        ); // Max Npc
-           // NPC
-        for i in 0..5 {
-            if mapview.map_zone_setting[zone_index as usize].npc_id[i].is_some()
-            {
-                self.editor_textbox[i + 1].input_text(
+            pos + Vec2::new(10.0, 272.0),
+            "NPC Entries",
+        );
+        self.editor_label.push(label);
+        if self.zone_npc_index >= setting.npcs.len() {
+            self.zone_npc_index = setting.npcs.len().saturating_sub(1);
+        }
+        let entries: Vec<String> = setting
+            .npcs
+            .iter()
+            .enumerate()
+            .map(|(index, npc)| {
+                let name = systems
+                    .game_data
+                    .get_name(GameDataKind::Npc, npc.npc_id)
+                    .unwrap_or("Unknown");
+                format!("{}: {} ({})", index + 1, name, npc.npc_id)
+            })
+            .collect();
+        let mut entry_box = SelectionBox::new(
+            systems,
+            pos + Vec2::new(10.0, 244.0),
+            [
+                ORDER_PROPERTIES_BUTTON,
+                ORDER_PROPERTIES_BUTTON_TEXT,
+                ORDER_DROPDOWN_WINDOW,
+                ORDER_DROPDOWN_SELECTION,
+                ORDER_DROPDOWN_TEXT,
+                ORDER_DROPDOWN_SCROLLBAR,
+            ],
+            180.0,
+            if entries.is_empty() {
+                vec!["None".to_string()]
+            } else {
+                entries
+            },
+            0,
+        );
+        entry_box.set_selected(systems, self.zone_npc_index);
+        self.editor_selectionbox.push(entry_box);
+
+        // Add NPC, Remove NPC
+        for (index, msg) in ["Add NPC", "Remove"].iter().enumerate() {
+            self.editor_button.push(Button::new(
+                systems,
+                systems.resource.preference_button.allocation,
+                msg,
+                Vec2::new(pos.x + 14.0 + (index * 92) as f32, pos.y + 210.0),
+                Vec2::new(80.0, 22.0),
+                [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                2.0,
+                [0, 1],
+            ));
+        }
+
+        let npc = match setting.npcs.get(self.zone_npc_index) {
+            Some(npc) => npc,
+            None => return,
+        };
+
+        // NPC, the ID is picked from the npc list of the game data
+        let label =
+            create_zone_label(systems, pos + Vec2::new(10.0, 180.0), "NPC");
+        self.editor_label.push(label);
+        let selection_box = create_game_data_box(
+            systems,
+            pos + Vec2::new(10.0, 152.0),
+            180.0,
+            GameDataKind::Npc,
+            Some(npc.npc_id),
+            false,
+        );
+        self.editor_selectionbox.push(selection_box);
+
+        // Weight, Level Min, Level Max, Respawn
+        for (index, (msg, value)) in [
+            ("Weight", npc.weight as u64),
+            ("Level Min", npc.level_min as u64),
+            ("Level Max", npc.level_max as u64),
+            ("Respawn", npc.respawn),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let y = pos.y + 122.0 - (index * 28) as f32;
+            let label =
+                create_zone_label(systems, Vec2::new(pos.x + 10.0, y), msg);
+            self.editor_label.push(label);
+            let mut textbox = Textbox::new(
+                systems,
+                Vec3::new(pos.x + 110.0, y, ORDER_ATTRIBUTE_TEXTBOX),
+                Vec2::new(80.0, 22.0),
+                false,
+                [0, 1],
+            );
+            textbox.input_text(systems, value.to_string());
+            self.editor_textbox.push(textbox);
+        }
+    }
+
+    // The placements are the last option of the zone tab
+    pub fn is_placement_open(&self, mapview: &MapView) -> bool {
+        self.current_tab == TAB_ZONE
+            && self.current_tab_data as usize == mapview.map_zone_setting.len()
+    }
+
+    fn open_placement_settings(
+        &mut self,
+        systems: &mut DrawSetting,
+        mapview: &MapView,
+        pos: Vec2,
+    ) {
+        // The selection is dropped if the placement is gone, such as on undo
+        if let Some(tile_pos) = self.selected_entity {
+            match mapview.get_entity(tile_pos) {
+                Some(entity) => self.entity_setting = entity.clone(),
+                None => self.selected_entity = None,
+            }
+        }
+        let entity = self.entity_setting.clone();
+
+        // Type, Facing
+        let options = [
+            (
+                "Type",
+                EntityKind::KINDS
+                    .iter()
+                    .map(|kind| kind.as_str().to_string())
+                    .collect::<Vec<String>>(),
+                EntityKind::KINDS
+                    .iter()
+                    .position(|kind| *kind == entity.kind)
+                    .unwrap_or_default(),
+            ),
+            (
+                "Facing",
+                FacingDir::DIRS
+                    .iter()
+                    .map(|dir| dir.as_str().to_string())
+                    .collect::<Vec<String>>(),
+                FacingDir::DIRS
+                    .iter()
+                    .position(|dir| *dir == entity.dir)
+                    .unwrap_or_default(),
+            ),
+        ];
+        for (index, (msg, list, selected)) in options.into_iter().enumerate() {
+            let y = pos.y + 368.0 - (index * 28) as f32;
+            let label =
+                create_zone_label(systems, Vec2::new(pos.x + 10.0, y), msg);
+            self.editor_label.push(label);
+            let mut selection_box = SelectionBox::new(
+                systems,
+                Vec2::new(pos.x + 85.0, y - 2.0),
+                [
+                    ORDER_PROPERTIES_BUTTON,
+                    ORDER_PROPERTIES_BUTTON_TEXT,
+                    ORDER_DROPDOWN_WINDOW,
+                    ORDER_DROPDOWN_SELECTION,
+                    ORDER_DROPDOWN_TEXT,
+                    ORDER_DROPDOWN_SCROLLBAR,
+                ],
+                105.0,
+                list,
+                0,
+            );
+            selection_box.set_selected(systems, selected);
+            self.editor_selectionbox.push(selection_box);
+        }
+
+        // NPC ID or Object ID
+        match entity.kind {
+            EntityKind::Npc => {
+                let label = create_zone_label(
                     systems,
-                    mapview.map_zone_setting[zone_index as usize].npc_id[i]
-                        .unwrap()
-                        .to_string(),
+                    pos + Vec2::new(10.0, 312.0),
+                    "NPC",
                 );
-            } else {
-                self.editor_textbox[i + 1].input_text(systems, String::new());
+                self.editor_label.push(label);
+                let selection_box = create_game_data_box(
+                    systems,
+                    pos + Vec2::new(10.0, 284.0),
+                    180.0,
+                    GameDataKind::Npc,
+                    Some(entity.index),
+                    false,
+                );
+                self.editor_selectionbox.push(selection_box);
+            }
+            EntityKind::Object => {
+                let label = create_zone_label(
+                    systems,
+                    pos + Vec2::new(10.0, 312.0),
+                    "Object ID",
+                );
+                self.editor_label.push(label);
+                let mut textbox = Textbox::new(
+                    systems,
+                    Vec3::new(
+                        pos.x + 110.0,
+                        pos.y + 312.0,
+                        ORDER_ATTRIBUTE_TEXTBOX,
+                    ),
+                    Vec2::new(80.0, 22.0),
+                    false,
+                    [0, 1],
+                );
+                textbox.input_text(systems, entity.index.to_string());
+                self.editor_textbox.push(textbox);
             }
         }
+
+        // Properties, one key=value pair on each line
+        let label = create_zone_label(
+            systems,
+            pos + Vec2::new(10.0, 252.0),
+            "Properties",
+        );
+        self.editor_label.push(label);
+        let mut text_editor = TextEditor::new(
+            systems,
+            Vec3::new(pos.x + 10.0, pos.y + 112.0, ORDER_ATTRIBUTE_TEXTBOX),
+            Vec2::new(180.0, 122.0),
+            1000,
+            [0, 1],
+        );
+        text_editor.input_text(systems, entity.properties_to_text());
+        self.editor_text_editor.push(text_editor);
+
+        let status = match self.selected_entity {
+            Some(tile_pos) => {
+                format!("Selected X: {} Y: {}", tile_pos.x, tile_pos.y)
+            }
+            None => "Click on the map to place".to_string(),
+        };
+        let label =
+            create_zone_label(systems, pos + Vec2::new(10.0, 82.0), &status);
+        self.editor_label.push(label);
+
+        // New Placement, Delete Placement
+        for (index, msg) in ["New", "Delete"].iter().enumerate() {
+            self.editor_button.push(Button::new(
+                systems,
+                systems.resource.preference_button.allocation,
+                msg,
+                Vec2::new(pos.x + 14.0 + (index * 92) as f32, pos.y + 48.0),
+                Vec2::new(80.0, 22.0),
+                [ORDER_OPTION_BUTTON, ORDER_OPTION_BUTTON_TEXT],
+                2.0,
+                [0, 1],
+            ));
+        }
+    }
+
+    // Read the placement back from the settings
+    pub fn get_entity_setting(&self) -> MapEntity {
+        let mut entity = self.entity_setting.clone();
+        if let Some(selection_box) = self.editor_selectionbox.first() {
+            entity.kind = EntityKind::KINDS
+                .get(selection_box.selected_index)
+                .copied()
+                .unwrap_or_default();
+        }
+        if let Some(selection_box) = self.editor_selectionbox.get(1) {
+            entity.dir = FacingDir::DIRS
+                .get(selection_box.selected_index)
+                .copied()
+                .unwrap_or_default();
+        }
+        // The ID widget is built for the type that the panel was opened with
+        if entity.kind == self.entity_setting.kind {
+            let index = match entity.kind {
+                EntityKind::Npc => self
+                    .editor_selectionbox
+                    .get(2)
+                    .and_then(|selection_box| selection_box.get_value()),
+                EntityKind::Object => self
+                    .editor_textbox
+                    .first()
+                    .and_then(|textbox| textbox.data.parse::<u64>().ok()),
+            };
+            entity.index = index.unwrap_or_default();
+        } else {
+            entity.index = 0;
+        }
+        if let Some(text_editor) = self.editor_text_editor.first() {
+            entity.properties =
+                TriggerData::params_from_text(&text_editor.data);
+        }
+        entity
+    }
+
+    // Read the zone settings back from the textboxes and selection boxes
+    pub fn get_zone_setting(
+        &self,
+        mapview: &MapView,
+    ) -> Option<MapZoneSetting> {
+        let mut setting = mapview
+            .map_zone_setting
+            .get(self.current_tab_data as usize)?
+            .clone();
+        let get_value = |index: usize| {
+            self.editor_textbox
+                .get(index)
+                .and_then(|textbox| textbox.data.parse::<u64>().ok())
+                .unwrap_or_default()
+        };
+        if let Some(textbox) = self.editor_textbox.first() {
+            setting.name = textbox.data.clone();
+        }
+        setting.max_npc = get_value(1);
+        if let Some(npc) = setting.npcs.get_mut(self.zone_npc_index) {
+            if let Some(npc_id) = self
+                .editor_selectionbox
+                .get(1)
+                .and_then(|selection_box| selection_box.get_value())
+            {
+                npc.npc_id = npc_id;
+            }
+            if self.editor_textbox.len() > 2 {
+                npc.weight = get_value(2) as u32;
+                npc.level_min = get_value(3) as u32;
+                npc.level_max = get_value(4) as u32;
+                npc.respawn = get_value(5);
+            }
+        }
+        Some(setting)
     }
 
     pub fn select_textbox(
@@ -724,77 +1264,85 @@ impl Interface {
         }
     }
 
-    pub fn get_attribute_setting(&mut self) -> MapAttribute {
-        // We adjust the index of the selected attribute as the index 0 is for the walkable attribute
-        // which is not available on the tab data selection
+    pub fn select_text_editor(
+        &mut self,
+        systems: &mut DrawSetting,
+        mouse_pos: Vec2,
+    ) {
+        self.editor_text_editor.iter_mut().for_each(|text_editor| {
+            if text_editor.in_area(mouse_pos) {
+                text_editor.set_select(systems, true);
+                text_editor.click_text(systems, mouse_pos, false);
+            } else {
+                text_editor.set_select(systems, false);
+            }
+        });
+    }
+
+    // Build the attribute from the values of the generated settings
+    pub fn get_attribute_setting(
+        &mut self,
+        systems: &mut DrawSetting,
+    ) -> MapAttribute {
+        let schema = &systems.attribute_schema;
         let attribute =
-            MapAttribute::convert_to_plain_enum(self.current_tab_data + 1);
-        match attribute {
-            MapAttribute::Warp(_) => {
-                let (mut mx, mut my, mut mg, mut tx, mut ty) =
-                    (0_i32, 0_i32, 0_u64, 0_u32, 0_u32);
-                for (index, textbox) in self.editor_textbox.iter().enumerate() {
-                    let value = textbox.data.parse::<i64>().unwrap_or_default();
-                    match index {
-                        1 => {
-                            my = value as i32;
-                        }
-                        2 => {
-                            mg = value as u64;
-                        }
-                        3 => {
-                            tx = value as u32;
-                        }
-                        4 => {
-                            ty = value as u32;
-                        }
-                        _ => {
-                            mx = value as i32;
-                        }
-                    }
+            match schema.attributes.get(self.current_tab_data as usize) {
+                Some(attribute) => attribute,
+                None => return MapAttribute::Walkable,
+            };
+        let data: Vec<InsertTypes> = attribute
+            .fields
+            .iter()
+            .zip(self.attribute_fields.iter())
+            .map(|(field, widget)| match (&field.kind, widget) {
+                (FieldKind::Int, FieldWidget::Textbox(index)) => {
+                    InsertTypes::Int(
+                        self.editor_textbox[*index]
+                            .data
+                            .parse::<i64>()
+                            .unwrap_or_default(),
+                    )
                 }
-                MapAttribute::Warp(WarpData {
-                    map_x: mx,
-                    map_y: my,
-                    map_group: mg,
-                    tile_x: tx,
-                    tile_y: ty,
-                })
-            }
-            MapAttribute::Sign(_) => {
-                let text = self.editor_textbox[0].data.clone();
-                MapAttribute::Sign(text)
-            }
-            MapAttribute::ItemSpawn(_) => {
-                let (itemindex, value, timer) = (
-                    self.editor_textbox[0]
-                        .data
-                        .parse::<u32>()
-                        .unwrap_or_default(),
-                    self.editor_textbox[1]
-                        .data
-                        .parse::<u16>()
-                        .unwrap_or_default(),
-                    self.editor_textbox[2]
-                        .data
-                        .parse::<u64>()
-                        .unwrap_or_default(),
-                );
-                MapAttribute::ItemSpawn(ItemSpawnData {
-                    index: itemindex,
-                    amount: value,
-                    timer,
-                })
-            }
-            MapAttribute::Shop(_) => {
-                let shop_index = self.editor_textbox[0]
-                    .data
-                    .parse::<u16>()
-                    .unwrap_or_default();
-                MapAttribute::Shop(shop_index)
-            }
-            _ => attribute,
-        }
+                (FieldKind::UInt, FieldWidget::Textbox(index)) => {
+                    InsertTypes::UInt(
+                        self.editor_textbox[*index]
+                            .data
+                            .parse::<u64>()
+                            .unwrap_or_default(),
+                    )
+                }
+                (FieldKind::String, FieldWidget::Textbox(index)) => {
+                    InsertTypes::Str(self.editor_textbox[*index].data.clone())
+                }
+                (FieldKind::String, FieldWidget::TextEditor(index)) => {
+                    InsertTypes::Str(
+                        self.editor_text_editor[*index].data.clone(),
+                    )
+                }
+                (FieldKind::Bool, FieldWidget::Checkbox(index)) => {
+                    InsertTypes::Bool(self.editor_checkbox[*index].is_select)
+                }
+                (FieldKind::GameData(_), FieldWidget::SelectionBox(index)) => {
+                    InsertTypes::UInt(
+                        self.editor_selectionbox[*index]
+                            .get_value()
+                            .unwrap_or_default(),
+                    )
+                }
+                (FieldKind::Enum(_), FieldWidget::SelectionBox(index)) => {
+                    let selectionbox = &self.editor_selectionbox[*index];
+                    InsertTypes::Str(
+                        selectionbox
+                            .list
+                            .get(selectionbox.selected_index)
+                            .cloned()
+                            .unwrap_or_default(),
+                    )
+                }
+                (kind, _) => schema.default_value(kind),
+            })
+            .collect();
+        MapAttribute::from_data(&attribute.name, &data)
     }
 
     pub fn open_dialog(
@@ -821,6 +1369,17 @@ impl Interface {
 }
 
 // Function to create a tool button
+fn create_zone_label(systems: &mut DrawSetting, pos: Vec2, msg: &str) -> usize {
+    let mut text = create_basic_label(
+        systems,
+        Vec3::new(pos.x, pos.y, ORDER_ATTRIBUTE_LABEL),
+        Vec2::new(100.0, 20.0),
+        Color::rgba(180, 180, 180, 255),
+    );
+    text.set_text(&mut systems.renderer, msg, Attrs::new(), Shaping::Advanced);
+    systems.gfx.add_text(text, 1)
+}
+
 fn create_tool_button(
     resource: usize,
     systems: &mut DrawSetting,
@@ -16,6 +16,9 @@ pub enum DialogType {
     ExitConfirm,
     MapSave,
     MapLoad,
+    TiledImport,
+    TiledExport,
+    MapDiff,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -33,6 +36,23 @@ pub enum DialogData {
     MapList(IndexMap<String, (i32, i32, i64)>),
 }
 
+fn add_dialog_label(
+    systems: &mut DrawSetting,
+    pos: Vec2,
+    width: f32,
+    msg: &str,
+) -> usize {
+    let mut text = create_label(
+        systems,
+        Vec3::new(pos.x, pos.y, ORDER_DIALOG_CONTENT_TEXT),
+        Vec2::new(width, 20.0),
+        Bounds::new(pos.x, pos.y, pos.x + width, pos.y + 20.0),
+        Color::rgba(200, 200, 200, 255),
+    );
+    text.set_text(&mut systems.renderer, msg, Attrs::new(), Shaping::Advanced);
+    systems.gfx.add_text(text, 3)
+}
+
 pub struct DialogButton {
     pub image: usize,
     pub text: usize,
@@ -174,13 +194,16 @@ impl Dialog {
             match dialog_type {
                 DialogType::ExitConfirm => 384.0,
                 DialogType::MapSave => 456.0,
-                DialogType::MapLoad => 456.0,
+                DialogType::MapLoad | DialogType::MapDiff => 456.0,
+                DialogType::TiledImport | DialogType::TiledExport => 456.0,
                 _ => 384.0,
             },
             match dialog_type {
                 DialogType::ExitConfirm => 108.0,
                 DialogType::MapSave => 201.0,
-                DialogType::MapLoad => 144.0,
+                DialogType::MapLoad | DialogType::MapDiff => 144.0,
+                DialogType::TiledImport => 178.0,
+                DialogType::TiledExport => 212.0,
                 _ => 108.0,
             },
         );
@@ -192,14 +215,20 @@ impl Dialog {
         let message_pos_y = match dialog_type {
             DialogType::ExitConfirm => window_pos.y + 62.0,
             DialogType::MapSave => window_pos.y + 155.0,
-            DialogType::MapLoad => window_pos.y + 98.0,
+            DialogType::MapLoad | DialogType::MapDiff => window_pos.y + 98.0,
+            DialogType::TiledImport => window_pos.y + 132.0,
+            DialogType::TiledExport => window_pos.y + 166.0,
             _ => 62.0,
         };
         let button_pos = Vec2::new(
             match dialog_type {
                 DialogType::ExitConfirm => window_pos.x + 84.0,
-                DialogType::MapLoad => window_pos.x + 120.0,
-                DialogType::MapSave => window_pos.x + 64.0,
+                DialogType::MapLoad
+                | DialogType::TiledImport
+                | DialogType::TiledExport => window_pos.x + 120.0,
+                DialogType::MapSave | DialogType::MapDiff => {
+                    window_pos.x + 64.0
+                }
                 _ => window_pos.x + 84.0,
             },
             window_pos.y + 18.0,
@@ -250,11 +279,43 @@ impl Dialog {
                     ),
                 ]
             }
-            DialogType::MapLoad => {
+            DialogType::MapDiff => {
+                vec![
+                    DialogButton::new(
+                        systems,
+                        "Compare",
+                        button_pos,
+                        Vec2::new(103.0, 20.0),
+                        DialogButtonType::Confirm,
+                    ),
+                    DialogButton::new(
+                        systems,
+                        "Backup",
+                        button_pos + Vec2::new(113.0, 0.0),
+                        Vec2::new(103.0, 20.0),
+                        DialogButtonType::Decline,
+                    ),
+                    DialogButton::new(
+                        systems,
+                        "Cancel",
+                        button_pos + Vec2::new(226.0, 0.0),
+                        Vec2::new(103.0, 20.0),
+                        DialogButtonType::Cancel,
+                    ),
+                ]
+            }
+            DialogType::MapLoad
+            | DialogType::TiledImport
+            | DialogType::TiledExport => {
+                let confirm_text = match dialog_type {
+                    DialogType::MapLoad => "Load",
+                    DialogType::TiledImport => "Import",
+                    _ => "Export",
+                };
                 vec![
                     DialogButton::new(
                         systems,
-                        "Load",
+                        confirm_text,
                         button_pos,
                         Vec2::new(103.0, 20.0),
                         DialogButtonType::Confirm,
@@ -298,6 +359,15 @@ impl Dialog {
             DialogType::MapLoad => {
                 "Please enter the map location that you would like to load"
             }
+            DialogType::TiledImport => {
+                "Enter the Tiled file and the map that it will start on"
+            }
+            DialogType::TiledExport => {
+                "Enter the top left map and the number of maps to export"
+            }
+            DialogType::MapDiff => {
+                "Compare with a map file, or with the saved map if it is empty"
+            }
             _ => "Error",
         };
 
@@ -348,6 +418,11 @@ impl Dialog {
 
         // Content
         let mut scrollbar_x = window_pos.x;
+        let file_y = match dialog_type {
+            DialogType::TiledExport => window_pos.y + 134.0,
+            DialogType::MapDiff => window_pos.y + 66.0,
+            _ => window_pos.y + 100.0,
+        };
         let content_image = match dialog_type {
             DialogType::MapSave => {
                 let label_box_size = Vec2::new(364.0, 85.0);
@@ -433,7 +508,9 @@ impl Dialog {
                 }
                 data
             }
-            DialogType::MapLoad => {
+            DialogType::MapLoad
+            | DialogType::TiledImport
+            | DialogType::TiledExport => {
                 // Text Size = X[10] Y[10] Group[45]
                 let textbox_total_size = 240.0; // [10][5][50][5][10][5][50][5][45][5][50]
                 let content_pos = Vec2::new(
@@ -508,18 +585,50 @@ impl Dialog {
                     Attrs::new(),
                     Shaping::Advanced,
                 );
-                vec![
+                let mut list = vec![
                     systems.gfx.add_text(mapx, 3),
                     systems.gfx.add_text(mapy, 3),
                     systems.gfx.add_text(mapgroup, 3),
-                ]
+                ];
+                if dialog_type != DialogType::MapLoad {
+                    list.push(add_dialog_label(
+                        systems,
+                        Vec2::new(window_pos.x + 28.0, file_y),
+                        35.0,
+                        "File",
+                    ));
+                }
+                if dialog_type == DialogType::TiledExport {
+                    let size_y = window_pos.y + 100.0;
+                    list.push(add_dialog_label(
+                        systems,
+                        Vec2::new(content_pos.x, size_y),
+                        10.0,
+                        "W",
+                    ));
+                    list.push(add_dialog_label(
+                        systems,
+                        Vec2::new(content_pos.x + 70.0, size_y),
+                        10.0,
+                        "H",
+                    ));
+                }
+                list
             }
+            DialogType::MapDiff => vec![add_dialog_label(
+                systems,
+                Vec2::new(window_pos.x + 28.0, file_y),
+                35.0,
+                "File",
+            )],
             _ => Vec::with_capacity(0),
         };
 
         // Textbox
         let editor_textbox = match dialog_type {
-            DialogType::MapLoad => {
+            DialogType::MapLoad
+            | DialogType::TiledImport
+            | DialogType::TiledExport => {
                 let textbox_total_size = 240.0; // [10][50][5][10][50][5][45][50]
                 let content_pos = Vec2::new(
                     window_pos.x
@@ -527,7 +636,7 @@ impl Dialog {
                     window_pos.y + 66.0,
                 )
                 .floor();
-                vec![
+                let mut list = vec![
                     Textbox::new(
                         systems,
                         Vec3::new(
@@ -561,8 +670,50 @@ impl Dialog {
                         false,
                         [2, 3],
                     ),
-                ]
+                ];
+                // The path of the Tiled file is placed above the location
+                if dialog_type != DialogType::MapLoad {
+                    list.push(Textbox::new(
+                        systems,
+                        Vec3::new(
+                            window_pos.x + 68.0,
+                            file_y,
+                            ORDER_DIALOG_CONTENT_IMG1,
+                        ),
+                        Vec2::new(360.0, 24.0),
+                        false,
+                        [2, 3],
+                    ));
+                }
+                // Number of maps toward the right and the bottom
+                if dialog_type == DialogType::TiledExport {
+                    for offset_x in [15.0, 85.0] {
+                        list.push(Textbox::new(
+                            systems,
+                            Vec3::new(
+                                content_pos.x + offset_x,
+                                window_pos.y + 100.0,
+                                ORDER_DIALOG_CONTENT_IMG1,
+                            ),
+                            Vec2::new(50.0, 24.0),
+                            false,
+                            [2, 3],
+                        ));
+                    }
+                }
+                list
             }
+            DialogType::MapDiff => vec![Textbox::new(
+                systems,
+                Vec3::new(
+                    window_pos.x + 68.0,
+                    file_y,
+                    ORDER_DIALOG_CONTENT_IMG1,
+                ),
+                Vec2::new(360.0, 24.0),
+                false,
+                [2, 3],
+            )],
             _ => {
                 vec![]
             }
@@ -685,7 +836,13 @@ impl Dialog {
     }
 
     pub fn select_text(&mut self, systems: &mut DrawSetting, mouse_pos: Vec2) {
-        if self.dialog_type != DialogType::MapLoad {
+        if !matches!(
+            self.dialog_type,
+            DialogType::MapLoad
+                | DialogType::TiledImport
+                | DialogType::TiledExport
+                | DialogType::MapDiff
+        ) {
             return;
         }
 
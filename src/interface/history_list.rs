@@ -0,0 +1,299 @@
+use cosmic_text::Attrs;
+use graphics::*;
+
+use crate::{
+    collection::*,
+    interface::{label::*, scrollbar::*},
+    map::EditHistory,
+    DrawSetting,
+};
+
+use super::tileset_list::SelectButton;
+
+const MAX_VISIBLE_LIST: usize = 18;
+
+pub struct HistoryList {
+    pub visible: bool,
+    pub bg: Vec<usize>,
+    pub header: usize,
+    pub selection_buttons: Vec<SelectButton>,
+    pub texts: Vec<usize>,
+    start_view_index: usize,
+    // Amount of entries on the list, including the starting point
+    entry_count: usize,
+    pub scrollbar: Scrollbar,
+}
+
+impl HistoryList {
+    pub fn new(systems: &mut DrawSetting) -> Self {
+        let mut bg1 = Rect::new(&mut systems.renderer, 0);
+        bg1.set_size(Vec2::new(200.0, 398.0))
+            .set_position(Vec3::new(735.0, 369.0, ORDER_HISTORYLIST))
+            .set_color(Color::rgba(50, 50, 50, 255))
+            .set_use_camera(true);
+        let mut bg2 = Rect::new(&mut systems.renderer, 0);
+        bg2.set_size(Vec2::new(200.0, 24.0))
+            .set_position(Vec3::new(735.0, 767.0, ORDER_HISTORYLIST))
+            .set_color(Color::rgba(25, 25, 25, 255))
+            .set_use_camera(true);
+        let mut bg3 = Rect::new(&mut systems.renderer, 0);
+        bg3.set_size(Vec2::new(8.0, 377.0))
+            .set_position(Vec3::new(924.0, 381.0, ORDER_HISTORYLIST_SCROLL_BG))
+            .set_color(Color::rgba(30, 30, 30, 255))
+            .set_use_camera(true);
+
+        let mut text = create_basic_label(
+            systems,
+            Vec3::new(742.0, 769.0, ORDER_HISTORYLIST_LABEL),
+            Vec2::new(186.0, 20.0),
+            Color::rgba(180, 180, 180, 255),
+        );
+        text.set_text(
+            &mut systems.renderer,
+            "Edit History",
+            Attrs::new(),
+            Shaping::Advanced,
+        );
+        let header = systems.gfx.add_text(text, 1);
+        systems.gfx.set_visible(header, false);
+
+        let mut texts = Vec::with_capacity(MAX_VISIBLE_LIST);
+        let mut selection_buttons = Vec::with_capacity(MAX_VISIBLE_LIST);
+        for index in 0..MAX_VISIBLE_LIST {
+            let mut image = Image::new(
+                Some(systems.resource.tileset_list_select.allocation),
+                &mut systems.renderer,
+                0,
+            );
+            image.pos = Vec3::new(
+                bg1.position.x + 3.0,
+                bg1.position.y + 369.0 - (21.0 * index as f32),
+                ORDER_HISTORYLIST_BUTTON,
+            );
+            image.hw = Vec2::new(183.0, 20.0);
+            image.uv = Vec4::new(0.0, 0.0, 183.0, 20.0);
+            let button = SelectButton {
+                image: systems.gfx.add_image(image, 0),
+                in_hover: false,
+                is_selected: false,
+            };
+            systems.gfx.set_visible(button.image, false);
+            selection_buttons.push(button);
+
+            let text = create_basic_label(
+                systems,
+                Vec3::new(
+                    bg1.position.x + 7.0,
+                    bg1.position.y + 369.0 - (21.0 * index as f32),
+                    ORDER_HISTORYLIST_LABEL,
+                ),
+                Vec2::new(175.0, 20.0),
+                Color::rgba(180, 180, 180, 255),
+            );
+            let index = systems.gfx.add_text(text, 1);
+            systems.gfx.set_visible(index, false);
+            texts.push(index);
+        }
+
+        let scrollbar = Scrollbar::new(
+            systems,
+            Vec3::new(
+                bg1.position.x + 188.0,
+                bg1.position.y + 389.0,
+                ORDER_HISTORYLIST_SCROLLBAR,
+            ),
+            0,
+            377,
+            20,
+            0,
+        );
+
+        let bg = vec![
+            systems.gfx.add_rect(bg1, 0),
+            systems.gfx.add_rect(bg2, 0),
+            systems.gfx.add_rect(bg3, 0),
+        ];
+        bg.iter().for_each(|bg| {
+            systems.gfx.set_visible(*bg, false);
+        });
+
+        Self {
+            visible: false,
+            bg,
+            header,
+            selection_buttons,
+            texts,
+            start_view_index: 0,
+            entry_count: 1,
+            scrollbar,
+        }
+    }
+
+    // The newest edit is placed on top of the list, the undone edits
+    // are kept above the current position until a new edit is made
+    pub fn refresh(
+        &mut self,
+        systems: &mut DrawSetting,
+        history: &mut EditHistory,
+    ) {
+        history.changed = false;
+
+        let entry_count = history.len() + 1;
+        if self.entry_count != entry_count {
+            self.entry_count = entry_count;
+            self.start_view_index = 0;
+            self.scrollbar.update_scroll_max_value(
+                systems,
+                entry_count.max(MAX_VISIBLE_LIST) - MAX_VISIBLE_LIST,
+            );
+        }
+        self.update_list(systems, history);
+    }
+
+    pub fn update_list(
+        &mut self,
+        systems: &mut DrawSetting,
+        history: &EditHistory,
+    ) {
+        for index in 0..MAX_VISIBLE_LIST {
+            let entry = self.start_view_index + index;
+            let has_entry = entry < self.entry_count;
+            if self.visible {
+                systems.gfx.set_visible(
+                    self.selection_buttons[index].image,
+                    has_entry,
+                );
+                systems.gfx.set_visible(self.texts[index], has_entry);
+            }
+            if !has_entry {
+                continue;
+            }
+
+            let position = self.entry_count - 1 - entry;
+            let name = match history.get_group(position) {
+                Some(group) => group.name.as_str(),
+                None => "Start",
+            };
+            systems.gfx.set_text(
+                &mut systems.renderer,
+                self.texts[index],
+                name,
+            );
+            systems.gfx.set_color(
+                self.texts[index],
+                if position > history.position() {
+                    Color::rgba(110, 110, 110, 255)
+                } else {
+                    Color::rgba(180, 180, 180, 255)
+                },
+            );
+            self.selection_buttons[index]
+                .set_select(systems, position == history.position());
+        }
+    }
+
+    pub fn update_scroll(&mut self, scroll_index: usize) -> bool {
+        if !self.visible {
+            return false;
+        }
+        if self.start_view_index != scroll_index {
+            self.start_view_index = scroll_index;
+            return true;
+        }
+        false
+    }
+
+    pub fn in_list(
+        &mut self,
+        systems: &mut DrawSetting,
+        mouse_pos: Vec2,
+    ) -> bool {
+        if !self.visible {
+            return false;
+        }
+        let (pos, size) = (
+            systems.gfx.get_pos(self.bg[0]),
+            systems.gfx.get_size(self.bg[0]),
+        );
+        mouse_pos.x >= pos.x
+            && mouse_pos.x <= pos.x + size.x
+            && mouse_pos.y >= pos.y
+            && mouse_pos.y <= pos.y + size.y + 24.0
+    }
+
+    // Return the history position of the selected entry
+    pub fn select_list(
+        &mut self,
+        systems: &mut DrawSetting,
+        mouse_pos: Vec2,
+    ) -> Option<usize> {
+        if !self.visible {
+            return None;
+        }
+        let index = self.selection_buttons.iter().position(|button| {
+            let (pos, size) = (
+                systems.gfx.get_pos(button.image),
+                systems.gfx.get_size(button.image),
+            );
+            mouse_pos.x >= pos.x
+                && mouse_pos.x <= pos.x + size.x
+                && mouse_pos.y >= pos.y
+                && mouse_pos.y <= pos.y + size.y
+        })?;
+        let entry = self.start_view_index + index;
+        if entry < self.entry_count {
+            Some(self.entry_count - 1 - entry)
+        } else {
+            None
+        }
+    }
+
+    pub fn hover_selection(
+        &mut self,
+        systems: &mut DrawSetting,
+        mouse_pos: Vec2,
+    ) {
+        if !self.visible {
+            return;
+        }
+        for index in 0..self.selection_buttons.len() {
+            let (pos, size) = (
+                systems.gfx.get_pos(self.selection_buttons[index].image),
+                systems.gfx.get_size(self.selection_buttons[index].image),
+            );
+            let in_hover = mouse_pos.x >= pos.x
+                && mouse_pos.x <= pos.x + size.x
+                && mouse_pos.y >= pos.y
+                && mouse_pos.y <= pos.y + size.y;
+            self.selection_buttons[index].set_hover(systems, in_hover);
+        }
+    }
+
+    pub fn show(&mut self, systems: &mut DrawSetting, history: &EditHistory) {
+        if self.visible {
+            return;
+        }
+        self.visible = true;
+        self.bg.iter().for_each(|bg| {
+            systems.gfx.set_visible(*bg, true);
+        });
+        systems.gfx.set_visible(self.header, true);
+        self.scrollbar.show(systems);
+        self.update_list(systems, history);
+    }
+
+    pub fn hide(&mut self, systems: &mut DrawSetting) {
+        self.visible = false;
+        self.scrollbar.hide(systems);
+        self.bg.iter().for_each(|bg| {
+            systems.gfx.set_visible(*bg, false);
+        });
+        systems.gfx.set_visible(self.header, false);
+        self.texts.iter().for_each(|text| {
+            systems.gfx.set_visible(*text, false);
+        });
+        self.selection_buttons.iter_mut().for_each(|button| {
+            systems.gfx.set_visible(button.image, false);
+        });
+    }
+}
@@ -18,6 +18,10 @@ pub enum EditorKey {
     KeyErase,
     KeyFill,
     KeyEyetool,
+    KeyZoomIn,
+    KeyZoomOut,
+    KeyZoomReset,
+    KeyDiff,
     Count,
 }
 
@@ -32,6 +36,10 @@ impl EditorKey {
             5 => "Erase",
             6 => "Fill",
             7 => "Eyetool",
+            8 => "Zoom In",
+            9 => "Zoom Out",
+            10 => "Reset Zoom",
+            11 => "Map Diff",
             _ => "Error",
         }
     }
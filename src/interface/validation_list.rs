@@ -0,0 +1,271 @@
+use graphics::*;
+
+use crate::{
+    collection::*,
+    interface::{label::*, scrollbar::*},
+    validation::*,
+    DrawSetting,
+};
+
+use super::tileset_list::SelectButton;
+
+const MAX_VISIBLE_LIST: usize = 18;
+
+pub struct ValidationList {
+    pub visible: bool,
+    pub bg: Vec<usize>,
+    pub header: usize,
+    pub selection_buttons: Vec<SelectButton>,
+    pub texts: Vec<usize>,
+    pub issues: Vec<ValidationIssue>,
+    start_view_index: usize,
+    pub scrollbar: Scrollbar,
+}
+
+impl ValidationList {
+    pub fn new(systems: &mut DrawSetting) -> Self {
+        let mut bg1 = Rect::new(&mut systems.renderer, 0);
+        bg1.set_size(Vec2::new(400.0, 398.0))
+            .set_position(Vec3::new(535.0, 369.0, ORDER_VALIDATIONLIST))
+            .set_color(Color::rgba(50, 50, 50, 255))
+            .set_use_camera(true);
+        let mut bg2 = Rect::new(&mut systems.renderer, 0);
+        bg2.set_size(Vec2::new(400.0, 24.0))
+            .set_position(Vec3::new(535.0, 767.0, ORDER_VALIDATIONLIST))
+            .set_color(Color::rgba(25, 25, 25, 255))
+            .set_use_camera(true);
+        let mut bg3 = Rect::new(&mut systems.renderer, 0);
+        bg3.set_size(Vec2::new(8.0, 377.0))
+            .set_position(Vec3::new(
+                924.0,
+                381.0,
+                ORDER_VALIDATIONLIST_SCROLL_BG,
+            ))
+            .set_color(Color::rgba(30, 30, 30, 255))
+            .set_use_camera(true);
+
+        let text = create_basic_label(
+            systems,
+            Vec3::new(542.0, 769.0, ORDER_VALIDATIONLIST_LABEL),
+            Vec2::new(386.0, 20.0),
+            Color::rgba(180, 180, 180, 255),
+        );
+        let header = systems.gfx.add_text(text, 1);
+        systems.gfx.set_visible(header, false);
+
+        let mut texts = Vec::with_capacity(MAX_VISIBLE_LIST);
+        let mut selection_buttons = Vec::with_capacity(MAX_VISIBLE_LIST);
+        for index in 0..MAX_VISIBLE_LIST {
+            let mut image = Image::new(
+                Some(systems.resource.tileset_list_select.allocation),
+                &mut systems.renderer,
+                0,
+            );
+            image.pos = Vec3::new(
+                bg1.position.x + 3.0,
+                bg1.position.y + 369.0 - (21.0 * index as f32),
+                ORDER_VALIDATIONLIST_BUTTON,
+            );
+            image.hw = Vec2::new(383.0, 20.0);
+            image.uv = Vec4::new(0.0, 0.0, 183.0, 20.0);
+            let button = SelectButton {
+                image: systems.gfx.add_image(image, 0),
+                in_hover: false,
+                is_selected: false,
+            };
+            systems.gfx.set_visible(button.image, false);
+            selection_buttons.push(button);
+
+            let text = create_basic_label(
+                systems,
+                Vec3::new(
+                    bg1.position.x + 7.0,
+                    bg1.position.y + 369.0 - (21.0 * index as f32),
+                    ORDER_VALIDATIONLIST_LABEL,
+                ),
+                Vec2::new(375.0, 20.0),
+                Color::rgba(180, 180, 180, 255),
+            );
+            let index = systems.gfx.add_text(text, 1);
+            systems.gfx.set_visible(index, false);
+            texts.push(index);
+        }
+
+        let scrollbar = Scrollbar::new(
+            systems,
+            Vec3::new(
+                bg1.position.x + 388.0,
+                bg1.position.y + 389.0,
+                ORDER_VALIDATIONLIST_SCROLLBAR,
+            ),
+            0,
+            377,
+            20,
+            0,
+        );
+
+        let bg = vec![
+            systems.gfx.add_rect(bg1, 0),
+            systems.gfx.add_rect(bg2, 0),
+            systems.gfx.add_rect(bg3, 0),
+        ];
+        bg.iter().for_each(|bg| {
+            systems.gfx.set_visible(*bg, false);
+        });
+
+        Self {
+            visible: false,
+            bg,
+            header,
+            selection_buttons,
+            texts,
+            issues: Vec::new(),
+            start_view_index: 0,
+            scrollbar,
+        }
+    }
+
+    pub fn set_issues(
+        &mut self,
+        systems: &mut DrawSetting,
+        title: &str,
+        issues: Vec<ValidationIssue>,
+    ) {
+        self.issues = issues;
+        self.start_view_index = 0;
+        self.scrollbar.update_scroll_max_value(
+            systems,
+            self.issues.len().max(MAX_VISIBLE_LIST) - MAX_VISIBLE_LIST,
+        );
+
+        let header = if self.issues.is_empty() {
+            format!("{}: No Problem Found", title)
+        } else {
+            format!("{}: {} Problem(s)", title, self.issues.len())
+        };
+        systems
+            .gfx
+            .set_text(&mut systems.renderer, self.header, &header);
+        self.update_list(systems);
+    }
+
+    pub fn update_list(&mut self, systems: &mut DrawSetting) {
+        for index in 0..MAX_VISIBLE_LIST {
+            let entry = self.start_view_index + index;
+            let issue = self.issues.get(entry);
+            if self.visible {
+                systems.gfx.set_visible(
+                    self.selection_buttons[index].image,
+                    issue.is_some(),
+                );
+                systems.gfx.set_visible(self.texts[index], issue.is_some());
+            }
+            if let Some(issue) = issue {
+                systems.gfx.set_text(
+                    &mut systems.renderer,
+                    self.texts[index],
+                    &issue.short_description(),
+                );
+            }
+        }
+    }
+
+    pub fn update_scroll(&mut self, scroll_index: usize) -> bool {
+        if !self.visible {
+            return false;
+        }
+        if self.start_view_index != scroll_index {
+            self.start_view_index = scroll_index;
+            return true;
+        }
+        false
+    }
+
+    pub fn in_list(
+        &mut self,
+        systems: &mut DrawSetting,
+        mouse_pos: Vec2,
+    ) -> bool {
+        if !self.visible {
+            return false;
+        }
+        let (pos, size) = (
+            systems.gfx.get_pos(self.bg[0]),
+            systems.gfx.get_size(self.bg[0]),
+        );
+        mouse_pos.x >= pos.x
+            && mouse_pos.x <= pos.x + size.x
+            && mouse_pos.y >= pos.y
+            && mouse_pos.y <= pos.y + size.y + 24.0
+    }
+
+    pub fn select_list(
+        &mut self,
+        systems: &mut DrawSetting,
+        mouse_pos: Vec2,
+    ) -> Option<ValidationIssue> {
+        if !self.visible {
+            return None;
+        }
+        let index = self.selection_buttons.iter().position(|button| {
+            let (pos, size) = (
+                systems.gfx.get_pos(button.image),
+                systems.gfx.get_size(button.image),
+            );
+            mouse_pos.x >= pos.x
+                && mouse_pos.x <= pos.x + size.x
+                && mouse_pos.y >= pos.y
+                && mouse_pos.y <= pos.y + size.y
+        })?;
+        self.issues.get(self.start_view_index + index).cloned()
+    }
+
+    pub fn hover_selection(
+        &mut self,
+        systems: &mut DrawSetting,
+        mouse_pos: Vec2,
+    ) {
+        if !self.visible {
+            return;
+        }
+        for index in 0..self.selection_buttons.len() {
+            let (pos, size) = (
+                systems.gfx.get_pos(self.selection_buttons[index].image),
+                systems.gfx.get_size(self.selection_buttons[index].image),
+            );
+            let in_hover = mouse_pos.x >= pos.x
+                && mouse_pos.x <= pos.x + size.x
+                && mouse_pos.y >= pos.y
+                && mouse_pos.y <= pos.y + size.y;
+            self.selection_buttons[index].set_hover(systems, in_hover);
+        }
+    }
+
+    pub fn show(&mut self, systems: &mut DrawSetting) {
+        if self.visible {
+            return;
+        }
+        self.visible = true;
+        self.bg.iter().for_each(|bg| {
+            systems.gfx.set_visible(*bg, true);
+        });
+        systems.gfx.set_visible(self.header, true);
+        self.scrollbar.show(systems);
+        self.update_list(systems);
+    }
+
+    pub fn hide(&mut self, systems: &mut DrawSetting) {
+        self.visible = false;
+        self.scrollbar.hide(systems);
+        self.bg.iter().for_each(|bg| {
+            systems.gfx.set_visible(*bg, false);
+        });
+        systems.gfx.set_visible(self.header, false);
+        self.texts.iter().for_each(|text| {
+            systems.gfx.set_visible(*text, false);
+        });
+        self.selection_buttons.iter_mut().for_each(|button| {
+            systems.gfx.set_visible(button.image, false);
+        });
+    }
+}
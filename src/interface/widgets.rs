@@ -5,6 +5,7 @@ pub mod label;
 pub mod scrollbar;
 pub mod selection_box;
 pub mod tabtext;
+pub mod text_editor;
 pub mod textbox;
 pub mod tool_button;
 
@@ -15,5 +16,6 @@ pub use label::*;
 pub use scrollbar::*;
 pub use selection_box::*;
 pub use tabtext::*;
+pub use text_editor::*;
 pub use textbox::*;
-pub use tool_button::*;
\ No newline at end of file
+pub use tool_button::*;
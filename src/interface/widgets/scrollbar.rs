@@ -272,6 +272,20 @@ impl Scrollbar {
             .floor() as usize;
     }
 
+    // Move the scrollbar to the position of the value
+    pub fn set_value(&mut self, systems: &mut DrawSetting, value: usize) {
+        let value = value.min(self.max_value);
+        let y = if self.max_value > 0 {
+            self.start_pos as f32
+                - (value as f32 / self.max_value as f32) * self.length as f32
+        } else {
+            self.start_pos as f32
+        };
+        self.hold_pos = 0.0;
+        self.move_scrollbar(systems, y, true);
+        self.cur_value = value;
+    }
+
     pub fn show(&mut self, systems: &mut DrawSetting) {
         self.visible = true;
         self.images.iter().for_each(|image| {
@@ -1,10 +1,11 @@
 use cosmic_text::{Attrs, Metrics};
 use graphics::*;
+use winit::{event::*, keyboard::*};
 
 const MAX_VISIBLE_LIST: usize = 5;
 
 use crate::{
-    interface::{label::*, scrollbar::*},
+    interface::{label::*, scrollbar::*, textbox::is_text},
     DrawSetting,
 };
 
@@ -62,10 +63,18 @@ pub struct SelectionBox {
     pub scrollbar: Scrollbar,
     pub is_list_visible: bool,
     pub selected_index: usize,
+    // ID of each entry of the list, when the list is showing game data
+    pub values: Vec<Option<u64>>,
     is_hover: bool,
     is_click: bool,
+    is_error: bool,
+    // Entry that is kept on the list but is not valid, such as an unknown ID
+    invalid_index: Option<usize>,
     list_exceed: bool,
     start_index: usize,
+    // Entries of the list that match the search text
+    search: String,
+    filter: Vec<usize>,
 }
 
 impl SelectionBox {
@@ -189,11 +198,12 @@ impl SelectionBox {
         );
         txt.set_text(
             &mut systems.renderer,
-            &list[0],
+            list.first().map(|data| data.as_str()).unwrap_or_default(),
             Attrs::new(),
             Shaping::Advanced,
         );
         let text = systems.gfx.add_text(txt, 1);
+        let filter = (0..list.len()).collect();
 
         Self {
             button,
@@ -205,9 +215,14 @@ impl SelectionBox {
             is_list_visible: false,
             is_hover: false,
             is_click: false,
+            is_error: false,
+            invalid_index: None,
             selected_index: 0,
+            values: Vec::new(),
             list_exceed,
             start_index: 0,
+            search: String::new(),
+            filter,
         }
     }
 
@@ -216,19 +231,29 @@ impl SelectionBox {
             return;
         }
         self.start_index = start_pos;
-        for index in 0..MAX_VISIBLE_LIST {
-            let list_index = index + self.start_index;
-            if list_index < self.list.len() {
-                if self.selected_index == list_index {
-                    self.list_text[index].set_select(systems, true);
-                } else {
-                    self.list_text[index].set_select(systems, false);
+        self.refresh_list(systems);
+    }
+
+    fn refresh_list(&mut self, systems: &mut DrawSetting) {
+        for index in 0..self.list_text.len() {
+            let list_index = match self.filter.get(index + self.start_index) {
+                Some(list_index) => *list_index,
+                None => {
+                    systems.gfx.set_visible(self.list_text[index].text, false);
+                    systems.gfx.set_visible(self.list_text[index].rect, false);
+                    continue;
                 }
-                systems.gfx.set_text(
-                    &mut systems.renderer,
-                    self.list_text[index].text,
-                    &self.list[list_index],
-                );
+            };
+            self.list_text[index]
+                .set_select(systems, self.selected_index == list_index);
+            systems.gfx.set_text(
+                &mut systems.renderer,
+                self.list_text[index].text,
+                &self.list[list_index],
+            );
+            if self.is_list_visible {
+                systems.gfx.set_visible(self.list_text[index].text, true);
+                systems.gfx.set_visible(self.list_text[index].rect, true);
             }
         }
     }
@@ -239,26 +264,7 @@ impl SelectionBox {
         systems.gfx.set_visible(self.rect[1], true);
 
         self.start_index = 0;
-        for index in 0..MAX_VISIBLE_LIST {
-            let list_index = index + self.start_index;
-            if list_index < self.list.len() {
-                if self.selected_index == list_index {
-                    self.list_text[index].set_select(systems, true);
-                } else {
-                    self.list_text[index].set_select(systems, false);
-                }
-                systems.gfx.set_text(
-                    &mut systems.renderer,
-                    self.list_text[index].text,
-                    &self.list[list_index],
-                );
-            }
-        }
-
-        self.list_text.iter_mut().for_each(|list_text| {
-            systems.gfx.set_visible(list_text.text, true);
-            systems.gfx.set_visible(list_text.rect, true);
-        });
+        self.refresh_list(systems);
         if self.list_exceed {
             self.scrollbar.show(systems);
             reset_scrollbar(systems, &mut self.scrollbar);
@@ -274,6 +280,106 @@ impl SelectionBox {
             systems.gfx.set_visible(list_text.text, false);
             systems.gfx.set_visible(list_text.rect, false);
         });
+
+        // Clear the search once the list is closed
+        if !self.search.is_empty() {
+            self.search.clear();
+            self.apply_filter(systems);
+        }
+    }
+
+    // Typing while the list is open will only show the matching entries
+    pub fn enter_search(
+        &mut self,
+        systems: &mut DrawSetting,
+        event: &KeyEvent,
+    ) {
+        if !event.state.is_pressed() || !self.is_list_visible {
+            return;
+        }
+
+        if event.physical_key == PhysicalKey::Code(KeyCode::Backspace) {
+            self.search.pop();
+        } else if event.physical_key == PhysicalKey::Code(KeyCode::Delete) {
+            self.search.clear();
+        } else if is_text(event) {
+            if let Some(char) = event.logical_key.to_text() {
+                self.search.push_str(char);
+            }
+        }
+        self.apply_filter(systems);
+    }
+
+    fn apply_filter(&mut self, systems: &mut DrawSetting) {
+        let search = self.search.to_lowercase();
+        self.filter = (0..self.list.len())
+            .filter(|index| self.list[*index].to_lowercase().contains(&search))
+            .collect();
+
+        self.start_index = 0;
+        self.list_exceed = self.filter.len() > self.list_text.len();
+        self.scrollbar.update_scroll_max_value(
+            systems,
+            self.filter.len().saturating_sub(self.list_text.len()),
+        );
+        if self.is_list_visible {
+            if self.list_exceed {
+                self.scrollbar.show(systems);
+                reset_scrollbar(systems, &mut self.scrollbar);
+            } else {
+                self.scrollbar.hide(systems);
+            }
+        }
+        self.refresh_list(systems);
+
+        let text = if self.search.is_empty() {
+            self.list
+                .get(self.selected_index)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            format!("Search: {}", self.search)
+        };
+        systems
+            .gfx
+            .set_text(&mut systems.renderer, self.text, &text);
+    }
+
+    pub fn set_invalid_entry(
+        &mut self,
+        systems: &mut DrawSetting,
+        index: Option<usize>,
+    ) {
+        self.invalid_index = index;
+        self.update_error(systems);
+    }
+
+    // The selection is shown as an error while the invalid entry is selected
+    fn update_error(&mut self, systems: &mut DrawSetting) {
+        let is_error = self.invalid_index == Some(self.selected_index);
+        if self.is_error == is_error {
+            return;
+        }
+        self.is_error = is_error;
+        if self.is_error {
+            systems
+                .gfx
+                .set_border_color(self.rect[0], Color::rgba(200, 40, 40, 255));
+            systems
+                .gfx
+                .set_color(self.text, Color::rgba(220, 80, 80, 255));
+        } else {
+            systems
+                .gfx
+                .set_border_color(self.rect[0], Color::rgba(20, 20, 20, 255));
+            systems
+                .gfx
+                .set_color(self.text, Color::rgba(180, 180, 180, 255));
+        }
+    }
+
+    pub fn get_value(&self) -> Option<u64> {
+        self.values.get(self.selected_index).copied().flatten()
     }
 
     pub fn set_hover(&mut self, systems: &mut DrawSetting, is_hover: bool) {
@@ -368,16 +474,35 @@ impl SelectionBox {
         });
     }
 
+    // Select the entry of the list that is shown on the index
     pub fn switch_list(&mut self, systems: &mut DrawSetting, index: usize) {
-        let list_index = index + self.start_index;
-        if list_index == self.selected_index {
+        if let Some(list_index) = self.filter.get(index + self.start_index) {
+            self.set_selected(systems, *list_index);
+        }
+    }
+
+    pub fn set_selected(&mut self, systems: &mut DrawSetting, index: usize) {
+        if index == self.selected_index || index >= self.list.len() {
             return;
         }
-        self.selected_index = list_index;
+        self.selected_index = index;
         systems.gfx.set_text(
             &mut systems.renderer,
             self.text,
             &self.list[self.selected_index],
         );
+        self.update_error(systems);
+    }
+
+    pub fn unload(&mut self, systems: &mut DrawSetting) {
+        self.scrollbar.unload(systems);
+        systems.gfx.remove_gfx(self.button);
+        systems.gfx.remove_gfx(self.text);
+        systems.gfx.remove_gfx(self.rect[0]);
+        systems.gfx.remove_gfx(self.rect[1]);
+        self.list_text.iter_mut().for_each(|list| {
+            systems.gfx.remove_gfx(list.rect);
+            systems.gfx.remove_gfx(list.text);
+        });
     }
 }
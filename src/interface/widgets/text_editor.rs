@@ -0,0 +1,477 @@
+use cosmic_text::{Attrs, Family, Metrics};
+use winit::{event::*, keyboard::*};
+
+use graphics::*;
+
+use crate::{interface::textbox::is_text, DrawSetting};
+
+// Multi-line textbox with a caret and text selection, the text is shown with a
+// monospace font so that the word wrap and caret can be placed by column
+pub struct TextEditor {
+    pub image: usize,
+    pub text: usize,
+    caret: usize,
+    selection: Vec<usize>,
+    pub data: String,
+    pub is_selected: bool,
+    pos: Vec3,
+    size: Vec2,
+    char_size: Vec2,
+    columns: usize,
+    limit: usize,
+    // Char index of the caret, and the other end of the selection
+    cursor: usize,
+    anchor: Option<usize>,
+    // Start and end char index of each wrapped line
+    lines: Vec<(usize, usize)>,
+    start_line: usize,
+    hold_key_modifier: [bool; 2],
+}
+
+impl TextEditor {
+    pub fn new(
+        systems: &mut DrawSetting,
+        pos: Vec3,
+        size: Vec2,
+        limit: usize,
+        render_layer: [usize; 2],
+    ) -> Self {
+        let mut img = Rect::new(&mut systems.renderer, 0);
+        img.set_size(size)
+            .set_position(pos)
+            .set_border_color(Color::rgba(80, 80, 80, 255))
+            .set_border_width(1.0)
+            .set_color(Color::rgba(80, 80, 80, 255))
+            .set_use_camera(true);
+        let image = systems.gfx.add_rect(img, render_layer[0]);
+
+        let mut txt = Text::new(
+            &mut systems.renderer,
+            Some(Metrics::new(16.0, 16.0).scale(systems.scale as f32)),
+            Vec3::new(pos.x + 2.0, pos.y - 2.0, pos.z),
+            size,
+            1.0,
+        );
+        txt.set_buffer_size(
+            &mut systems.renderer,
+            systems.size.width as i32,
+            systems.size.height as i32,
+        )
+        .set_bounds(Some(Bounds::new(
+            pos.x,
+            pos.y,
+            pos.x + size.x,
+            pos.y + size.y,
+        )))
+        .set_default_color(Color::rgba(200, 200, 200, 255))
+        .set_text(
+            &mut systems.renderer,
+            "0",
+            Attrs::new().family(Family::Monospace),
+            Shaping::Advanced,
+        );
+        txt.set_wrap(&mut systems.renderer, cosmic_text::Wrap::None);
+        txt.use_camera = true;
+        txt.changed = true;
+
+        // Every character has the same size on a monospace font
+        let mut char_size = txt.measure();
+        if char_size.x <= 0.0 {
+            char_size.x = 8.0;
+        }
+        if char_size.y <= 0.0 {
+            char_size.y = 16.0;
+        }
+        let text = systems.gfx.add_text(txt, render_layer[1]);
+
+        let mut caret_rect = Rect::new(&mut systems.renderer, 0);
+        caret_rect
+            .set_size(Vec2::new(1.0, char_size.y))
+            .set_position(Vec3::new(pos.x, pos.y, pos.z - 0.002))
+            .set_color(Color::rgba(220, 220, 220, 255))
+            .set_use_camera(true);
+        let caret = systems.gfx.add_rect(caret_rect, render_layer[0]);
+        systems.gfx.set_visible(caret, false);
+
+        let visible_lines =
+            (((size.y - 4.0) / char_size.y).floor() as usize).max(1);
+        let selection = (0..visible_lines)
+            .map(|_| {
+                let mut rect = Rect::new(&mut systems.renderer, 0);
+                rect.set_position(Vec3::new(pos.x, pos.y, pos.z - 0.001))
+                    .set_color(Color::rgba(60, 90, 160, 255))
+                    .set_use_camera(true);
+                let rect = systems.gfx.add_rect(rect, render_layer[0]);
+                systems.gfx.set_visible(rect, false);
+                rect
+            })
+            .collect();
+
+        let mut text_editor = Self {
+            image,
+            text,
+            caret,
+            selection,
+            data: String::new(),
+            is_selected: false,
+            pos,
+            size,
+            char_size,
+            columns: (((size.x - 4.0) / char_size.x).floor() as usize).max(1),
+            limit,
+            cursor: 0,
+            anchor: None,
+            lines: vec![(0, 0)],
+            start_line: 0,
+            hold_key_modifier: [false; 2],
+        };
+        text_editor.refresh(systems);
+        text_editor
+    }
+
+    pub fn unload(&mut self, systems: &mut DrawSetting) {
+        systems.gfx.remove_gfx(self.image);
+        systems.gfx.remove_gfx(self.text);
+        systems.gfx.remove_gfx(self.caret);
+        self.selection.iter().for_each(|rect| {
+            systems.gfx.remove_gfx(*rect);
+        });
+    }
+
+    pub fn input_text(&mut self, systems: &mut DrawSetting, text: String) {
+        self.data = text;
+        self.cursor = self.data.chars().count();
+        self.anchor = None;
+        self.refresh(systems);
+    }
+
+    pub fn set_select(&mut self, systems: &mut DrawSetting, is_select: bool) {
+        if self.is_selected == is_select {
+            return;
+        }
+        self.is_selected = is_select;
+        self.hold_key_modifier = [false; 2];
+        if self.is_selected {
+            systems
+                .gfx
+                .set_border_color(self.image, Color::rgba(180, 180, 180, 255));
+        } else {
+            systems
+                .gfx
+                .set_border_color(self.image, Color::rgba(80, 80, 80, 255));
+            self.anchor = None;
+        }
+        self.refresh(systems);
+    }
+
+    pub fn in_area(&self, mouse_pos: Vec2) -> bool {
+        mouse_pos.x >= self.pos.x
+            && mouse_pos.x <= self.pos.x + self.size.x
+            && mouse_pos.y >= self.pos.y
+            && mouse_pos.y <= self.pos.y + self.size.y
+    }
+
+    // Place the caret on the mouse position, while holding the mouse
+    // or the shift key the selection will be extended
+    pub fn click_text(
+        &mut self,
+        systems: &mut DrawSetting,
+        mouse_pos: Vec2,
+        is_hold: bool,
+    ) {
+        let top = self.pos.y + self.size.y - 2.0;
+        let line = ((top - mouse_pos.y) / self.char_size.y).floor().max(0.0)
+            as usize
+            + self.start_line;
+        let (start, end) = self.lines[line.min(self.lines.len() - 1)];
+        let column = ((mouse_pos.x - self.pos.x - 2.0) / self.char_size.x)
+            .round()
+            .max(0.0) as usize;
+        let cursor = (start + column).min(end);
+
+        if is_hold || self.hold_key_modifier[1] {
+            if self.anchor.is_none() {
+                self.anchor = Some(self.cursor);
+            }
+        } else {
+            self.anchor = Some(cursor);
+        }
+        self.cursor = cursor;
+        self.refresh(systems);
+    }
+
+    pub fn enter_text(&mut self, systems: &mut DrawSetting, event: &KeyEvent) {
+        if !self.is_selected {
+            return;
+        }
+
+        match event.physical_key {
+            PhysicalKey::Code(KeyCode::ControlLeft)
+            | PhysicalKey::Code(KeyCode::ControlRight) => {
+                self.hold_key_modifier[0] = event.state.is_pressed();
+                return;
+            }
+            PhysicalKey::Code(KeyCode::ShiftLeft)
+            | PhysicalKey::Code(KeyCode::ShiftRight) => {
+                self.hold_key_modifier[1] = event.state.is_pressed();
+                return;
+            }
+            _ => {}
+        }
+        if !event.state.is_pressed() {
+            return;
+        }
+
+        let (line, column) = self.get_cursor_line();
+        match event.physical_key {
+            PhysicalKey::Code(KeyCode::ArrowLeft) => {
+                self.move_cursor(self.cursor.saturating_sub(1))
+            }
+            PhysicalKey::Code(KeyCode::ArrowRight) => {
+                self.move_cursor(self.cursor + 1)
+            }
+            PhysicalKey::Code(KeyCode::ArrowUp) => {
+                if line > 0 {
+                    let (start, end) = self.lines[line - 1];
+                    self.move_cursor((start + column).min(end));
+                } else {
+                    self.move_cursor(0);
+                }
+            }
+            PhysicalKey::Code(KeyCode::ArrowDown) => {
+                if line + 1 < self.lines.len() {
+                    let (start, end) = self.lines[line + 1];
+                    self.move_cursor((start + column).min(end));
+                } else {
+                    self.move_cursor(self.data.chars().count());
+                }
+            }
+            PhysicalKey::Code(KeyCode::Home) => {
+                self.move_cursor(self.lines[line].0)
+            }
+            PhysicalKey::Code(KeyCode::End) => {
+                self.move_cursor(self.lines[line].1)
+            }
+            PhysicalKey::Code(KeyCode::Backspace) => {
+                if !self.delete_selection() && self.cursor > 0 {
+                    self.cursor -= 1;
+                    self.remove_char(self.cursor);
+                }
+            }
+            PhysicalKey::Code(KeyCode::Delete) => {
+                if !self.delete_selection()
+                    && self.cursor < self.data.chars().count()
+                {
+                    self.remove_char(self.cursor);
+                }
+            }
+            PhysicalKey::Code(KeyCode::Enter)
+            | PhysicalKey::Code(KeyCode::NumpadEnter) => self.insert_text("\n"),
+            PhysicalKey::Code(KeyCode::KeyA) if self.hold_key_modifier[0] => {
+                self.anchor = Some(0);
+                self.cursor = self.data.chars().count();
+            }
+            _ => {
+                if is_text(event) && !self.hold_key_modifier[0] {
+                    if let Some(char) = event.logical_key.to_text() {
+                        self.insert_text(char);
+                    }
+                }
+            }
+        }
+        self.refresh(systems);
+    }
+
+    // Move the caret, the selection is kept while shift is being held
+    fn move_cursor(&mut self, cursor: usize) {
+        if self.hold_key_modifier[1] {
+            if self.anchor.is_none() {
+                self.anchor = Some(self.cursor);
+            }
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = cursor.min(self.data.chars().count());
+    }
+
+    fn get_selection(&self) -> Option<(usize, usize)> {
+        match self.anchor {
+            Some(anchor) if anchor != self.cursor => {
+                Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+            }
+            _ => None,
+        }
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.data
+            .char_indices()
+            .nth(char_index)
+            .map(|(index, _)| index)
+            .unwrap_or(self.data.len())
+    }
+
+    fn remove_char(&mut self, char_index: usize) {
+        let index = self.byte_index(char_index);
+        self.data.remove(index);
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        let (start, end) = match self.get_selection() {
+            Some(selection) => selection,
+            None => {
+                self.anchor = None;
+                return false;
+            }
+        };
+        let (start_byte, end_byte) =
+            (self.byte_index(start), self.byte_index(end));
+        self.data.replace_range(start_byte..end_byte, "");
+        self.cursor = start;
+        self.anchor = None;
+        true
+    }
+
+    fn insert_text(&mut self, text: &str) {
+        self.delete_selection();
+        if self.data.chars().count() + text.chars().count() > self.limit {
+            return;
+        }
+        let index = self.byte_index(self.cursor);
+        self.data.insert_str(index, text);
+        self.cursor += text.chars().count();
+    }
+
+    // Line and column of the caret, a caret at the end of a wrapped line
+    // is placed at the start of the next line
+    fn get_cursor_line(&self) -> (usize, usize) {
+        let line = self
+            .lines
+            .iter()
+            .rposition(|(start, _)| *start <= self.cursor)
+            .unwrap_or_default();
+        (line, self.cursor - self.lines[line].0)
+    }
+
+    fn wrap_lines(&self) -> Vec<(usize, usize)> {
+        let chars: Vec<char> = self.data.chars().collect();
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut last_space = None;
+
+        for (index, char) in chars.iter().enumerate() {
+            if *char == '\n' {
+                lines.push((start, index));
+                start = index + 1;
+                last_space = None;
+                continue;
+            }
+            if index - start >= self.columns {
+                // Break after the last space, or within the word if the
+                // word is longer than the line
+                let end = match last_space {
+                    Some(space) if space >= start => space + 1,
+                    _ => index,
+                };
+                lines.push((start, end));
+                start = end;
+                last_space = chars[start..index]
+                    .iter()
+                    .rposition(|char| *char == ' ')
+                    .map(|space| space + start);
+            }
+            if *char == ' ' {
+                last_space = Some(index);
+            }
+        }
+        lines.push((start, chars.len()));
+        lines
+    }
+
+    fn refresh(&mut self, systems: &mut DrawSetting) {
+        self.lines = self.wrap_lines();
+        let visible_lines = self.selection.len();
+        let (line, column) = self.get_cursor_line();
+
+        // Keep the caret within view
+        if line < self.start_line {
+            self.start_line = line;
+        } else if line >= self.start_line + visible_lines {
+            self.start_line = line + 1 - visible_lines;
+        }
+        self.start_line = self
+            .start_line
+            .min(self.lines.len().saturating_sub(visible_lines));
+
+        let chars: Vec<char> = self.data.chars().collect();
+        let msg = self
+            .lines
+            .iter()
+            .skip(self.start_line)
+            .take(visible_lines)
+            .map(|(start, end)| {
+                chars[*start..*end]
+                    .iter()
+                    .filter(|char| **char != '\n')
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        systems.gfx.set_text_attrs(
+            &mut systems.renderer,
+            self.text,
+            &msg,
+            Attrs::new().family(Family::Monospace),
+        );
+
+        let top = self.pos.y + self.size.y - 2.0;
+        let text_x = self.pos.x + 2.0;
+
+        let selection = self.get_selection();
+        for (index, rect) in self.selection.iter().enumerate() {
+            let area = self.lines.get(self.start_line + index).and_then(
+                |(start, end)| {
+                    let (sel_start, sel_end) = selection?;
+                    let (from, to) = (sel_start.max(*start), sel_end.min(*end));
+                    if from >= to {
+                        return None;
+                    }
+                    Some((from - start, to - from))
+                },
+            );
+            match area {
+                Some((from, length)) => {
+                    systems.gfx.set_pos(
+                        *rect,
+                        Vec3::new(
+                            text_x + from as f32 * self.char_size.x,
+                            top - (index + 1) as f32 * self.char_size.y,
+                            self.pos.z - 0.001,
+                        ),
+                    );
+                    systems.gfx.set_size(
+                        *rect,
+                        Vec2::new(
+                            length as f32 * self.char_size.x,
+                            self.char_size.y,
+                        ),
+                    );
+                    systems.gfx.set_visible(*rect, self.is_selected);
+                }
+                None => systems.gfx.set_visible(*rect, false),
+            }
+        }
+
+        let caret_x = (text_x + column as f32 * self.char_size.x)
+            .min(self.pos.x + self.size.x - 2.0);
+        systems.gfx.set_pos(
+            self.caret,
+            Vec3::new(
+                caret_x,
+                top - (line - self.start_line + 1) as f32 * self.char_size.y,
+                self.pos.z - 0.002,
+            ),
+        );
+        systems.gfx.set_visible(self.caret, self.is_selected);
+    }
+}
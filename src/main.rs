@@ -27,24 +27,33 @@ use winit::{
 mod collection;
 mod config;
 mod editor_input;
+mod game_data;
 mod gfx_collection;
 mod interface;
 mod map;
 mod map_data;
+mod map_merge;
+mod map_render;
 mod renderer;
 mod resource;
+mod tiled;
 mod tileset;
+mod validation;
 
 use collection::*;
 use config::*;
 use editor_input::{dialog_input::*, *};
+use game_data::*;
 use gfx_collection::*;
 use interface::*;
 use map::*;
 use map_data::*;
+use map_merge::*;
+use map_render::*;
 use renderer::*;
 use resource::*;
 use tileset::*;
+use validation::*;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 enum Axis {
@@ -109,6 +118,22 @@ async fn main() -> Result<(), AscendingError> {
     // Create the directory for our map data
     fs::create_dir_all("./data/maps/")?;
 
+    // Check the maps without opening the editor window
+    if std::env::args().any(|arg| arg == "--validate") {
+        std::process::exit(run_headless_validation());
+    }
+    // Save a picture of a map without opening the editor window
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|arg| arg == "--render") {
+        std::process::exit(run_headless_render(&args[index + 1..]));
+    }
+    if let Some(index) = args.iter().position(|arg| arg == "--world") {
+        std::process::exit(run_headless_world(&args[index + 1..]));
+    }
+    if let Some(index) = args.iter().position(|arg| arg == "--merge") {
+        std::process::exit(run_headless_merge(&args[index + 1..]));
+    }
+
     // Starts an event gathering type for the window.
     let event_loop = EventLoop::new()?;
 
@@ -195,6 +220,12 @@ async fn main() -> Result<(), AscendingError> {
 
     // Load textures image
     let resource = TextureAllocation::new(&mut atlases, &renderer)?;
+    // Keep the tile ids for the renders that are made without a window
+    if let Err(e) = save_tile_index(&resource) {
+        warn!("Failed to save the tile index: {:?}", e);
+    }
+
+    let mut config_data = load_config();
 
     // Compile all rendering data in one type for quick access and passing
     let mut systems = DrawSetting {
@@ -204,20 +235,35 @@ async fn main() -> Result<(), AscendingError> {
         scale,
         resource,
         audio_list: AudioCollection::new(),
+        attribute_schema: load_attribute_schema(),
+        game_data: GameDatabase::load(&config_data.game_data_path),
     };
 
     // Initiate map editor data
-    let mut config_data = load_config();
     let mut gui = Interface::new(&mut systems, &mut config_data);
     let mut tileset = Tileset::new(&mut systems, &mut config_data);
     let mut gameinput = GameInput::new();
     let mut mapview = MapView::new(&mut systems, &mut config_data);
-    let mut database = EditorData::new()?;
+    let mut database = EditorData::new(&config_data)?;
 
     // Load the initial map
     database.load_map_data(&mut systems, &mut mapview);
     database.load_link_maps(&mut mapview);
 
+    // Open the conflicts left by the merges so that they can be reviewed
+    let conflicts = load_merge_conflicts();
+    if !conflicts.is_empty() {
+        gui.validation_list.set_issues(
+            &mut systems,
+            "Merge Conflicts",
+            conflicts
+                .iter()
+                .map(|conflict| conflict.to_issue())
+                .collect(),
+        );
+        gui.validation_list.show(&mut systems);
+    }
+
     // setup our system which includes Camera and projection as well as our controls.
     // for the camera.
     let system = System::new(
@@ -233,6 +279,19 @@ async fn main() -> Result<(), AscendingError> {
         FlatControls::new(FlatSettings { zoom: ZOOM_LEVEL }),
         [size.width, size.height],
     );
+    let map_system = System::new(
+        &mut systems.renderer,
+        Projection::Orthographic {
+            left: 0.0,
+            right: size.width,
+            bottom: 0.0,
+            top: size.height,
+            near: 1.0,
+            far: -100.0,
+        },
+        FlatControls::new(FlatSettings { zoom: ZOOM_LEVEL }),
+        [size.width, size.height],
+    );
 
     // We establish the different renderers here to load their data up to use them.
     let text_renderer = TextRenderer::new(&systems.renderer).unwrap();
@@ -247,6 +306,7 @@ async fn main() -> Result<(), AscendingError> {
     // add everything into our convience type for quicker access and passing.
     let mut graphics = Graphics {
         system,
+        map_system,
         image_atlas: atlases.remove(0),
         map_renderer,
         map_atlas: atlases.remove(0),
@@ -269,6 +329,7 @@ async fn main() -> Result<(), AscendingError> {
 
     let mut mouse_pos: PhysicalPosition<f64> = PhysicalPosition::new(0.0, 0.0);
     let mut mouse_press: bool = false;
+    let mut middle_press: bool = false;
 
     #[allow(deprecated)]
     event_loop.run(move |event, elwt| {
@@ -325,7 +386,23 @@ async fn main() -> Result<(), AscendingError> {
                     WindowEvent::CursorMoved { position, .. } => {
                         mouse_pos = *position;
 
-                        if mouse_press {
+                        if middle_press {
+                            handle_input(
+                                &mut systems,
+                                MouseInputType::MiddleDownMove,
+                                &Vec2::new(
+                                    mouse_pos.x as f32,
+                                    mouse_pos.y as f32,
+                                ),
+                                &mut gameinput,
+                                &mut gui,
+                                &mut tileset,
+                                &mut mapview,
+                                &mut database,
+                                &mut config_data,
+                                elwt,
+                            );
+                        } else if mouse_press {
                             handle_input(
                                 &mut systems,
                                 MouseInputType::LeftDownMove,
@@ -359,6 +436,54 @@ async fn main() -> Result<(), AscendingError> {
                             );
                         }
                     }
+                    WindowEvent::MouseInput {
+                        state,
+                        button: MouseButton::Middle,
+                        ..
+                    } => {
+                        let inputtype = match state {
+                            ElementState::Pressed => {
+                                middle_press = true;
+                                MouseInputType::MiddleDown
+                            }
+                            ElementState::Released => {
+                                middle_press = false;
+                                MouseInputType::MiddleRelease
+                            }
+                        };
+                        handle_input(
+                            &mut systems,
+                            inputtype,
+                            &Vec2::new(mouse_pos.x as f32, mouse_pos.y as f32),
+                            &mut gameinput,
+                            &mut gui,
+                            &mut tileset,
+                            &mut mapview,
+                            &mut database,
+                            &mut config_data,
+                            elwt,
+                        );
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let amount = match delta {
+                            MouseScrollDelta::LineDelta(_, y) => *y,
+                            MouseScrollDelta::PixelDelta(pos) => {
+                                (pos.y / 20.0) as f32
+                            }
+                        };
+                        handle_input(
+                            &mut systems,
+                            MouseInputType::Scroll(amount),
+                            &Vec2::new(mouse_pos.x as f32, mouse_pos.y as f32),
+                            &mut gameinput,
+                            &mut gui,
+                            &mut tileset,
+                            &mut mapview,
+                            &mut database,
+                            &mut config_data,
+                            elwt,
+                        );
+                    }
                     WindowEvent::MouseInput { state, .. } => match state {
                         ElementState::Pressed => {
                             handle_input(
@@ -429,6 +554,16 @@ async fn main() -> Result<(), AscendingError> {
                 near: 1.0,
                 far: -100.0,
             });
+            graphics
+                .map_system
+                .set_projection(Projection::Orthographic {
+                    left: 0.0,
+                    right: new_size.width,
+                    bottom: 0.0,
+                    top: new_size.height,
+                    near: 1.0,
+                    far: -100.0,
+                });
 
             systems.renderer.update_depth_texture();
         }
@@ -437,12 +572,23 @@ async fn main() -> Result<(), AscendingError> {
         let seconds = frame_time.seconds();
         // update our systems data to the gpu. this is the Camera in the shaders.
         graphics.system.update(&systems.renderer, &frame_time);
+        update_map_camera(&mut graphics, mapview.camera_mut());
+
+        // Keep the history list in sync with the latest edits
+        if mapview.history.changed {
+            gui.history_list.refresh(&mut systems, &mut mapview.history);
+        }
+        graphics.map_system.update(&systems.renderer, &frame_time);
 
         // update our systems data to the gpu. this is the Screen in the shaders.
         graphics.system.update_screen(
             &systems.renderer,
             [new_size.width, new_size.height],
         );
+        graphics.map_system.update_screen(
+            &systems.renderer,
+            [new_size.width, new_size.height],
+        );
 
         // This adds the Image data to the Buffer for rendering.
         add_image_to_buffer(
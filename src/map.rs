@@ -1,13 +1,26 @@
 pub mod attributes;
+mod camera;
+mod diff;
+mod dir_block;
+mod entities;
 mod recording;
-use bit_op::{bit_u8::*, BitOp};
+mod schema;
+mod walkability;
+mod world;
 
 use cosmic_text::{Attrs, Metrics, Weight};
 use graphics::*;
 use indexmap::IndexMap;
 
 pub use attributes::*;
-use recording::*;
+pub use camera::*;
+pub use diff::*;
+pub use dir_block::*;
+pub use entities::*;
+pub use recording::*;
+pub use schema::*;
+pub use walkability::*;
+pub use world::*;
 
 use crate::{
     collection::*, create_basic_label, map_data::*, ConfigData, DrawSetting,
@@ -17,24 +30,25 @@ pub struct MapAttributes {
     pub pos: Vec2,
     pub image: usize,
     pub text: usize,
-    pub attribute: MapAttribute,
+    // Attributes that are placed on the tile, an empty list is walkable
+    pub attributes: Vec<MapAttribute>,
 }
 
 impl MapAttributes {
-    pub fn set_attribute(
+    pub fn set_attributes(
         &mut self,
         systems: &mut DrawSetting,
-        attribute: MapAttribute,
+        attributes: Vec<MapAttribute>,
     ) {
-        self.attribute = attribute.clone();
+        self.attributes = attributes;
+        let (color, letter) = (
+            systems.attribute_schema.get_tile_color(&self.attributes),
+            systems.attribute_schema.get_tile_str(&self.attributes),
+        );
+        systems.gfx.set_color(self.image, color);
         systems
             .gfx
-            .set_color(self.image, MapAttribute::get_color(&self.attribute));
-        systems.gfx.set_text(
-            &mut systems.renderer,
-            self.text,
-            MapAttribute::as_map_str(&self.attribute),
-        );
+            .set_text(&mut systems.renderer, self.text, &letter);
         let size = systems.gfx.get_measure(self.text);
         let mut pos = systems.gfx.get_pos(self.text);
         pos.x = self.pos.x + (TEXTURE_SIZE as f32 * 0.5) - (size.x * 0.5);
@@ -42,15 +56,29 @@ impl MapAttributes {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct MapZone {
     pub pos: Vec<Vec2>,
 }
 
-#[derive(Default)]
+impl MapZone {
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.pos.iter().any(|&check_pos| check_pos == pos)
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
 pub struct MapZoneSetting {
+    pub name: String,
     pub max_npc: u64,
-    pub npc_id: [Option<u64>; 5],
+    pub npcs: Vec<ZoneNpc>,
+}
+
+// Entity placed on the map with the overlay that show it on the map view
+pub struct MapEntityTile {
+    pub data: MapEntity,
+    pub image: usize,
+    pub text: usize,
 }
 
 #[derive(Default, Clone, Copy)]
@@ -66,21 +94,7 @@ impl DirBlockTile {
         systems: &mut DrawSetting,
         dir_visible: [bool; 4], // Up, Left, Down, Right
     ) {
-        // B0 = Down, B1 = Up, B2 = Left, B3 = Right
-        let mut dir_data = 0;
-        if dir_visible[0] {
-            dir_data.set(B1);
-        }
-        if dir_visible[1] {
-            dir_data.set(B2);
-        }
-        if dir_visible[2] {
-            dir_data.set(B0);
-        }
-        if dir_visible[3] {
-            dir_data.set(B3);
-        }
-        self.dir_data = dir_data;
+        self.dir_data = get_dir_bits(dir_visible);
 
         for (index, visible) in dir_visible.iter().enumerate() {
             systems.gfx.set_visible(self.dir[index], *visible);
@@ -90,21 +104,7 @@ impl DirBlockTile {
     pub fn set_data_bit(&mut self, systems: &mut DrawSetting, dir: u8) {
         self.dir_data = dir;
 
-        // B0 = Down, B1 = Up, B2 = Left, B3 = Right
-        let mut dir_visible = [false; 4]; // Up, Left, Down, Right
-        if self.dir_data.get(B0) == 0b00000001 {
-            dir_visible[2] = true;
-        }
-        if self.dir_data.get(B1) == 0b00000010 {
-            dir_visible[0] = true;
-        }
-        if self.dir_data.get(B2) == 0b00000100 {
-            dir_visible[1] = true;
-        }
-        if self.dir_data.get(B3) == 0b00001000 {
-            dir_visible[3] = true;
-        }
-
+        let dir_visible = get_dir_visible(self.dir_data);
         for (index, visible) in dir_visible.iter().enumerate() {
             systems.gfx.set_visible(self.dir[index], *visible);
         }
@@ -118,6 +118,11 @@ impl DirBlockTile {
 pub struct MapView {
     pub maps: Vec<Map>,
     pub link_map_selection: Vec<usize>,
+    pub link_map_exist: [bool; 8],
+    pub link_map_changed: [bool; 8],
+    // Set when an edit touches the current map, the linked map strips
+    // only change the neighbouring maps
+    pub map_changed: bool,
     pub selection_preview: usize,
     preview_pos: Vec2,
     preview_size: Vec2,
@@ -125,13 +130,26 @@ pub struct MapView {
     pub map_attributes: Vec<MapAttributes>,
     pub map_zone: Vec<usize>,
     pub map_dir_block: Vec<DirBlockTile>,
-    pub map_zone_loc: [MapZone; 5],
-    pub map_zone_setting: [MapZoneSetting; 5],
+    pub map_zone_loc: Vec<MapZone>,
+    pub map_zone_setting: Vec<MapZoneSetting>,
+    pub map_entities: Vec<MapEntityTile>,
+    show_entities: bool,
+    // Result of the walkability analysis
+    pub map_walk: Vec<usize>,
+    // Tiles that are different from the compared map
+    pub map_diff: Vec<usize>,
+    pub diff: Option<MapDiff>,
     pub fixed_weather: u8,
     pub music: Option<String>,
 
-    // Recording
-    pub record: Records,
+    // Undo and redo history
+    pub history: EditHistory,
+
+    // Zoom and panning of the map view
+    pub camera: MapCamera,
+
+    // Overview of every map within the group
+    pub world: WorldView,
 }
 
 impl MapView {
@@ -230,7 +248,8 @@ impl MapView {
                 .set_color(Color::rgba(0, 0, 0, 130))
                 .set_use_camera(true);
 
-            link_map_selection.push(systems.gfx.add_rect(image, 0));
+            link_map_selection
+                .push(systems.gfx.add_rect(image, RENDER_MAPVIEW));
         }
 
         // This will create the selection box on the map view
@@ -249,12 +268,15 @@ impl MapView {
                 150,
             ))
             .set_use_camera(true);
-        let selection_preview = systems.gfx.add_rect(selectionpreview, 0);
+        let selection_preview =
+            systems.gfx.add_rect(selectionpreview, RENDER_MAPVIEW);
 
         // Map Attributes & Map Zones
         let mut map_attributes = Vec::with_capacity(1024);
         let mut map_zone = Vec::with_capacity(1024);
         let mut map_dir_block = Vec::with_capacity(1024);
+        let mut map_walk = Vec::with_capacity(1024);
+        let mut map_diff = Vec::with_capacity(1024);
         for i in 0..1024 {
             let pos = Vec2::new(
                 maps[0].pos.x + ((i % 32) * TEXTURE_SIZE) as f32,
@@ -276,8 +298,10 @@ impl MapView {
                 Color::rgba(255, 255, 255, 255),
             );
 
-            let (image, text) =
-                (systems.gfx.add_rect(img, 0), systems.gfx.add_text(txt, 1));
+            let (image, text) = (
+                systems.gfx.add_rect(img, RENDER_MAPVIEW),
+                systems.gfx.add_text(txt, RENDER_MAPVIEW_TEXT),
+            );
             systems.gfx.set_visible(image, false);
             systems.gfx.set_visible(text, false);
 
@@ -285,7 +309,7 @@ impl MapView {
                 pos,
                 image,
                 text,
-                attribute: MapAttribute::Walkable,
+                attributes: Vec::new(),
             });
 
             // Zone BG
@@ -295,7 +319,29 @@ impl MapView {
                 .set_position(Vec3::new(pos.x, pos.y, ORDER_MAP_ZONE))
                 .set_color(Color::rgba(0, 0, 0, 0))
                 .set_use_camera(true);
-            map_zone.push(systems.gfx.add_rect(zone_box, 0));
+            map_zone.push(systems.gfx.add_rect(zone_box, RENDER_MAPVIEW));
+
+            // Walkability
+            let mut walk_box = Rect::new(&mut systems.renderer, 0);
+            walk_box
+                .set_size(Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32))
+                .set_position(Vec3::new(pos.x, pos.y, ORDER_MAP_WALK))
+                .set_color(Color::rgba(0, 0, 0, 0))
+                .set_use_camera(true);
+            let walk = systems.gfx.add_rect(walk_box, RENDER_MAPVIEW);
+            systems.gfx.set_visible(walk, false);
+            map_walk.push(walk);
+
+            // Map Diff
+            let mut diff_box = Rect::new(&mut systems.renderer, 0);
+            diff_box
+                .set_size(Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32))
+                .set_position(Vec3::new(pos.x, pos.y, ORDER_MAP_DIFF))
+                .set_color(Color::rgba(0, 0, 0, 0))
+                .set_use_camera(true);
+            let diff = systems.gfx.add_rect(diff_box, RENDER_MAPVIEW);
+            systems.gfx.set_visible(diff, false);
+            map_diff.push(diff);
 
             // Dir Block
             let mut block_bg = Image::new(
@@ -307,7 +353,8 @@ impl MapView {
             block_bg.hw = Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
             block_bg.uv =
                 Vec4::new(0.0, 0.0, TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
-            let bg = systems.gfx.add_image(block_bg, 0);
+            block_bg.use_camera = true;
+            let bg = systems.gfx.add_image(block_bg, RENDER_MAPVIEW);
             systems.gfx.set_visible(bg, false);
 
             let mut dir0 = Image::new(
@@ -319,6 +366,7 @@ impl MapView {
             dir0.hw = Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
             dir0.uv =
                 Vec4::new(20.0, 0.0, TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
+            dir0.use_camera = true;
 
             let mut dir1 = Image::new(
                 Some(systems.resource.direction_block_tile.allocation),
@@ -329,6 +377,7 @@ impl MapView {
             dir1.hw = Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
             dir1.uv =
                 Vec4::new(40.0, 0.0, TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
+            dir1.use_camera = true;
 
             let mut dir2 = Image::new(
                 Some(systems.resource.direction_block_tile.allocation),
@@ -339,6 +388,7 @@ impl MapView {
             dir2.hw = Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
             dir2.uv =
                 Vec4::new(60.0, 0.0, TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
+            dir2.use_camera = true;
 
             let mut dir3 = Image::new(
                 Some(systems.resource.direction_block_tile.allocation),
@@ -349,12 +399,13 @@ impl MapView {
             dir3.hw = Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
             dir3.uv =
                 Vec4::new(80.0, 0.0, TEXTURE_SIZE as f32, TEXTURE_SIZE as f32);
+            dir3.use_camera = true;
 
             let dir = [
-                systems.gfx.add_image(dir0, 0),
-                systems.gfx.add_image(dir1, 0),
-                systems.gfx.add_image(dir2, 0),
-                systems.gfx.add_image(dir3, 0),
+                systems.gfx.add_image(dir0, RENDER_MAPVIEW),
+                systems.gfx.add_image(dir1, RENDER_MAPVIEW),
+                systems.gfx.add_image(dir2, RENDER_MAPVIEW),
+                systems.gfx.add_image(dir3, RENDER_MAPVIEW),
             ];
             for data in dir.iter() {
                 systems.gfx.set_visible(*data, false);
@@ -376,9 +427,19 @@ impl MapView {
             map_attributes,
             map_zone,
             map_dir_block,
-            map_zone_loc: Default::default(),
-            map_zone_setting: Default::default(),
-            record: Records::new(),
+            map_zone_loc: Vec::new(),
+            map_zone_setting: Vec::new(),
+            map_entities: Vec::new(),
+            show_entities: false,
+            map_walk,
+            map_diff,
+            diff: None,
+            link_map_exist: [false; 8],
+            link_map_changed: [false; 8],
+            map_changed: false,
+            history: EditHistory::new(),
+            camera: MapCamera::new(),
+            world: WorldView::new(systems),
             fixed_weather: 0,
             music: None,
         }
@@ -391,6 +452,12 @@ impl MapView {
         pos: Vec2,
     ) -> Option<usize> {
         let mut result = None;
+        // The linked map selection are placed within the map camera
+        let pos = if self.camera.in_view(pos) {
+            self.camera.screen_to_world(pos)
+        } else {
+            Vec2::new(-1.0, -1.0)
+        };
         for (index, selection) in self.link_map_selection.iter_mut().enumerate()
         {
             let (position, size, color) = (
@@ -415,51 +482,58 @@ impl MapView {
         result
     }
 
-    pub fn set_attribute(
+    pub fn set_tile_attributes(
         &mut self,
         systems: &mut DrawSetting,
         set_pos: Vec2,
-        attributes: MapAttribute,
+        attributes: Vec<MapAttribute>,
     ) {
         let tilepos = get_tile_pos(set_pos.x as i32, set_pos.y as i32);
 
         // Record change for undo purpose
-        let last_attribute = self.map_attributes[tilepos].attribute.clone();
-        let last_attribute_num = MapAttribute::convert_to_num(&last_attribute);
-        let data = match last_attribute {
-            MapAttribute::Warp(warpdata) => {
-                vec![
-                    InsertTypes::Int(warpdata.map_x as i64),
-                    InsertTypes::Int(warpdata.map_y as i64),
-                    InsertTypes::UInt(warpdata.map_group),
-                    InsertTypes::UInt(warpdata.tile_x as u64),
-                    InsertTypes::UInt(warpdata.tile_y as u64),
-                ]
-            }
-            MapAttribute::Sign(text) => vec![InsertTypes::Str(text)],
-            MapAttribute::ItemSpawn(itemdata) => {
-                vec![
-                    InsertTypes::UInt(itemdata.index as u64),
-                    InsertTypes::UInt(itemdata.amount as u64),
-                    InsertTypes::UInt(itemdata.timer),
-                ]
-            }
-            MapAttribute::Shop(index) => vec![InsertTypes::UInt(index as u64)],
-            _ => vec![],
-        };
-        self.record.push_undo(
-            Vec3::new(set_pos.x, set_pos.y, 0.0),
-            RecordType::Attribute,
-            last_attribute_num as i64,
-            data,
-        );
+        self.history.push(EditCommand::Attribute {
+            pos: set_pos,
+            before: self.map_attributes[tilepos].attributes.clone(),
+            after: attributes.clone(),
+        });
 
-        self.map_attributes[tilepos].set_attribute(systems, attributes);
+        self.map_attributes[tilepos].set_attributes(systems, attributes);
     }
 
-    pub fn get_attribute(&mut self, pos: Vec2) -> MapAttribute {
+    // Add the attribute to the tile, following the combination rules
+    pub fn set_attribute(
+        &mut self,
+        systems: &mut DrawSetting,
+        set_pos: Vec2,
+        attribute: MapAttribute,
+    ) {
+        let tilepos = get_tile_pos(set_pos.x as i32, set_pos.y as i32);
+        let attributes = systems
+            .attribute_schema
+            .add_attribute(&self.map_attributes[tilepos].attributes, attribute);
+        self.set_tile_attributes(systems, set_pos, attributes);
+    }
+
+    // Remove the attributes of the same type from the tile
+    pub fn remove_attribute(
+        &mut self,
+        systems: &mut DrawSetting,
+        set_pos: Vec2,
+        name: &str,
+    ) {
+        let tilepos = get_tile_pos(set_pos.x as i32, set_pos.y as i32);
+        let attributes = self.map_attributes[tilepos]
+            .attributes
+            .iter()
+            .filter(|attribute| attribute.name() != name)
+            .cloned()
+            .collect();
+        self.set_tile_attributes(systems, set_pos, attributes);
+    }
+
+    pub fn get_attributes(&mut self, pos: Vec2) -> Vec<MapAttribute> {
         let tilepos = get_tile_pos(pos.x as i32, pos.y as i32);
-        self.map_attributes[tilepos].attribute.clone()
+        self.map_attributes[tilepos].attributes.clone()
     }
 
     pub fn set_attribute_fill(
@@ -471,8 +545,12 @@ impl MapView {
         let tilepos = get_tile_pos(set_pos.x as i32, set_pos.y as i32);
 
         // We will only change the tiles that have a similar texture id, and this will be use to check
-        let comparedata = self.map_attributes[tilepos].attribute.clone();
-        if comparedata == attribute {
+        let comparedata = self.map_attributes[tilepos].attributes.clone();
+        if systems
+            .attribute_schema
+            .add_attribute(&comparedata, attribute.clone())
+            == comparedata
+        {
             return;
         }
 
@@ -484,44 +562,8 @@ impl MapView {
 
         // Loop through our collections of position that requires to be paint
         while let Some(pos) = paint_to_map.pop() {
-            // Record change for undo purpose
-            let last_attribute = self.map_attributes[tilepos].attribute.clone();
-            let last_attribute_num =
-                MapAttribute::convert_to_num(&last_attribute);
-            let data = match last_attribute {
-                MapAttribute::Warp(warpdata) => {
-                    vec![
-                        InsertTypes::Int(warpdata.map_x as i64),
-                        InsertTypes::Int(warpdata.map_y as i64),
-                        InsertTypes::UInt(warpdata.map_group),
-                        InsertTypes::UInt(warpdata.tile_x as u64),
-                        InsertTypes::UInt(warpdata.tile_y as u64),
-                    ]
-                }
-                MapAttribute::Sign(text) => vec![InsertTypes::Str(text)],
-                MapAttribute::ItemSpawn(itemdata) => {
-                    vec![
-                        InsertTypes::UInt(itemdata.index as u64),
-                        InsertTypes::UInt(itemdata.amount as u64),
-                        InsertTypes::UInt(itemdata.timer),
-                    ]
-                }
-                MapAttribute::Shop(index) => {
-                    vec![InsertTypes::UInt(index as u64)]
-                }
-                _ => vec![],
-            };
-            self.record.push_undo(
-                Vec3::new(set_pos.x, set_pos.y, 0.0),
-                RecordType::Attribute,
-                last_attribute_num as i64,
-                data,
-            );
-
-            // Paint the map
-            let tile_pos = get_tile_pos(pos.x as i32, pos.y as i32);
-            self.map_attributes[tile_pos]
-                .set_attribute(systems, attribute.clone());
+            // Paint the map, the change is recorded for undo purpose
+            self.set_attribute(systems, pos, attribute.clone());
 
             // Check direction
             for dir in 0..4 {
@@ -553,7 +595,7 @@ impl MapView {
                     let check_tilepos =
                         get_tile_pos(checkpos.x as i32, checkpos.y as i32);
                     let check_data =
-                        self.map_attributes[check_tilepos].attribute.clone();
+                        self.map_attributes[check_tilepos].attributes.clone();
                     if check_data == comparedata {
                         paint_to_map.push(checkpos);
                     }
@@ -569,7 +611,202 @@ impl MapView {
         dir_visible: [bool; 4],
     ) {
         let tilepos = get_tile_pos(set_pos.x as i32, set_pos.y as i32);
+        let before = self.map_dir_block[tilepos].dir_data;
         self.map_dir_block[tilepos].set_data(systems, dir_visible);
+
+        // Record change for undo purpose
+        self.history.push(EditCommand::DirBlock {
+            pos: set_pos,
+            before,
+            after: self.map_dir_block[tilepos].dir_data,
+        });
+    }
+
+    pub fn set_dir_block_data(
+        &mut self,
+        systems: &mut DrawSetting,
+        set_pos: Vec2,
+        dir_data: u8,
+    ) {
+        let tilepos = get_tile_pos(set_pos.x as i32, set_pos.y as i32);
+        let before = self.map_dir_block[tilepos].dir_data;
+        self.map_dir_block[tilepos].set_data_bit(systems, dir_data);
+
+        // Record change for undo purpose
+        self.history.push(EditCommand::DirBlock {
+            pos: set_pos,
+            before,
+            after: dir_data,
+        });
+    }
+
+    pub fn set_dir_block_fill(
+        &mut self,
+        systems: &mut DrawSetting,
+        set_pos: Vec2,
+        dir_visible: [bool; 4],
+    ) {
+        let tilepos = get_tile_pos(set_pos.x as i32, set_pos.y as i32);
+        let dir_data = get_dir_bits(dir_visible);
+
+        // We will only change the tiles that have the same dir block
+        let comparedata = self.map_dir_block[tilepos].dir_data;
+        if comparedata == dir_data {
+            return;
+        }
+
+        // This will hold the location that need to be paint
+        let mut paint_to_map: Vec<Vec2> = vec![set_pos];
+
+        // Loop through our collections of position that requires to be paint
+        while let Some(pos) = paint_to_map.pop() {
+            self.set_dir_block_data(systems, pos, dir_data);
+
+            // Check the surrounding tiles
+            for adjust_pos in [
+                Vec2::new(0.0, 1.0),
+                Vec2::new(-1.0, 0.0),
+                Vec2::new(0.0, -1.0),
+                Vec2::new(1.0, 0.0),
+            ] {
+                let checkpos = pos + adjust_pos;
+                if checkpos.x >= 0.0
+                    && checkpos.x < 32.0
+                    && checkpos.y >= 0.0
+                    && checkpos.y < 32.0
+                {
+                    let check_tile =
+                        get_tile_pos(checkpos.x as i32, checkpos.y as i32);
+                    if self.map_dir_block[check_tile].dir_data == comparedata {
+                        paint_to_map.push(checkpos);
+                    }
+                }
+            }
+        }
+    }
+
+    // Set the directions on every tile of the rectangle, or when using the
+    // outline, block the sides of the tiles that are on the rectangle edge
+    pub fn set_dir_block_rect(
+        &mut self,
+        systems: &mut DrawSetting,
+        start: Vec2,
+        end: Vec2,
+        dir_visible: [bool; 4],
+        mode: DirBlockMode,
+    ) {
+        let (min, max) = (start.min(end), start.max(end));
+        for y in min.y as i32..=max.y as i32 {
+            for x in min.x as i32..=max.x as i32 {
+                if !(0..32).contains(&x) || !(0..32).contains(&y) {
+                    continue;
+                }
+                let pos = Vec2::new(x as f32, y as f32);
+                let dir_data = match mode {
+                    DirBlockMode::Outline => {
+                        let edge = get_dir_bits([
+                            y == max.y as i32,
+                            x == min.x as i32,
+                            y == min.y as i32,
+                            x == max.x as i32,
+                        ]);
+                        if edge == 0 {
+                            continue;
+                        }
+                        self.map_dir_block[get_tile_pos(x, y)].dir_data | edge
+                    }
+                    _ => get_dir_bits(dir_visible),
+                };
+                self.set_dir_block_data(systems, pos, dir_data);
+            }
+        }
+    }
+
+    // Replace the dir blocks of the whole map with the ones derived from
+    // the Blocked attributes and the blocked tiles of the tile properties
+    pub fn generate_dir_blocks(
+        &mut self,
+        systems: &mut DrawSetting,
+        properties: &TileProperties,
+    ) {
+        let blocked: Vec<bool> = (0..1024)
+            .map(|tile_num| {
+                let (x, y) = ((tile_num % 32) as u32, (tile_num / 32) as u32);
+                self.map_attributes[tile_num]
+                    .attributes
+                    .contains(&MapAttribute::Blocked)
+                    || (0..9).any(|layer| {
+                        let id = self.maps[0].get_tile((x, y, layer)).id;
+                        id > 0 && properties.is_blocked(systems, id)
+                    })
+            })
+            .collect();
+        for (tile_num, dir_data) in
+            generate_dir_blocks(&blocked).into_iter().enumerate()
+        {
+            let pos = Vec2::new((tile_num % 32) as f32, (tile_num / 32) as f32);
+            self.set_dir_block_data(systems, pos, dir_data);
+        }
+    }
+
+    // Show the rectangle that is being drawn on the selection preview
+    pub fn set_selection_rect(
+        &mut self,
+        systems: &mut DrawSetting,
+        start: Vec2,
+        end: Vec2,
+    ) {
+        let (min, max) = (start.min(end), start.max(end));
+        self.hover_selection_preview(systems, min);
+        self.change_selection_preview_size(systems, max - min + Vec2::ONE);
+    }
+
+    // Tile positions outside of 0..32 are pointing to the linked map strips,
+    // these are 2 tiles wide so the positions can go from -2 up to 33
+    pub fn locate_tile(&self, pos: Vec2) -> Option<(usize, u32, u32)> {
+        let (x, y) = (pos.x as i32, pos.y as i32);
+        if !(-2..34).contains(&x) || !(-2..34).contains(&y) {
+            return None;
+        }
+        let (offset_x, offset_y) = (x.div_euclid(32), y.div_euclid(32));
+        if offset_x == 0 && offset_y == 0 {
+            return Some((0, x as u32, y as u32));
+        }
+        let maplink = (0..8).find(|&maplink| {
+            let (link_x, link_y, _, _) = get_link_map_info(maplink);
+            link_x == offset_x && link_y == offset_y
+        })?;
+        let (_, _, start, _) = get_link_map_info(maplink);
+        Some((
+            maplink + 1,
+            (x.rem_euclid(32) - start.x as i32) as u32,
+            (y.rem_euclid(32) - start.y as i32) as u32,
+        ))
+    }
+
+    // Same as locate_tile but only return the tiles that can be edited
+    pub fn get_tile_target(&self, pos: Vec2) -> Option<(usize, u32, u32)> {
+        let target = self.locate_tile(pos)?;
+        if target.0 > 0 && !self.link_map_exist[target.0 - 1] {
+            return None;
+        }
+        Some(target)
+    }
+
+    fn get_map_tile(&self, pos: Vec2, layer: u32) -> Option<TileData> {
+        let (index, x, y) = self.get_tile_target(pos)?;
+        Some(self.maps[index].get_tile((x, y, layer)))
+    }
+
+    fn set_map_tile(&mut self, pos: Vec2, layer: u32, tiledata: TileData) {
+        if let Some((index, x, y)) = self.get_tile_target(pos) {
+            self.maps[index].set_tile((x, y, layer), tiledata);
+            if index > 0 {
+                self.link_map_changed[index - 1] = true;
+            } else {
+                self.map_changed = true;
+            }
+        }
     }
 
     pub fn set_tile_group(
@@ -591,33 +828,19 @@ impl MapView {
 
                 // Make sure we only add tile that are not empty
                 if tiledata.id > 0 {
-                    // Make sure we wont set map outside the map size limit
-                    if (set_pos.x as u32 + x) < 32
-                        && (set_pos.y as u32 + y) < 32
+                    let tile_pos = set_pos + Vec2::new(x as f32, y as f32);
+                    // Make sure we wont set map outside the editable area
+                    if let Some(last_tile) = self.get_map_tile(tile_pos, layer)
                     {
                         // Record change for undo purpose
-                        let last_texture = self.maps[0]
-                            .get_tile((
-                                set_pos.x as u32 + x,
-                                set_pos.y as u32 + y,
-                                layer,
-                            ))
-                            .id;
-                        self.record.push_undo(
-                            Vec3::new(
-                                set_pos.x + x as f32,
-                                set_pos.y + y as f32,
-                                layer as f32,
-                            ),
-                            RecordType::Layer,
-                            last_texture as i64,
-                            vec![],
-                        );
-
-                        self.maps[0].set_tile(
-                            (set_pos.x as u32 + x, set_pos.y as u32 + y, layer),
-                            tiledata,
-                        );
+                        self.history.push(EditCommand::Tile {
+                            pos: tile_pos,
+                            layer,
+                            before: last_tile.id as u32,
+                            after: tiledata.id as u32,
+                        });
+
+                        self.set_map_tile(tile_pos, layer, tiledata);
                     }
                 }
             }
@@ -627,39 +850,19 @@ impl MapView {
     pub fn delete_tile_group(&mut self, set_pos: Vec2, layer: u32, size: Vec2) {
         for x in 0..size.x as u32 {
             for y in 0..size.y as u32 {
-                // Make sure we wont set map outside the map size limit
-                if (set_pos.x as u32 + x) < 32 && (set_pos.y as u32 + y) < 32 {
-                    let texture_id = self.maps[0]
-                        .get_tile((
-                            set_pos.x as u32 + x,
-                            set_pos.y as u32 + y,
-                            layer,
-                        ))
-                        .id;
-                    if texture_id > 0 {
+                let tile_pos = set_pos + Vec2::new(x as f32, y as f32);
+                // Make sure we wont set map outside the editable area
+                if let Some(last_tile) = self.get_map_tile(tile_pos, layer) {
+                    if last_tile.id > 0 {
                         // Record change for undo purpose
-                        let last_texture = self.maps[0]
-                            .get_tile((
-                                set_pos.x as u32 + x,
-                                set_pos.y as u32 + y,
-                                layer,
-                            ))
-                            .id;
-                        self.record.push_undo(
-                            Vec3::new(
-                                set_pos.x + x as f32,
-                                set_pos.y + y as f32,
-                                layer as f32,
-                            ),
-                            RecordType::Layer,
-                            last_texture as i64,
-                            vec![],
-                        );
-
-                        self.maps[0].set_tile(
-                            (set_pos.x as u32 + x, set_pos.y as u32 + y, layer),
-                            TileData::default(),
-                        );
+                        self.history.push(EditCommand::Tile {
+                            pos: tile_pos,
+                            layer,
+                            before: last_tile.id as u32,
+                            after: 0,
+                        });
+
+                        self.set_map_tile(tile_pos, layer, TileData::default());
                     }
                 }
             }
@@ -667,7 +870,7 @@ impl MapView {
     }
 
     pub fn get_tile_data(&mut self, set_pos: Vec2) -> TileData {
-        self.maps[0].get_tile((set_pos.x as u32, set_pos.y as u32, 0))
+        self.get_map_tile(set_pos, 0).unwrap_or_default()
     }
 
     pub fn set_tile_fill(
@@ -684,10 +887,15 @@ impl MapView {
             return;
         }
 
+        // The fill will stay within the map where it has started
+        let map_index = match self.get_tile_target(set_pos) {
+            Some((index, _, _)) => index,
+            None => return,
+        };
+
         // We will only change the tiles that have a similar texture id, and this will be use to check
-        let comparedata = self.maps[0]
-            .get_tile((set_pos.x as u32, set_pos.y as u32, layer))
-            .id;
+        let comparedata =
+            self.get_map_tile(set_pos, layer).unwrap_or_default().id;
         if comparedata == tiledata.id {
             return;
         }
@@ -701,19 +909,17 @@ impl MapView {
         // Loop through our collections of position that requires to be paint
         while let Some(pos) = paint_to_map.pop() {
             // Record change for undo purpose
-            let last_texture = self.maps[0]
-                .get_tile((pos.x as u32, pos.y as u32, layer))
-                .id;
-            self.record.push_undo(
-                Vec3::new(pos.x, pos.y, layer as f32),
-                RecordType::Layer,
-                last_texture as i64,
-                vec![],
-            );
+            let last_texture =
+                self.get_map_tile(pos, layer).unwrap_or_default().id;
+            self.history.push(EditCommand::Tile {
+                pos,
+                layer,
+                before: last_texture as u32,
+                after: tiledata.id as u32,
+            });
 
             // Paint the map
-            self.maps[0]
-                .set_tile((pos.x as u32, pos.y as u32, layer), tiledata);
+            self.set_map_tile(pos, layer, tiledata);
 
             // Check direction
             for dir in 0..4 {
@@ -735,15 +941,14 @@ impl MapView {
                 }
                 let checkpos = pos + adjust_pos;
 
-                if checkpos.x >= 0.0
-                    && checkpos.x < 32.0
-                    && checkpos.y >= 0.0
-                    && checkpos.y < 32.0
+                if self.get_tile_target(checkpos).map(|target| target.0)
+                    == Some(map_index)
                 {
                     // Check the map texture id and we make sure that we only change
                     // if they have the same texture id as the starting tile
-                    let check_data = self.maps[0]
-                        .get_tile((checkpos.x as u32, checkpos.y as u32, layer))
+                    let check_data = self
+                        .get_map_tile(checkpos, layer)
+                        .unwrap_or_default()
                         .id;
                     if check_data == comparedata {
                         paint_to_map.push(checkpos);
@@ -763,7 +968,11 @@ impl MapView {
             systems.gfx.set_color(*zone, Color::rgba(0, 0, 0, 0));
         });
         // Add the selected zone
-        for data in self.map_zone_loc[zone_index].pos.iter() {
+        let zone = match self.map_zone_loc.get(zone_index) {
+            Some(zone) => zone,
+            None => return,
+        };
+        for data in zone.pos.iter() {
             let tilenum = get_tile_pos(data.x as i32, data.y as i32);
             systems
                 .gfx
@@ -777,33 +986,25 @@ impl MapView {
         zone_index: usize,
         pos: Vec2,
     ) {
-        // Record change for undo purpose
-        let does_exist = if self.map_zone_loc[zone_index]
-            .pos
-            .iter()
-            .any(|&check_pos| check_pos == pos)
-        {
-            1
-        } else {
-            0
+        let zone = match self.map_zone_loc.get_mut(zone_index) {
+            Some(zone) => zone,
+            None => return,
         };
-        self.record.push_undo(
-            Vec3::new(pos.x, pos.y, zone_index as f32),
-            RecordType::Zone,
-            does_exist,
-            vec![],
-        );
+        // Record change for undo purpose
+        let does_exist = zone.contains(pos);
+        self.history.push(EditCommand::Zone {
+            pos,
+            zone: zone_index,
+            before: does_exist,
+            after: true,
+        });
 
         let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
         systems
             .gfx
             .set_color(self.map_zone[tilenum], get_zone_color(zone_index));
-        if !self.map_zone_loc[zone_index]
-            .pos
-            .iter()
-            .any(|&check_pos| check_pos == pos)
-        {
-            self.map_zone_loc[zone_index].pos.push(pos);
+        if !does_exist {
+            zone.pos.push(pos);
         }
     }
 
@@ -813,12 +1014,12 @@ impl MapView {
         set_pos: Vec2,
         zone_index: usize,
     ) {
+        let zone = match self.map_zone_loc.get_mut(zone_index) {
+            Some(zone) => zone,
+            None => return,
+        };
         // Fill only empty area
-        if self.map_zone_loc[zone_index]
-            .pos
-            .iter()
-            .any(|&check_pos| check_pos == set_pos)
-        {
+        if zone.contains(set_pos) {
             return;
         }
 
@@ -831,33 +1032,21 @@ impl MapView {
         // Loop through our collections of position that requires to be paint
         while let Some(pos) = paint_to_map.pop() {
             // Record change for undo purpose
-            let does_exist = if self.map_zone_loc[zone_index]
-                .pos
-                .iter()
-                .any(|&check_pos| check_pos == pos)
-            {
-                1
-            } else {
-                0
-            };
-            self.record.push_undo(
-                Vec3::new(pos.x, pos.y, zone_index as f32),
-                RecordType::Zone,
-                does_exist,
-                vec![],
-            );
+            let does_exist = zone.contains(pos);
+            self.history.push(EditCommand::Zone {
+                pos,
+                zone: zone_index,
+                before: does_exist,
+                after: true,
+            });
 
             // Paint the map
             let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
             systems
                 .gfx
                 .set_color(self.map_zone[tilenum], get_zone_color(zone_index));
-            if !self.map_zone_loc[zone_index]
-                .pos
-                .iter()
-                .any(|&check_pos| check_pos == pos)
-            {
-                self.map_zone_loc[zone_index].pos.push(pos);
+            if !does_exist {
+                zone.pos.push(pos);
             }
 
             // Check direction
@@ -886,11 +1075,7 @@ impl MapView {
                     && checkpos.y < 32.0
                 {
                     // Check if zone is empty
-                    if !self.map_zone_loc[zone_index]
-                        .pos
-                        .iter()
-                        .any(|&check_pos| check_pos == checkpos)
-                    {
+                    if !zone.contains(checkpos) {
                         paint_to_map.push(checkpos);
                     }
                 }
@@ -904,30 +1089,24 @@ impl MapView {
         zone_index: usize,
         pos: Vec2,
     ) {
-        // Record change for undo purpose
-        let does_exist = if self.map_zone_loc[zone_index]
-            .pos
-            .iter()
-            .any(|&check_pos| check_pos == pos)
-        {
-            1
-        } else {
-            0
+        let zone = match self.map_zone_loc.get_mut(zone_index) {
+            Some(zone) => zone,
+            None => return,
         };
-        self.record.push_undo(
-            Vec3::new(pos.x, pos.y, zone_index as f32),
-            RecordType::Zone,
-            does_exist,
-            vec![],
-        );
+        // Record change for undo purpose
+        let does_exist = zone.contains(pos);
+        self.history.push(EditCommand::Zone {
+            pos,
+            zone: zone_index,
+            before: does_exist,
+            after: false,
+        });
 
         let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
         systems
             .gfx
             .set_color(self.map_zone[tilenum], Color::rgba(0, 0, 0, 0));
-        self.map_zone_loc[zone_index]
-            .pos
-            .retain(|&check_pos| check_pos != pos);
+        zone.pos.retain(|&check_pos| check_pos != pos);
     }
 
     pub fn hover_selection_preview(
@@ -935,13 +1114,16 @@ impl MapView {
         systems: &mut DrawSetting,
         set_pos: Vec2,
     ) {
-        if self.preview_pos != set_pos && set_pos.x < 32.0 && set_pos.y < 32.0 {
+        if self.preview_pos == set_pos {
+            return;
+        }
+        if let Some((index, x, y)) = self.locate_tile(set_pos) {
             self.preview_pos = set_pos;
             systems.gfx.set_pos(
                 self.selection_preview,
                 Vec3::new(
-                    self.maps[0].pos.x + set_pos.x * TEXTURE_SIZE as f32,
-                    self.maps[0].pos.y + set_pos.y * TEXTURE_SIZE as f32,
+                    self.maps[index].pos.x + x as f32 * TEXTURE_SIZE as f32,
+                    self.maps[index].pos.y + y as f32 * TEXTURE_SIZE as f32,
                     ORDER_MAP_SELECTION,
                 ),
             );
@@ -958,6 +1140,31 @@ impl MapView {
         self.adjust_selection_preview(systems);
     }
 
+    // The world view has its own camera so the map view zoom is kept
+    pub fn camera_mut(&mut self) -> &mut MapCamera {
+        if self.world.is_open {
+            &mut self.world.camera
+        } else {
+            &mut self.camera
+        }
+    }
+
+    pub fn open_world_view(
+        &mut self,
+        systems: &mut DrawSetting,
+        database: &mut EditorData,
+    ) {
+        // The overview includes the edits that are not saved yet
+        database.save_map_data(self, Some(database.current_index.clone()));
+        self.world.open(systems, database);
+        self.world.camera.changed = true;
+    }
+
+    pub fn close_world_view(&mut self, systems: &mut DrawSetting) {
+        self.world.close(systems);
+        self.camera.changed = true;
+    }
+
     pub fn clear_map(&mut self, index: usize) {
         (0..9).for_each(|layer| {
             (0..32).for_each(|x| {
@@ -971,7 +1178,8 @@ impl MapView {
 
     // This function ensure that the selection preview does not show outside the map boundary
     fn adjust_selection_preview(&mut self, systems: &mut DrawSetting) {
-        let max_size = Vec2::new(32.0, 32.0);
+        // The selection can reach up to the end of the linked map strips
+        let max_size = Vec2::new(34.0, 34.0);
 
         let clamped_x =
             (self.preview_pos.x + self.preview_size.x).min(max_size.x);
@@ -992,211 +1200,586 @@ impl MapView {
         );
     }
 
-    pub fn apply_change(&mut self, systems: &mut DrawSetting, is_undo: bool) {
-        let record_list = if is_undo {
-            &self.record.undo
-        } else {
-            &self.record.redo
+    pub fn set_zone_setting(
+        &mut self,
+        zone_index: usize,
+        setting: MapZoneSetting,
+    ) {
+        let current = match self.map_zone_setting.get_mut(zone_index) {
+            Some(current) => current,
+            None => return,
+        };
+        self.history.push(EditCommand::ZoneSetting {
+            zone: zone_index,
+            before: current.clone(),
+            after: setting.clone(),
+        });
+        *current = setting;
+    }
+
+    // Add a new zone at the end of the list and return its index
+    pub fn add_zone(&mut self) -> usize {
+        let zone_index = self.map_zone_setting.len();
+        let setting = MapZoneSetting {
+            name: format!("Zone {}", zone_index + 1),
+            ..Default::default()
         };
-        if record_list.is_empty() {
+        self.history.push(EditCommand::ZoneList {
+            zone: zone_index,
+            before: None,
+            after: Some((setting.clone(), Vec::new())),
+        });
+        self.map_zone_setting.push(setting);
+        self.map_zone_loc.push(MapZone::default());
+        zone_index
+    }
+
+    pub fn remove_zone(
+        &mut self,
+        systems: &mut DrawSetting,
+        zone_index: usize,
+    ) {
+        if zone_index >= self.map_zone_setting.len() {
             return;
         }
+        let setting = self.map_zone_setting.remove(zone_index);
+        let zone = self.map_zone_loc.remove(zone_index);
+        self.history.push(EditCommand::ZoneList {
+            zone: zone_index,
+            before: Some((setting, zone.pos)),
+            after: None,
+        });
+        self.map_zone.iter().for_each(|zone| {
+            systems.gfx.set_color(*zone, Color::rgba(0, 0, 0, 0));
+        });
+    }
 
-        let get_change = if is_undo {
-            self.record.get_last_undo()
-        } else {
-            self.record.get_last_redo()
+    pub fn get_zone_data(&self) -> Vec<MapZoneData> {
+        self.map_zone_setting
+            .iter()
+            .zip(self.map_zone_loc.iter())
+            .map(|(setting, zone)| MapZoneData {
+                name: setting.name.clone(),
+                max_npc: setting.max_npc,
+                npcs: setting.npcs.clone(),
+                spawns: zone
+                    .pos
+                    .iter()
+                    .map(|zone_pos| (zone_pos.x as u16, zone_pos.y as u16))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    pub fn get_entity(&self, pos: Vec2) -> Option<&MapEntity> {
+        self.map_entities
+            .iter()
+            .find(|entity| {
+                entity.data.x == pos.x as u16 && entity.data.y == pos.y as u16
+            })
+            .map(|entity| &entity.data)
+    }
+
+    // Place, replace or remove the entity on the tile
+    pub fn set_entity(
+        &mut self,
+        systems: &mut DrawSetting,
+        pos: Vec2,
+        entity: Option<MapEntity>,
+    ) {
+        self.history.push(EditCommand::Entity {
+            pos,
+            before: self.get_entity(pos).cloned(),
+            after: entity.clone(),
+        });
+        self.place_entity(systems, pos, entity);
+    }
+
+    // Move the entity to another tile, the entity on the target is replaced
+    pub fn move_entity(
+        &mut self,
+        systems: &mut DrawSetting,
+        from: Vec2,
+        to: Vec2,
+    ) {
+        if from == to {
+            return;
+        }
+        if let Some(mut entity) = self.get_entity(from).cloned() {
+            self.set_entity(systems, from, None);
+            entity.x = to.x as u16;
+            entity.y = to.y as u16;
+            self.set_entity(systems, to, Some(entity));
+        }
+    }
+
+    fn place_entity(
+        &mut self,
+        systems: &mut DrawSetting,
+        pos: Vec2,
+        entity: Option<MapEntity>,
+    ) {
+        if let Some(index) = self.map_entities.iter().position(|data| {
+            data.data.x == pos.x as u16 && data.data.y == pos.y as u16
+        }) {
+            let data = self.map_entities.remove(index);
+            systems.gfx.remove_gfx(data.image);
+            systems.gfx.remove_gfx(data.text);
+        }
+        let mut data = match entity {
+            Some(data) => data,
+            None => return,
         };
+        data.x = pos.x as u16;
+        data.y = pos.y as u16;
 
-        if let Some(data) = get_change {
-            if is_undo {
-                self.record.set_redo_record();
-            } else {
-                self.record.set_undo_record();
-            };
+        let tile_pos = Vec2::new(
+            self.maps[0].pos.x + pos.x * TEXTURE_SIZE as f32,
+            self.maps[0].pos.y + pos.y * TEXTURE_SIZE as f32,
+        );
+        let mut img = Rect::new(&mut systems.renderer, 0);
+        img.set_size(Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32))
+            .set_position(Vec3::new(tile_pos.x, tile_pos.y, ORDER_MAP_ENTITY))
+            .set_color(get_entity_color(data.kind))
+            .set_border_width(1.0)
+            .set_border_color(Color::rgba(20, 20, 20, 255))
+            .set_use_camera(true);
+        let mut txt = create_basic_label(
+            systems,
+            Vec3::new(tile_pos.x, tile_pos.y - 13.0, ORDER_MAP_ATTRIBUTE_TEXT),
+            Vec2::new(32.0, 32.0),
+            Color::rgba(255, 255, 255, 255),
+        );
+        txt.set_text(
+            &mut systems.renderer,
+            &format!("{}{}", data.kind.letter(), data.dir.arrow()),
+            Attrs::new(),
+            Shaping::Advanced,
+        );
+        let (image, text) = (
+            systems.gfx.add_rect(img, RENDER_MAPVIEW),
+            systems.gfx.add_text(txt, RENDER_MAPVIEW_TEXT),
+        );
+        let size = systems.gfx.get_measure(text);
+        let mut text_pos = systems.gfx.get_pos(text);
+        text_pos.x = tile_pos.x + (TEXTURE_SIZE as f32 * 0.5) - (size.x * 0.5);
+        systems.gfx.set_pos(text, text_pos);
+        systems.gfx.set_visible(image, self.show_entities);
+        systems.gfx.set_visible(text, self.show_entities);
+
+        self.map_entities.push(MapEntityTile { data, image, text });
+    }
 
-            for (_key, changedata) in data.changes.iter() {
-                let pos = Vec3::new(
-                    changedata.pos.x,
-                    changedata.pos.y,
-                    changedata.pos.z,
-                );
+    // Replace all the entities without recording it, used when loading a map
+    pub fn load_entities(
+        &mut self,
+        systems: &mut DrawSetting,
+        entities: &[MapEntity],
+    ) {
+        self.map_entities.drain(..).for_each(|data| {
+            systems.gfx.remove_gfx(data.image);
+            systems.gfx.remove_gfx(data.text);
+        });
+        entities.iter().for_each(|entity| {
+            let pos = Vec2::new(entity.x as f32, entity.y as f32);
+            self.place_entity(systems, pos, Some(entity.clone()));
+        });
+    }
 
-                match changedata.record_type {
-                    RecordType::Layer => {
-                        let last_texture = self.maps[0]
-                            .get_tile((
-                                pos.x as u32,
-                                pos.y as u32,
-                                pos.z as u32,
-                            ))
-                            .id;
-                        if is_undo {
-                            self.record.push_redo(
-                                Vec3::new(pos.x, pos.y, pos.z),
-                                RecordType::Layer,
-                                last_texture as i64,
-                                vec![],
-                            );
-                        } else {
-                            self.record.push_undo(
-                                Vec3::new(pos.x, pos.y, pos.z),
-                                RecordType::Layer,
-                                last_texture as i64,
-                                vec![],
-                            );
-                        }
+    pub fn set_entity_visible(
+        &mut self,
+        systems: &mut DrawSetting,
+        visible: bool,
+    ) {
+        self.show_entities = visible;
+        self.map_entities.iter().for_each(|data| {
+            systems.gfx.set_visible(data.image, visible);
+            systems.gfx.set_visible(data.text, visible);
+        });
+    }
 
-                        let texture_id = changedata.id as u32;
-                        self.maps[0].set_tile(
-                            (pos.x as u32, pos.y as u32, pos.z as u32),
-                            TileData {
-                                id: texture_id as usize,
-                                color: Color::rgba(255, 255, 255, 255),
-                            },
-                        );
-                    }
-                    RecordType::Attribute => {
-                        let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
-                        let last_attribute =
-                            self.map_attributes[tilenum].attribute.clone();
-                        let last_attribute_num =
-                            MapAttribute::convert_to_num(&last_attribute);
-                        let data = match last_attribute {
-                            MapAttribute::Warp(warpdata) => {
-                                vec![
-                                    InsertTypes::Int(warpdata.map_x as i64),
-                                    InsertTypes::Int(warpdata.map_y as i64),
-                                    InsertTypes::UInt(warpdata.map_group),
-                                    InsertTypes::UInt(warpdata.tile_x as u64),
-                                    InsertTypes::UInt(warpdata.tile_y as u64),
-                                ]
-                            }
-                            MapAttribute::Sign(text) => {
-                                vec![InsertTypes::Str(text)]
-                            }
-                            MapAttribute::ItemSpawn(itemdata) => {
-                                vec![
-                                    InsertTypes::UInt(itemdata.index as u64),
-                                    InsertTypes::UInt(itemdata.amount as u64),
-                                    InsertTypes::UInt(itemdata.timer),
-                                ]
-                            }
-                            MapAttribute::Shop(index) => {
-                                vec![InsertTypes::UInt(index as u64)]
-                            }
-                            _ => vec![],
-                        };
-                        if is_undo {
-                            self.record.push_redo(
-                                Vec3::new(pos.x, pos.y, pos.z),
-                                RecordType::Attribute,
-                                last_attribute_num as i64,
-                                data,
-                            );
-                        } else {
-                            self.record.push_undo(
-                                Vec3::new(pos.x, pos.y, pos.z),
-                                RecordType::Attribute,
-                                last_attribute_num as i64,
-                                data,
-                            );
-                        }
+    // Color the listed tiles and hide the rest of the walkability overlay
+    pub fn set_walk_overlay(
+        &mut self,
+        systems: &mut DrawSetting,
+        tiles: &[(usize, Color)],
+    ) {
+        self.clear_walk_overlay(systems);
+        for (tile_num, color) in tiles.iter() {
+            if let Some(walk) = self.map_walk.get(*tile_num) {
+                systems.gfx.set_color(*walk, *color);
+                systems.gfx.set_visible(*walk, true);
+            }
+        }
+    }
 
-                        let attribute_enum = MapAttribute::convert_to_enum(
-                            changedata.id as u32,
-                            &changedata.data,
-                        );
-                        let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
-                        self.map_attributes[tilenum]
-                            .set_attribute(systems, attribute_enum);
-                    }
-                    RecordType::Zone => {
-                        // We will use the pos.z for the selected zone index
-                        let zone_index = pos.z as usize;
-
-                        let does_exist = if self.map_zone_loc[zone_index]
-                            .pos
-                            .iter()
-                            .any(|&check_pos| {
-                                check_pos == Vec2::new(pos.x, pos.y)
-                            }) {
-                            1
-                        } else {
-                            0
-                        };
-                        if is_undo {
-                            self.record.push_redo(
-                                Vec3::new(pos.x, pos.y, pos.z),
-                                RecordType::Zone,
-                                does_exist,
-                                vec![],
-                            );
-                        } else {
-                            self.record.push_undo(
-                                Vec3::new(pos.x, pos.y, pos.z),
-                                RecordType::Zone,
-                                does_exist,
-                                vec![],
-                            );
-                        }
+    pub fn clear_walk_overlay(&mut self, systems: &mut DrawSetting) {
+        self.map_walk.iter().for_each(|walk| {
+            systems.gfx.set_visible(*walk, false);
+        });
+    }
+
+    // Color the tiles that are different from the compared map
+    pub fn set_diff_overlay(
+        &mut self,
+        systems: &mut DrawSetting,
+        tiles: &[(usize, Color)],
+    ) {
+        self.clear_diff_overlay(systems);
+        for (tile_num, color) in tiles.iter() {
+            if let Some(diff) = self.map_diff.get(*tile_num) {
+                systems.gfx.set_color(*diff, *color);
+                systems.gfx.set_visible(*diff, true);
+            }
+        }
+    }
+
+    pub fn clear_diff_overlay(&mut self, systems: &mut DrawSetting) {
+        self.map_diff.iter().for_each(|diff| {
+            systems.gfx.set_visible(*diff, false);
+        });
+    }
+
+    // Set the tile back to the data of the other map, the revert is added
+    // to the history so that it can be undone
+    pub fn revert_tile(
+        &mut self,
+        systems: &mut DrawSetting,
+        pos: Vec2,
+        other: &MapData,
+    ) {
+        let tile_num = get_tile_pos(pos.x as i32, pos.y as i32);
+        let mut commands = Vec::new();
+        for layer in 0..9 {
+            commands.push(EditCommand::Tile {
+                pos,
+                layer,
+                before: self.maps[0]
+                    .get_tile((pos.x as u32, pos.y as u32, layer))
+                    .id as u32,
+                after: other
+                    .tile
+                    .get(layer as usize)
+                    .and_then(|tile| tile.id.get(tile_num))
+                    .copied()
+                    .unwrap_or_default(),
+            });
+        }
+        commands.push(EditCommand::Attribute {
+            pos,
+            before: self.map_attributes[tile_num].attributes.clone(),
+            after: other.attribute.get(tile_num).cloned().unwrap_or_default(),
+        });
+        commands.push(EditCommand::DirBlock {
+            pos,
+            before: self.map_dir_block[tile_num].dir_data,
+            after: other.dir_block.get(tile_num).copied().unwrap_or_default(),
+        });
+        let spawn = (pos.x as u16, pos.y as u16);
+        let spawns =
+            revert_zone_spawns(&self.get_zone_data(), &other.zones, spawn);
+        for (zone_index, zone) in self.map_zone_loc.iter().enumerate() {
+            commands.push(EditCommand::Zone {
+                pos,
+                zone: zone_index,
+                before: zone.contains(pos),
+                after: spawns.get(zone_index).copied().unwrap_or_default(),
+            });
+        }
+        commands.push(EditCommand::Entity {
+            pos,
+            before: self.get_entity(pos).cloned(),
+            after: other
+                .entities
+                .iter()
+                .find(|entity| entity.x == spawn.0 && entity.y == spawn.1)
+                .cloned(),
+        });
+
+        self.history.begin_group("Revert Tile");
+        for command in commands.into_iter() {
+            if command.is_unchanged() {
+                continue;
+            }
+            self.apply_command(systems, &command, true);
+            self.history.push(command);
+        }
+        self.history.end_group();
+    }
 
-                        let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
-                        if changedata.id > 0 {
-                            // Exist, add zone
-                            systems.gfx.set_color(
-                                self.map_zone[tilenum],
-                                get_zone_color(zone_index),
-                            );
-                            if !self.map_zone_loc[zone_index].pos.iter().any(
-                                |&check_pos| {
-                                    check_pos == Vec2::new(pos.x, pos.y)
-                                },
-                            ) {
-                                self.map_zone_loc[zone_index]
-                                    .pos
-                                    .push(Vec2::new(pos.x, pos.y));
-                            }
-                        } else {
-                            // Does not exist, remove zone
-                            systems.gfx.set_color(
-                                self.map_zone[tilenum],
-                                Color::rgba(0, 0, 0, 0),
-                            );
-                            self.map_zone_loc[zone_index].pos.retain(
-                                |&check_pos| {
-                                    check_pos != Vec2::new(pos.x, pos.y)
-                                },
-                            );
+    pub fn set_weather(&mut self, weather: u8) {
+        self.history.push(EditCommand::Weather {
+            before: self.fixed_weather,
+            after: weather,
+        });
+        self.fixed_weather = weather;
+    }
+
+    pub fn set_music(&mut self, music: Option<String>) {
+        self.history.push(EditCommand::Music {
+            before: self.music.clone(),
+            after: music.clone(),
+        });
+        self.music = music;
+    }
+
+    // Restore the data of a command, either the data before or after the change
+    fn apply_command(
+        &mut self,
+        systems: &mut DrawSetting,
+        command: &EditCommand,
+        use_after: bool,
+    ) {
+        // The tiles can be on the linked map strips, they are marked when set
+        if !matches!(command, EditCommand::Tile { .. }) {
+            self.map_changed = true;
+        }
+        match command {
+            EditCommand::Tile {
+                pos,
+                layer,
+                before,
+                after,
+            } => {
+                let id = if use_after { *after } else { *before };
+                self.set_map_tile(
+                    *pos,
+                    *layer,
+                    if id > 0 {
+                        TileData {
+                            id: id as usize,
+                            color: Color::rgba(255, 255, 255, 255),
+                        }
+                    } else {
+                        TileData::default()
+                    },
+                );
+            }
+            EditCommand::Attribute { pos, before, after } => {
+                let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
+                let attributes = if use_after { after } else { before };
+                self.map_attributes[tilenum]
+                    .set_attributes(systems, attributes.clone());
+            }
+            EditCommand::Zone {
+                pos,
+                zone,
+                before,
+                after,
+            } => {
+                let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
+                let exist = if use_after { *after } else { *before };
+                let zone_index = *zone;
+                let zone = match self.map_zone_loc.get_mut(zone_index) {
+                    Some(zone) => zone,
+                    None => return,
+                };
+                if exist {
+                    systems.gfx.set_color(
+                        self.map_zone[tilenum],
+                        get_zone_color(zone_index),
+                    );
+                    if !zone.contains(*pos) {
+                        zone.pos.push(*pos);
+                    }
+                } else {
+                    systems.gfx.set_color(
+                        self.map_zone[tilenum],
+                        Color::rgba(0, 0, 0, 0),
+                    );
+                    zone.pos.retain(|&check_pos| check_pos != *pos);
+                }
+            }
+            EditCommand::DirBlock { pos, before, after } => {
+                let tilenum = get_tile_pos(pos.x as i32, pos.y as i32);
+                let dir_block = &mut self.map_dir_block[tilenum];
+                dir_block.dir_data = if use_after { *after } else { *before };
+                // The arrows are only shown while the dir block tab is open
+                if systems.gfx.get_visible(dir_block.bg) {
+                    dir_block.update(systems);
+                }
+            }
+            EditCommand::ZoneSetting {
+                zone,
+                before,
+                after,
+            } => {
+                if let Some(setting) = self.map_zone_setting.get_mut(*zone) {
+                    *setting = if use_after { after } else { before }.clone();
+                }
+            }
+            EditCommand::ZoneList {
+                zone,
+                before,
+                after,
+            } => {
+                let data = if use_after { after } else { before };
+                match data {
+                    Some((setting, pos)) => {
+                        let index = (*zone).min(self.map_zone_setting.len());
+                        self.map_zone_setting.insert(index, setting.clone());
+                        self.map_zone_loc
+                            .insert(index, MapZone { pos: pos.clone() });
+                    }
+                    None => {
+                        if *zone < self.map_zone_setting.len() {
+                            self.map_zone_setting.remove(*zone);
+                            self.map_zone_loc.remove(*zone);
                         }
                     }
                 }
+                self.map_zone.iter().for_each(|zone| {
+                    systems.gfx.set_color(*zone, Color::rgba(0, 0, 0, 0));
+                });
+            }
+            EditCommand::Entity { pos, before, after } => {
+                let entity = if use_after { after } else { before };
+                self.place_entity(systems, *pos, entity.clone());
+            }
+            EditCommand::Weather { before, after } => {
+                self.fixed_weather = if use_after { *after } else { *before };
+            }
+            EditCommand::Music { before, after } => {
+                self.music = if use_after { after } else { before }.clone();
+            }
+        }
+    }
+
+    pub fn undo(&mut self, systems: &mut DrawSetting) -> bool {
+        let group = match self.history.take_undo() {
+            Some(group) => group,
+            None => return false,
+        };
+        group.commands.values().rev().for_each(|command| {
+            self.apply_command(systems, command, false);
+        });
+        self.history.done_undo(group);
+        true
+    }
+
+    pub fn redo(&mut self, systems: &mut DrawSetting) -> bool {
+        let group = match self.history.take_redo() {
+            Some(group) => group,
+            None => return false,
+        };
+        group.commands.values().for_each(|command| {
+            self.apply_command(systems, command, true);
+        });
+        self.history.done_redo(group);
+        true
+    }
+
+    // Undo or redo until the history reaches the selected position
+    pub fn jump_history(
+        &mut self,
+        systems: &mut DrawSetting,
+        position: usize,
+    ) -> bool {
+        let mut did_change = false;
+        while self.history.position() > position {
+            if !self.undo(systems) {
+                break;
+            }
+            did_change = true;
+        }
+        while self.history.position() < position {
+            if !self.redo(systems) {
+                break;
             }
-            self.record.stop_record();
+            did_change = true;
         }
+        did_change
     }
 }
 
 pub fn get_zone_color(zone_index: usize) -> Color {
     match zone_index {
+        0 => Color::rgba(40, 40, 200, 140),
         1 => Color::rgba(200, 40, 40, 140),
         2 => Color::rgba(40, 200, 40, 140),
         3 => Color::rgba(150, 40, 150, 140),
         4 => Color::rgba(40, 150, 150, 140),
-        _ => Color::rgba(40, 40, 200, 140),
+        _ => {
+            // Spread the hue of the other zones with the golden angle
+            // so that neighbouring zones do not look alike
+            let hue = (zone_index as f32 * 137.508) % 360.0;
+            let sector = hue / 60.0;
+            let x = 1.0 - (sector % 2.0 - 1.0).abs();
+            let (r, g, b) = match sector as u32 {
+                0 => (1.0, x, 0.0),
+                1 => (x, 1.0, 0.0),
+                2 => (0.0, 1.0, x),
+                3 => (0.0, x, 1.0),
+                4 => (x, 0.0, 1.0),
+                _ => (1.0, 0.0, x),
+            };
+            // Keep the colors on the same range as the first zones
+            let to_u8 = |value: f32| (40.0 + value * 160.0) as u8;
+            Color::rgba(to_u8(r), to_u8(g), to_u8(b), 140)
+        }
+    }
+}
+
+pub fn get_entity_color(kind: EntityKind) -> Color {
+    match kind {
+        EntityKind::Npc => Color::rgba(230, 150, 30, 170),
+        EntityKind::Object => Color::rgba(30, 170, 170, 170),
     }
 }
 
 pub fn in_map(screen_pos: Vec2, mapview: &MapView) -> bool {
-    screen_pos.x >= mapview.maps[0].pos.x
-        && screen_pos.x <= mapview.maps[0].pos.x + (32 * TEXTURE_SIZE) as f32
-        && screen_pos.y >= mapview.maps[0].pos.y
-        && screen_pos.y <= mapview.maps[0].pos.y + (32 * TEXTURE_SIZE) as f32
+    // Anything outside of the map view is clipped, even when zoomed in
+    if !mapview.camera.in_view(screen_pos) {
+        return false;
+    }
+    let world_pos = mapview.camera.screen_to_world(screen_pos);
+    world_pos.x >= mapview.maps[0].pos.x
+        && world_pos.x < mapview.maps[0].pos.x + (32 * TEXTURE_SIZE) as f32
+        && world_pos.y >= mapview.maps[0].pos.y
+        && world_pos.y < mapview.maps[0].pos.y + (32 * TEXTURE_SIZE) as f32
+}
+
+// Check if the position is pointing to a linked map strip that can be edited
+pub fn in_link_map(screen_pos: Vec2, mapview: &MapView) -> bool {
+    if !mapview.camera.in_view(screen_pos) {
+        return false;
+    }
+    let world_pos = mapview.camera.screen_to_world(screen_pos);
+    get_link_map_at(world_pos, mapview)
+        .is_some_and(|maplink| mapview.link_map_exist[maplink])
+}
+
+fn get_link_map_at(world_pos: Vec2, mapview: &MapView) -> Option<usize> {
+    (0..8).find(|&maplink| {
+        let (_, _, _, size) = get_link_map_info(maplink);
+        let pos = mapview.maps[maplink + 1].pos;
+        world_pos.x >= pos.x
+            && world_pos.x < pos.x + size.x * TEXTURE_SIZE as f32
+            && world_pos.y >= pos.y
+            && world_pos.y < pos.y + size.y * TEXTURE_SIZE as f32
+    })
 }
 
 pub fn get_map_pos(screen_pos: Vec2, mapview: &MapView) -> Vec2 {
+    let world_pos = mapview.camera.screen_to_world(screen_pos);
+
+    // The linked map strips are placed with a small gap from the center map,
+    // so their tile positions are calculated from their own position
+    if let Some(maplink) = get_link_map_at(world_pos, mapview) {
+        let (offset_x, offset_y, start, _) = get_link_map_info(maplink);
+        let tile_pos = world_pos - mapview.maps[maplink + 1].pos;
+        return Vec2::new(
+            (offset_x * 32) as f32
+                + start.x
+                + (tile_pos.x / TEXTURE_SIZE as f32).floor(),
+            (offset_y * 32) as f32
+                + start.y
+                + (tile_pos.y / TEXTURE_SIZE as f32).floor(),
+        );
+    }
+
     let tile_pos =
-        screen_pos - Vec2::new(mapview.maps[0].pos.x, mapview.maps[0].pos.y);
+        world_pos - Vec2::new(mapview.maps[0].pos.x, mapview.maps[0].pos.y);
     Vec2::new(
         (tile_pos.x / TEXTURE_SIZE as f32).floor(),
         (tile_pos.y / TEXTURE_SIZE as f32).floor(),
@@ -2,8 +2,6 @@ use bytey::{ByteBufferRead, ByteBufferWrite};
 use graphics::*;
 use serde::{Deserialize, Serialize};
 
-pub const MAX_ATTRIBUTE: usize = 8;
-
 #[derive(
     Clone,
     Debug,
@@ -40,6 +38,120 @@ pub struct ItemSpawnData {
     pub timer: u64,
 }
 
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub enum TriggerKind {
+    // Runs when a player steps on the tile
+    #[default]
+    Step,
+    // Runs when a player faces the tile and presses the action key
+    Interact,
+    // Runs when a player comes within the radius of the tile
+    Proximity,
+}
+
+impl TriggerKind {
+    pub const KINDS: [TriggerKind; 3] = [
+        TriggerKind::Step,
+        TriggerKind::Interact,
+        TriggerKind::Proximity,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TriggerKind::Step => "Step",
+            TriggerKind::Interact => "Interact",
+            TriggerKind::Proximity => "Proximity",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        TriggerKind::KINDS
+            .into_iter()
+            .find(|kind| kind.as_str() == name)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub struct TriggerParam {
+    pub key: String,
+    pub value: String,
+}
+
+// Event that the server will run, such as a cutscene, quest flag or door
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub struct TriggerData {
+    pub kind: TriggerKind,
+    pub script: String,
+    // Distance in tiles, only used by proximity triggers
+    pub radius: u32,
+    pub params: Vec<TriggerParam>,
+}
+
+// Parameters are edited as one key=value pair on each line
+impl std::fmt::Display for TriggerParam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+impl TriggerData {
+    pub fn params_to_text(&self) -> String {
+        self.params
+            .iter()
+            .map(TriggerParam::to_string)
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn params_from_text(text: &str) -> Vec<TriggerParam> {
+        text.lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                let key = key.trim();
+                if key.is_empty() {
+                    return None;
+                }
+                Some(TriggerParam {
+                    key: key.to_string(),
+                    value: value.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -59,10 +171,39 @@ pub enum MapAttribute {
     ItemSpawn(ItemSpawnData),
     Storage,
     Shop(u16),
+    // Attribute types that are only declared on the attribute schema
+    Custom(CustomAttribute),
+    // Sign that holds a key of the string table instead of the text
+    LocalizedSign(String),
+    Trigger(TriggerData),
     Count,
 }
 
-#[derive(Debug)]
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub struct CustomAttribute {
+    pub name: String,
+    pub data: Vec<InsertTypes>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
 pub enum InsertTypes {
     Int(i64),
     UInt(u64),
@@ -91,95 +232,126 @@ impl InsertTypes {
             _ => String::new(),
         }
     }
-}
 
-impl MapAttribute {
-    pub fn as_str<'a>(attribute: u32) -> &'a str {
-        match attribute {
-            0 => "Walkable",
-            1 => "Blocked",
-            2 => "NpcBlocked",
-            3 => "Warp",
-            4 => "Sign",
-            5 => "Item",
-            6 => "Storage",
-            7 => "Shop",
-            _ => "",
+    pub fn get_bool(&self) -> bool {
+        match self {
+            InsertTypes::Bool(data) => *data,
+            _ => false,
         }
     }
+}
 
-    pub fn as_map_str<'a>(attribute: &MapAttribute) -> &'a str {
-        match attribute {
-            MapAttribute::Blocked => "B",
-            MapAttribute::NpcBlocked => "N",
-            MapAttribute::Warp(_) => "W",
-            MapAttribute::Sign(_) => "S",
-            MapAttribute::ItemSpawn(_) => "I",
-            MapAttribute::Storage => "S",
-            MapAttribute::Shop(_) => "S",
-            _ => "",
+impl MapAttribute {
+    // The name that is used to find the attribute type on the schema
+    pub fn name(&self) -> &str {
+        match self {
+            MapAttribute::Blocked => "Blocked",
+            MapAttribute::NpcBlocked => "NpcBlocked",
+            MapAttribute::Warp(_) => "Warp",
+            MapAttribute::Sign(_) | MapAttribute::LocalizedSign(_) => "Sign",
+            MapAttribute::ItemSpawn(_) => "Item",
+            MapAttribute::Storage => "Storage",
+            MapAttribute::Shop(_) => "Shop",
+            MapAttribute::Custom(data) => &data.name,
+            MapAttribute::Trigger(_) => "Trigger",
+            _ => "Walkable",
         }
     }
 
-    pub fn get_color(attribute: &MapAttribute) -> Color {
-        match attribute {
-            MapAttribute::Blocked => Color::rgba(200, 10, 10, 100),
-            MapAttribute::NpcBlocked => Color::rgba(200, 50, 10, 100),
-            MapAttribute::Warp(_) => Color::rgba(10, 10, 200, 100),
-            MapAttribute::Sign(_) => Color::rgba(10, 200, 10, 100),
-            MapAttribute::ItemSpawn(_) => Color::rgba(180, 180, 180, 100),
-            MapAttribute::Storage => Color::rgba(160, 170, 20, 255),
-            MapAttribute::Shop(_) => Color::rgba(200, 50, 100, 255),
-            _ => Color::rgba(0, 0, 0, 0),
+    // The field values of the attribute, in the order of the schema fields
+    pub fn get_data(&self) -> Vec<InsertTypes> {
+        match self {
+            MapAttribute::Warp(warpdata) => vec![
+                InsertTypes::Int(warpdata.map_x as i64),
+                InsertTypes::Int(warpdata.map_y as i64),
+                InsertTypes::UInt(warpdata.map_group),
+                InsertTypes::UInt(warpdata.tile_x as u64),
+                InsertTypes::UInt(warpdata.tile_y as u64),
+            ],
+            MapAttribute::Sign(text) => {
+                vec![InsertTypes::Str(text.clone()), InsertTypes::Bool(false)]
+            }
+            MapAttribute::LocalizedSign(key) => {
+                vec![InsertTypes::Str(key.clone()), InsertTypes::Bool(true)]
+            }
+            MapAttribute::ItemSpawn(itemdata) => vec![
+                InsertTypes::UInt(itemdata.index as u64),
+                InsertTypes::UInt(itemdata.amount as u64),
+                InsertTypes::UInt(itemdata.timer),
+            ],
+            MapAttribute::Shop(index) => vec![InsertTypes::UInt(*index as u64)],
+            MapAttribute::Custom(data) => data.data.clone(),
+            MapAttribute::Trigger(data) => vec![
+                InsertTypes::Str(data.kind.as_str().to_string()),
+                InsertTypes::Str(data.script.clone()),
+                InsertTypes::UInt(data.radius as u64),
+                InsertTypes::Str(data.params_to_text()),
+            ],
+            _ => vec![],
         }
     }
 
-    pub fn convert_to_enum(attribute: u32, data: &[InsertTypes]) -> Self {
-        match attribute {
-            1 => MapAttribute::Blocked,
-            2 => MapAttribute::NpcBlocked,
-            3 => MapAttribute::Warp(WarpData {
-                map_x: data[0].get_int() as i32,
-                map_y: data[1].get_int() as i32,
-                map_group: data[2].get_uint(),
-                tile_x: data[3].get_uint() as u32,
-                tile_y: data[4].get_uint() as u32,
+    pub fn from_data(name: &str, data: &[InsertTypes]) -> Self {
+        let int = |index: usize| {
+            data.get(index)
+                .map(|value| value.get_int())
+                .unwrap_or_default()
+        };
+        let uint = |index: usize| {
+            data.get(index)
+                .map(|value| value.get_uint())
+                .unwrap_or_default()
+        };
+        match name {
+            "Walkable" => MapAttribute::Walkable,
+            "Blocked" => MapAttribute::Blocked,
+            "NpcBlocked" => MapAttribute::NpcBlocked,
+            "Warp" => MapAttribute::Warp(WarpData {
+                map_x: int(0) as i32,
+                map_y: int(1) as i32,
+                map_group: uint(2),
+                tile_x: uint(3) as u32,
+                tile_y: uint(4) as u32,
             }),
-            4 => MapAttribute::Sign(data[0].get_string()),
-            5 => MapAttribute::ItemSpawn(ItemSpawnData {
-                index: data[0].get_uint() as u32,
-                amount: data[1].get_uint() as u16,
-                timer: data[1].get_uint(),
+            "Sign" => {
+                let text = data
+                    .first()
+                    .map(|value| value.get_string())
+                    .unwrap_or_default();
+                let is_key = data
+                    .get(1)
+                    .map(|value| value.get_bool())
+                    .unwrap_or_default();
+                if is_key {
+                    MapAttribute::LocalizedSign(text)
+                } else {
+                    MapAttribute::Sign(text)
+                }
+            }
+            "Item" => MapAttribute::ItemSpawn(ItemSpawnData {
+                index: uint(0) as u32,
+                amount: uint(1) as u16,
+                timer: uint(2),
+            }),
+            "Storage" => MapAttribute::Storage,
+            "Shop" => MapAttribute::Shop(uint(0) as u16),
+            "Trigger" => {
+                let string = |index: usize| {
+                    data.get(index)
+                        .map(|value| value.get_string())
+                        .unwrap_or_default()
+                };
+                MapAttribute::Trigger(TriggerData {
+                    kind: TriggerKind::from_name(&string(0)),
+                    script: string(1),
+                    radius: uint(2) as u32,
+                    params: TriggerData::params_from_text(&string(3)),
+                })
+            }
+            _ => MapAttribute::Custom(CustomAttribute {
+                name: name.to_string(),
+                data: data.to_vec(),
             }),
-            6 => MapAttribute::Storage,
-            7 => MapAttribute::Shop(data[0].get_uint() as u16),
-            _ => MapAttribute::Walkable,
-        }
-    }
-
-    pub fn convert_to_plain_enum(attribute: u32) -> Self {
-        match attribute {
-            1 => MapAttribute::Blocked,
-            2 => MapAttribute::NpcBlocked,
-            3 => MapAttribute::Warp(WarpData::default()),
-            4 => MapAttribute::Sign(String::new()),
-            5 => MapAttribute::ItemSpawn(ItemSpawnData::default()),
-            6 => MapAttribute::Storage,
-            7 => MapAttribute::Shop(0),
-            _ => MapAttribute::Walkable,
-        }
-    }
-
-    pub fn convert_to_num(attribute: &MapAttribute) -> u32 {
-        match attribute {
-            MapAttribute::Blocked => 1,
-            MapAttribute::NpcBlocked => 2,
-            MapAttribute::Warp(_) => 3,
-            MapAttribute::Sign(_) => 4,
-            MapAttribute::ItemSpawn(_) => 5,
-            MapAttribute::Storage => 6,
-            MapAttribute::Shop(_) => 7,
-            _ => 0,
         }
     }
 }
@@ -0,0 +1,106 @@
+use graphics::*;
+
+use crate::collection::*;
+
+// The area on screen where the map view (center map and linked maps) is drawn
+pub const MAPVIEW_POS: Vec2 = Vec2::new(215.0, 35.0);
+pub const MAPVIEW_SIZE: Vec2 = Vec2::new(724.0, 724.0);
+
+pub struct MapCamera {
+    pub zoom: f32,
+    pub pan: Vec2,
+    pub changed: bool,
+    min_zoom: f32,
+    max_zoom: f32,
+    zoom_step: f32,
+}
+
+impl MapCamera {
+    pub fn new() -> Self {
+        MapCamera::with_limits(MIN_MAP_ZOOM, MAX_MAP_ZOOM, MAP_ZOOM_STEP)
+    }
+
+    pub fn with_limits(min_zoom: f32, max_zoom: f32, zoom_step: f32) -> Self {
+        Self {
+            zoom: 1.0,
+            pan: Vec2::new(0.0, 0.0),
+            changed: true,
+            min_zoom,
+            max_zoom,
+            zoom_step,
+        }
+    }
+
+    pub fn center() -> Vec2 {
+        MAPVIEW_POS + (MAPVIEW_SIZE * 0.5)
+    }
+
+    // The camera scales the world around the center of the map view,
+    // then moves it by the pan offset. Screen = World * Zoom + Translation
+    pub fn translation(&self) -> Vec2 {
+        (MapCamera::center() * (1.0 - self.zoom)) + self.pan
+    }
+
+    pub fn screen_to_world(&self, screen_pos: Vec2) -> Vec2 {
+        (screen_pos - self.translation()) / self.zoom
+    }
+
+    pub fn world_to_screen(&self, world_pos: Vec2) -> Vec2 {
+        (world_pos * self.zoom) + self.translation()
+    }
+
+    pub fn in_view(&self, screen_pos: Vec2) -> bool {
+        screen_pos.x >= MAPVIEW_POS.x
+            && screen_pos.x <= MAPVIEW_POS.x + MAPVIEW_SIZE.x
+            && screen_pos.y >= MAPVIEW_POS.y
+            && screen_pos.y <= MAPVIEW_POS.y + MAPVIEW_SIZE.y
+    }
+
+    // Zoom while keeping the world position under the anchor at the same spot on screen
+    pub fn set_zoom(&mut self, zoom: f32, anchor: Vec2) {
+        let zoom = zoom.clamp(self.min_zoom, self.max_zoom);
+        if zoom == self.zoom {
+            return;
+        }
+        let world_anchor = self.screen_to_world(anchor);
+        self.zoom = zoom;
+        let new_anchor = self.world_to_screen(world_anchor);
+        self.pan += anchor - new_anchor;
+        self.changed = true;
+    }
+
+    pub fn zoom_in(&mut self, anchor: Vec2) {
+        self.set_zoom(self.zoom + self.zoom_step, anchor);
+    }
+
+    pub fn zoom_out(&mut self, anchor: Vec2) {
+        self.set_zoom(self.zoom - self.zoom_step, anchor);
+    }
+
+    pub fn move_pan(&mut self, amount: Vec2) {
+        if amount == Vec2::new(0.0, 0.0) {
+            return;
+        }
+        self.pan += amount;
+        self.changed = true;
+    }
+
+    // Center the view on the given world area and zoom until it fits
+    pub fn fit_area(&mut self, pos: Vec2, size: Vec2) {
+        let fit = MAPVIEW_SIZE / size.max(Vec2::new(1.0, 1.0));
+        self.zoom = fit.x.min(fit.y).clamp(self.min_zoom, self.max_zoom);
+        self.pan = (MapCamera::center() - (pos + (size * 0.5))) * self.zoom;
+        self.changed = true;
+    }
+
+    pub fn reset(&mut self) {
+        self.zoom = 1.0;
+        self.pan = Vec2::new(0.0, 0.0);
+        self.changed = true;
+    }
+
+    // Attribute letters and the grid become unreadable when zoomed out too far
+    pub fn show_labels(&self) -> bool {
+        self.zoom >= MIN_LABEL_ZOOM
+    }
+}
@@ -0,0 +1,455 @@
+use graphics::*;
+use std::cmp::Reverse;
+
+use crate::{attributes::*, map_data::*};
+
+use super::MapEntity;
+
+// What was changed between the two maps
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    Tile(usize),
+    Attribute,
+    DirBlock,
+    Zone,
+    Entity,
+    Setting,
+}
+
+impl DiffKind {
+    // Color of the tile on the map view, each layer has its own shade
+    pub fn color(&self) -> Color {
+        match self {
+            DiffKind::Tile(layer) => {
+                let shade = 255 - (*layer).min(8) as u8 * 12;
+                Color::rgba(shade, 200, 40, 140)
+            }
+            DiffKind::Attribute => Color::rgba(200, 40, 200, 140),
+            DiffKind::DirBlock => Color::rgba(40, 120, 220, 140),
+            DiffKind::Zone => Color::rgba(40, 200, 120, 140),
+            DiffKind::Entity => Color::rgba(230, 120, 30, 140),
+            DiffKind::Setting => Color::rgba(200, 200, 200, 140),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DiffChange {
+    pub kind: DiffKind,
+    // None when the change is on the map settings
+    pub tile: Option<(u32, u32)>,
+    pub reason: String,
+}
+
+// Comparison of the map on the map view with another map file, the other
+// map is kept so that the changed tiles can be reverted to it
+pub struct MapDiff {
+    pub key: String,
+    pub source: String,
+    pub other: MapData,
+    pub changes: Vec<DiffChange>,
+}
+
+impl MapDiff {
+    pub fn new(
+        key: &str,
+        source: &str,
+        current: &MapData,
+        other: MapData,
+    ) -> Self {
+        let changes = compare_maps(current, &other);
+        Self {
+            key: key.to_string(),
+            source: source.to_string(),
+            other,
+            changes,
+        }
+    }
+
+    pub fn update(&mut self, current: &MapData) {
+        self.changes = compare_maps(current, &self.other);
+    }
+
+    // Tiles to highlight, the first change of the tile gives the color
+    pub fn overlay(&self) -> Vec<(usize, Color)> {
+        let mut tiles: Vec<(usize, Color)> = Vec::new();
+        for change in self.changes.iter() {
+            if let Some((x, y)) = change.tile {
+                let tile_num = get_tile_pos(x as i32, y as i32);
+                if !tiles.iter().any(|(check, _)| *check == tile_num) {
+                    tiles.push((tile_num, change.kind.color()));
+                }
+            }
+        }
+        tiles
+    }
+}
+
+fn attribute_names(list: &[MapAttribute]) -> String {
+    if list.is_empty() {
+        return "None".to_string();
+    }
+    list.iter()
+        .map(|attribute| attribute.name().to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Zones are named by the user, the index is only used for the zones that
+// have no name as it changes when a zone before them is removed
+pub fn zone_label(zone: &MapZoneData, index: usize) -> String {
+    if zone.name.trim().is_empty() {
+        format!("Zone {}", index + 1)
+    } else {
+        format!("Zone {}", zone.name)
+    }
+}
+
+fn entity_name(entity: Option<&MapEntity>) -> String {
+    match entity {
+        Some(entity) => format!("{} {}", entity.kind.as_str(), entity.index),
+        None => "None".to_string(),
+    }
+}
+
+// Index of the zone of the other map that each zone of the current map
+// came from. A removed zone moves the zones that follow it on the list, so
+// the zones are matched by their content: equal zones first, then by their
+// name and then by the spawn tiles that they share
+pub fn match_zones(
+    current: &[MapZoneData],
+    other: &[MapZoneData],
+) -> Vec<Option<usize>> {
+    let mut found = vec![None; current.len()];
+    let mut used = vec![false; other.len()];
+    let scores: [&dyn Fn(&MapZoneData, &MapZoneData) -> usize; 3] = [
+        &|zone, other_zone| (zone == other_zone) as usize,
+        &|zone, other_zone| (zone.name == other_zone.name) as usize,
+        &|zone, other_zone| {
+            zone.spawns
+                .iter()
+                .filter(|spawn| other_zone.spawns.contains(spawn))
+                .count()
+        },
+    ];
+    for get_score in scores {
+        for (index, zone) in current.iter().enumerate() {
+            if found[index].is_some() {
+                continue;
+            }
+            // The first zone is picked when the scores are the same
+            let best = other
+                .iter()
+                .enumerate()
+                .filter(|(other_index, _)| !used[*other_index])
+                .map(|(other_index, other_zone)| {
+                    (other_index, get_score(zone, other_zone))
+                })
+                .filter(|(_, score)| *score > 0)
+                .max_by_key(|(other_index, score)| {
+                    (*score, Reverse(*other_index))
+                });
+            if let Some((other_index, _)) = best {
+                found[index] = Some(other_index);
+                used[other_index] = true;
+            }
+        }
+    }
+    found
+}
+
+// Whether each zone of the current map has the tile as a spawn on the
+// other map. A zone that is only on the other map can't be brought back
+// by a revert, so its spawn tiles are not listed as tile changes
+pub fn revert_zone_spawns(
+    current: &[MapZoneData],
+    other: &[MapZoneData],
+    spawn: (u16, u16),
+) -> Vec<bool> {
+    match_zones(current, other)
+        .into_iter()
+        .map(|other_index| {
+            other_index
+                .and_then(|index| other.get(index))
+                .map(|zone| zone.spawns.contains(&spawn))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+// List the changes from the other map to the current map, the values are
+// written as "other -> current"
+pub fn compare_maps(current: &MapData, other: &MapData) -> Vec<DiffChange> {
+    let mut changes = Vec::new();
+    let zone_match = match_zones(&current.zones, &other.zones);
+
+    for y in 0..32 {
+        for x in 0..32 {
+            let tile_num = get_tile_pos(x, y);
+            let tile = Some((x as u32, y as u32));
+            let mut add_change = |kind: DiffKind, reason: String| {
+                changes.push(DiffChange { kind, tile, reason })
+            };
+
+            for layer in 0..current.tile.len().max(other.tile.len()) {
+                let id = |data: &MapData| {
+                    data.tile
+                        .get(layer)
+                        .and_then(|tile| tile.id.get(tile_num))
+                        .copied()
+                        .unwrap_or_default()
+                };
+                let (before, after) = (id(other), id(current));
+                if before != after {
+                    add_change(
+                        DiffKind::Tile(layer),
+                        format!("Layer {} {} -> {}", layer + 1, before, after),
+                    );
+                }
+            }
+
+            let attribute = |data: &MapData| {
+                data.attribute.get(tile_num).cloned().unwrap_or_default()
+            };
+            let (before, after) = (attribute(other), attribute(current));
+            if before != after {
+                add_change(
+                    DiffKind::Attribute,
+                    format!(
+                        "Attributes {} -> {}",
+                        attribute_names(&before),
+                        attribute_names(&after)
+                    ),
+                );
+            }
+
+            let dir_block = |data: &MapData| {
+                data.dir_block.get(tile_num).copied().unwrap_or_default()
+            };
+            let (before, after) = (dir_block(other), dir_block(current));
+            if before != after {
+                add_change(
+                    DiffKind::DirBlock,
+                    format!("Dir block {} -> {}", before, after),
+                );
+            }
+
+            let spawn = (x as u16, y as u16);
+            for (index, zone) in current.zones.iter().enumerate() {
+                let before = zone_match[index]
+                    .map(|other_index| {
+                        other.zones[other_index].spawns.contains(&spawn)
+                    })
+                    .unwrap_or_default();
+                let after = zone.spawns.contains(&spawn);
+                if before != after {
+                    add_change(
+                        DiffKind::Zone,
+                        format!(
+                            "{} spawn {}",
+                            zone_label(zone, index),
+                            if after { "added" } else { "removed" }
+                        ),
+                    );
+                }
+            }
+
+            let entity = |data: &MapData| {
+                data.entities
+                    .iter()
+                    .find(|entity| entity.x == spawn.0 && entity.y == spawn.1)
+                    .cloned()
+            };
+            let (before, after) = (entity(other), entity(current));
+            if before != after {
+                add_change(
+                    DiffKind::Entity,
+                    format!(
+                        "Entity {} -> {}",
+                        entity_name(before.as_ref()),
+                        entity_name(after.as_ref())
+                    ),
+                );
+            }
+        }
+    }
+
+    // The settings are not on a tile, they are only listed
+    let mut add_setting = |reason: String| {
+        changes.push(DiffChange {
+            kind: DiffKind::Setting,
+            tile: None,
+            reason,
+        })
+    };
+    for (index, other_zone) in other.zones.iter().enumerate() {
+        if !zone_match.contains(&Some(index)) {
+            add_setting(format!(
+                "{} removed, reverting a tile does not bring it back",
+                zone_label(other_zone, index)
+            ));
+        }
+    }
+    for (index, zone) in current.zones.iter().enumerate() {
+        match zone_match[index].map(|other_index| &other.zones[other_index]) {
+            Some(other_zone) => {
+                if zone.name != other_zone.name
+                    || zone.max_npc != other_zone.max_npc
+                    || zone.npcs != other_zone.npcs
+                {
+                    add_setting(format!(
+                        "{} settings changed",
+                        zone_label(zone, index)
+                    ));
+                }
+            }
+            None => add_setting(format!("{} added", zone_label(zone, index))),
+        }
+    }
+    if current.music != other.music {
+        add_setting(format!(
+            "Music {} -> {}",
+            other.music.as_deref().unwrap_or("None"),
+            current.music.as_deref().unwrap_or("None")
+        ));
+    }
+    if current.weather != other.weather {
+        add_setting(format!(
+            "Weather {:?} -> {:?}",
+            other.weather, current.weather
+        ));
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_zone(name: &str, spawns: &[(u16, u16)]) -> MapZoneData {
+        MapZoneData {
+            name: name.to_string(),
+            max_npc: 1,
+            npcs: Vec::new(),
+            spawns: spawns.to_vec(),
+        }
+    }
+
+    fn test_map() -> MapData {
+        let mut mapdata = MapData::default(0, 0, 0);
+        mapdata.zones = vec![
+            test_zone("North", &[(1, 1)]),
+            test_zone("Middle", &[(2, 2)]),
+            test_zone("South", &[(3, 3), (4, 4)]),
+        ];
+        mapdata
+    }
+
+    #[test]
+    fn same_maps_have_no_changes() {
+        assert!(compare_maps(&test_map(), &test_map()).is_empty());
+    }
+
+    #[test]
+    fn changes_are_listed_on_their_tile() {
+        let other = test_map();
+        let mut current = test_map();
+        current.tile[2].id[get_tile_pos(5, 6)] = 4;
+        current.dir_block[get_tile_pos(7, 8)] = 1;
+        current.zones[2].spawns.push((9, 9));
+        current.weather = Weather::Rain;
+
+        let changes = compare_maps(&current, &other);
+        let found: Vec<(DiffKind, Option<(u32, u32)>)> = changes
+            .iter()
+            .map(|change| (change.kind, change.tile))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (DiffKind::Tile(2), Some((5, 6))),
+                (DiffKind::DirBlock, Some((7, 8))),
+                (DiffKind::Zone, Some((9, 9))),
+                (DiffKind::Setting, None),
+            ]
+        );
+        assert_eq!(changes[0].reason, "Layer 3 0 -> 4");
+    }
+
+    #[test]
+    fn removed_zone_does_not_move_the_others() {
+        let other = test_map();
+        let mut current = test_map();
+        current.zones.remove(1);
+
+        let changes = compare_maps(&current, &other);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, DiffKind::Setting);
+        assert_eq!(
+            changes[0].reason,
+            "Zone Middle removed, reverting a tile does not bring it back"
+        );
+    }
+
+    // Same as the tile revert of the map view, done on the map data
+    fn revert_tile(current: &mut MapData, other: &MapData, spawn: (u16, u16)) {
+        let tile_num = get_tile_pos(spawn.0 as i32, spawn.1 as i32);
+        for (layer, tile) in current.tile.iter_mut().enumerate() {
+            tile.id[tile_num] = other.tile[layer].id[tile_num];
+        }
+        current.attribute[tile_num] = other.attribute[tile_num].clone();
+        current.dir_block[tile_num] = other.dir_block[tile_num];
+        let spawns = revert_zone_spawns(&current.zones, &other.zones, spawn);
+        for (zone, has_spawn) in current.zones.iter_mut().zip(spawns) {
+            zone.spawns.retain(|check| *check != spawn);
+            if has_spawn {
+                zone.spawns.push(spawn);
+            }
+        }
+        let on_tile = |entity: &MapEntity| (entity.x, entity.y) == spawn;
+        current.entities.retain(|entity| !on_tile(entity));
+        current
+            .entities
+            .extend(other.entities.iter().filter(|e| on_tile(e)).cloned());
+    }
+
+    #[test]
+    fn reverted_tiles_have_no_changes() {
+        let other = test_map();
+        let mut current = test_map();
+        // The middle zone is only on the other map, with its spawn tile
+        current.zones.remove(1);
+        current.tile[0].id[get_tile_pos(2, 2)] = 6;
+        current.zones[1].spawns.push((2, 2));
+        current.zones.push(test_zone("New", &[(5, 5)]));
+
+        for spawn in [(2, 2), (5, 5)] {
+            revert_tile(&mut current, &other, spawn);
+            let tile = Some((spawn.0 as u32, spawn.1 as u32));
+            let changes = compare_maps(&current, &other);
+            assert!(
+                changes.iter().all(|change| change.tile != tile),
+                "{:?}",
+                changes
+            );
+        }
+    }
+
+    #[test]
+    fn zones_are_matched_by_content() {
+        let other = test_map();
+        let mut current = test_map().zones;
+        // Renamed and moved to the front, it still shares a spawn tile
+        let mut renamed = current.remove(2);
+        renamed.name = "Coast".to_string();
+        renamed.spawns.retain(|spawn| *spawn != (4, 4));
+        current.insert(0, renamed);
+        current.push(test_zone("New", &[(8, 8)]));
+
+        assert_eq!(
+            match_zones(&current, &other.zones),
+            vec![Some(2), Some(0), Some(1), None]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use bit_op::{bit_u8::*, BitOp};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::BufReader;
+
+use crate::{collection::TEXTURE_SIZE, tileset::MAX_TILE_Y, DrawSetting};
+
+const TILE_PROPERTIES_PATH: &str = "./data/tile_properties.json";
+
+// Shape that the dir block draw tool will use
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum DirBlockMode {
+    #[default]
+    Tile,
+    // Every tile of the rectangle will have the selected directions
+    Rect,
+    // Only the outer edges of the rectangle will be blocked
+    Outline,
+}
+
+impl DirBlockMode {
+    pub const MODES: [DirBlockMode; 3] = [
+        DirBlockMode::Tile,
+        DirBlockMode::Rect,
+        DirBlockMode::Outline,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DirBlockMode::Tile => "Tile",
+            DirBlockMode::Rect => "Rect",
+            DirBlockMode::Outline => "Outline",
+        }
+    }
+}
+
+// Convert the selected directions into the dir block bits
+pub fn get_dir_bits(dir_visible: [bool; 4]) -> u8 {
+    // B0 = Down, B1 = Up, B2 = Left, B3 = Right
+    let mut dir_data = 0;
+    if dir_visible[0] {
+        dir_data.set(B1);
+    }
+    if dir_visible[1] {
+        dir_data.set(B2);
+    }
+    if dir_visible[2] {
+        dir_data.set(B0);
+    }
+    if dir_visible[3] {
+        dir_data.set(B3);
+    }
+    dir_data
+}
+
+// Convert the dir block bits back into the directions
+pub fn get_dir_visible(dir_data: u8) -> [bool; 4] {
+    // Up, Left, Down, Right
+    [
+        dir_data.get(B1) != 0,
+        dir_data.get(B2) != 0,
+        dir_data.get(B0) != 0,
+        dir_data.get(B3) != 0,
+    ]
+}
+
+// Tiles of each tilesheet that can not be walked on, listed by their column
+// and row on the tileset, such as { "tile_0.png": [[3, 1], [4, 1]] }
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TileProperties {
+    #[serde(default)]
+    pub blocked: IndexMap<String, Vec<(u32, u32)>>,
+}
+
+impl TileProperties {
+    pub fn is_blocked(&self, systems: &DrawSetting, id: usize) -> bool {
+        let (x, y, tilesheet) = match systems.resource.tile_location.get(&id) {
+            Some(location) => *location,
+            None => return false,
+        };
+        let name = match systems.resource.tilesheet.get(tilesheet as usize) {
+            Some(data) => &data.name,
+            None => return false,
+        };
+        let tile = (x / TEXTURE_SIZE, MAX_TILE_Y - (y / TEXTURE_SIZE) - 1);
+        self.blocked
+            .get(name)
+            .map(|list| list.contains(&tile))
+            .unwrap_or_default()
+    }
+}
+
+// The file is optional, without it only the Blocked attributes are used
+pub fn load_tile_properties() -> TileProperties {
+    match OpenOptions::new().read(true).open(TILE_PROPERTIES_PATH) {
+        Ok(file) => {
+            let reader = BufReader::new(file);
+            match serde_json::from_reader::<_, TileProperties>(reader) {
+                Ok(data) => data,
+                Err(e) => {
+                    println!("Error {:?}", e);
+                    TileProperties::default()
+                }
+            }
+        }
+        Err(_) => TileProperties::default(),
+    }
+}
+
+// Blocked tiles are blocked from every side, and walkable tiles are blocked
+// toward the blocked tiles next to them
+pub fn generate_dir_blocks(blocked: &[bool]) -> Vec<u8> {
+    let is_blocked = |x: i32, y: i32| {
+        (0..32).contains(&x)
+            && (0..32).contains(&y)
+            && blocked[(y * 32 + x) as usize]
+    };
+    (0..1024)
+        .map(|tile_num| {
+            let (x, y) = ((tile_num % 32) as i32, (tile_num / 32) as i32);
+            if is_blocked(x, y) {
+                return get_dir_bits([true; 4]);
+            }
+            // Up, Left, Down, Right
+            get_dir_bits([
+                is_blocked(x, y + 1),
+                is_blocked(x - 1, y),
+                is_blocked(x, y - 1),
+                is_blocked(x + 1, y),
+            ])
+        })
+        .collect()
+}
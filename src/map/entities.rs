@@ -0,0 +1,122 @@
+use bytey::{ByteBufferRead, ByteBufferWrite};
+use serde::{Deserialize, Serialize};
+
+use super::attributes::*;
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub enum EntityKind {
+    // Npc of the game data, such as a shopkeeper or a guard
+    #[default]
+    Npc,
+    // Anything else that the server will place, such as a chest or a door
+    Object,
+}
+
+impl EntityKind {
+    pub const KINDS: [EntityKind; 2] = [EntityKind::Npc, EntityKind::Object];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityKind::Npc => "Npc",
+            EntityKind::Object => "Object",
+        }
+    }
+
+    pub fn letter(&self) -> &'static str {
+        match self {
+            EntityKind::Npc => "N",
+            EntityKind::Object => "O",
+        }
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub enum FacingDir {
+    #[default]
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+impl FacingDir {
+    pub const DIRS: [FacingDir; 4] = [
+        FacingDir::Down,
+        FacingDir::Up,
+        FacingDir::Left,
+        FacingDir::Right,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FacingDir::Down => "Down",
+            FacingDir::Up => "Up",
+            FacingDir::Left => "Left",
+            FacingDir::Right => "Right",
+        }
+    }
+
+    // Shown next to the entity letter on the map view
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            FacingDir::Down => "v",
+            FacingDir::Up => "^",
+            FacingDir::Left => "<",
+            FacingDir::Right => ">",
+        }
+    }
+}
+
+// NPC or object that is placed on an exact tile of the map
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub struct MapEntity {
+    pub kind: EntityKind,
+    // Npc ID of the game data, or the object ID used by the server
+    pub index: u64,
+    pub x: u16,
+    pub y: u16,
+    pub dir: FacingDir,
+    pub properties: Vec<TriggerParam>,
+}
+
+impl MapEntity {
+    pub fn properties_to_text(&self) -> String {
+        self.properties
+            .iter()
+            .map(TriggerParam::to_string)
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
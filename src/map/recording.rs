@@ -1,148 +1,325 @@
-use crate::attributes::*;
 use graphics::*;
 use indexmap::IndexMap;
+use std::collections::VecDeque;
+
+use crate::attributes::*;
+
+use super::{MapEntity, MapZoneSetting};
 
-const MAX_CHANGE: usize = 500;
+// Once the history is full, the oldest edit will be dropped
+const MAX_HISTORY: usize = 200;
 
-#[derive(Debug)]
-pub enum RecordType {
-    Layer,
-    Attribute,
-    Zone,
+// Every edit on the map is stored with the data before and after the change,
+// undo will restore the before data while redo will restore the after data
+#[derive(Clone, Debug)]
+pub enum EditCommand {
+    Tile {
+        pos: Vec2,
+        layer: u32,
+        before: u32,
+        after: u32,
+    },
+    Attribute {
+        pos: Vec2,
+        before: Vec<MapAttribute>,
+        after: Vec<MapAttribute>,
+    },
+    Zone {
+        pos: Vec2,
+        zone: usize,
+        before: bool,
+        after: bool,
+    },
+    DirBlock {
+        pos: Vec2,
+        before: u8,
+        after: u8,
+    },
+    ZoneSetting {
+        zone: usize,
+        before: MapZoneSetting,
+        after: MapZoneSetting,
+    },
+    // A zone being added or removed, None is used when the zone does not exist
+    ZoneList {
+        zone: usize,
+        before: Option<(MapZoneSetting, Vec<Vec2>)>,
+        after: Option<(MapZoneSetting, Vec<Vec2>)>,
+    },
+    Entity {
+        pos: Vec2,
+        before: Option<MapEntity>,
+        after: Option<MapEntity>,
+    },
+    Weather {
+        before: u8,
+        after: u8,
+    },
+    Music {
+        before: Option<String>,
+        after: Option<String>,
+    },
 }
 
-#[derive(Debug)]
-pub struct ChangeData {
-    pub record_type: RecordType,
-    pub pos: Vec3,
-    pub id: i64,
-    pub data: Vec<InsertTypes>,
+impl EditCommand {
+    // Commands with the same key are changing the same data
+    pub fn key(&self) -> String {
+        match self {
+            EditCommand::Tile { pos, layer, .. } => {
+                format!("tile_{}_{}_{}", pos.x, pos.y, layer)
+            }
+            EditCommand::Attribute { pos, .. } => {
+                format!("attribute_{}_{}", pos.x, pos.y)
+            }
+            EditCommand::Zone { pos, zone, .. } => {
+                format!("zone_{}_{}_{}", pos.x, pos.y, zone)
+            }
+            EditCommand::DirBlock { pos, .. } => {
+                format!("dirblock_{}_{}", pos.x, pos.y)
+            }
+            EditCommand::ZoneSetting { zone, .. } => {
+                format!("zonesetting_{}", zone)
+            }
+            EditCommand::ZoneList { zone, .. } => {
+                format!("zonelist_{}", zone)
+            }
+            EditCommand::Entity { pos, .. } => {
+                format!("entity_{}_{}", pos.x, pos.y)
+            }
+            EditCommand::Weather { .. } => "weather".to_string(),
+            EditCommand::Music { .. } => "music".to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EditCommand::Tile { .. } => "Edit Tiles",
+            EditCommand::Attribute { .. } => "Edit Attribute",
+            EditCommand::Zone { .. } => "Edit Zone",
+            EditCommand::DirBlock { .. } => "Edit Dir Block",
+            EditCommand::ZoneSetting { .. } => "Zone Setting",
+            EditCommand::ZoneList { after, .. } => {
+                if after.is_some() {
+                    "Add Zone"
+                } else {
+                    "Remove Zone"
+                }
+            }
+            EditCommand::Entity { .. } => "Edit Placement",
+            EditCommand::Weather { .. } => "Change Weather",
+            EditCommand::Music { .. } => "Change Music",
+        }
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        match self {
+            EditCommand::Tile { before, after, .. } => before == after,
+            EditCommand::Attribute { before, after, .. } => before == after,
+            EditCommand::Zone { before, after, .. } => before == after,
+            EditCommand::DirBlock { before, after, .. } => before == after,
+            EditCommand::ZoneSetting { before, after, .. } => before == after,
+            EditCommand::ZoneList { before, after, .. } => before == after,
+            EditCommand::Entity { before, after, .. } => before == after,
+            EditCommand::Weather { before, after } => before == after,
+            EditCommand::Music { before, after } => before == after,
+        }
+    }
+
+    // Keep our before data but take the after data of the newer command
+    fn merge(&mut self, command: EditCommand) {
+        match (self, command) {
+            (
+                EditCommand::Tile { after, .. },
+                EditCommand::Tile { after: new, .. },
+            ) => *after = new,
+            (
+                EditCommand::Attribute { after, .. },
+                EditCommand::Attribute { after: new, .. },
+            ) => *after = new,
+            (
+                EditCommand::Zone { after, .. },
+                EditCommand::Zone { after: new, .. },
+            ) => *after = new,
+            (
+                EditCommand::DirBlock { after, .. },
+                EditCommand::DirBlock { after: new, .. },
+            ) => *after = new,
+            (
+                EditCommand::ZoneSetting { after, .. },
+                EditCommand::ZoneSetting { after: new, .. },
+            ) => *after = new,
+            (
+                EditCommand::ZoneList { after, .. },
+                EditCommand::ZoneList { after: new, .. },
+            ) => *after = new,
+            (
+                EditCommand::Entity { after, .. },
+                EditCommand::Entity { after: new, .. },
+            ) => *after = new,
+            (
+                EditCommand::Weather { after, .. },
+                EditCommand::Weather { after: new, .. },
+            ) => *after = new,
+            (
+                EditCommand::Music { after, .. },
+                EditCommand::Music { after: new, .. },
+            ) => *after = new,
+            _ => {}
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct Record {
-    pub changes: IndexMap<String, ChangeData>,
+// All commands that are done within a single operation, such as a brush stroke
+#[derive(Clone, Debug)]
+pub struct EditGroup {
+    pub name: String,
+    pub commands: IndexMap<String, EditCommand>,
 }
-pub struct Records {
-    in_record: bool,
-    pub undo: Vec<Record>,
-    pub redo: Vec<Record>,
-    last_index: Option<usize>,
+
+impl EditGroup {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            commands: IndexMap::new(),
+        }
+    }
+
+    fn add(&mut self, command: EditCommand) {
+        let key = command.key();
+        if let Some(data) = self.commands.get_mut(&key) {
+            data.merge(command);
+        } else {
+            self.commands.insert(key, command);
+        }
+    }
 }
 
-impl Records {
+pub struct EditHistory {
+    pub undo: VecDeque<EditGroup>,
+    pub redo: Vec<EditGroup>,
+    current: Option<EditGroup>,
+    pub changed: bool,
+}
+
+impl EditHistory {
     pub fn new() -> Self {
         Self {
-            in_record: false,
-            undo: Vec::new(),
+            undo: VecDeque::new(),
             redo: Vec::new(),
-            last_index: None,
+            current: None,
+            changed: true,
         }
     }
 
-    pub fn set_undo_record(&mut self) {
-        if self.in_record {
+    pub fn begin_group(&mut self, name: &str) {
+        if self.current.is_some() {
             return;
         }
+        self.current = Some(EditGroup::new(name));
+    }
 
-        self.in_record = true;
-        let index = self.undo.len();
-        self.last_index = Some(index);
-        self.undo.push(Record {
-            changes: IndexMap::new(),
-        });
-    }
-
-    pub fn push_undo(
-        &mut self,
-        pos: Vec3,
-        record_type: RecordType,
-        id: i64,
-        data: Vec<InsertTypes>,
-    ) {
-        if !self.in_record {
-            return;
-        }
-        if self.undo.len() >= MAX_CHANGE {
+    pub fn push(&mut self, command: EditCommand) {
+        if let Some(group) = &mut self.current {
+            group.add(command);
             return;
         }
 
-        if let Some(index) = self.last_index {
-            let key_name = format!("{}_{}_{}", pos.x, pos.y, pos.z);
-            if !self.undo[index].changes.contains_key(&key_name) {
-                self.undo[index].changes.insert(
-                    key_name,
-                    ChangeData {
-                        record_type,
-                        pos,
-                        id,
-                        data,
-                    },
-                );
+        // Edits that are done outside of a group, like typing on the zone settings,
+        // are merged with the last group if it is only changing the same data
+        let key = command.key();
+        if let Some(group) = self.undo.back_mut() {
+            if self.redo.is_empty()
+                && group.commands.len() == 1
+                && group.commands.contains_key(&key)
+                && matches!(
+                    command,
+                    EditCommand::ZoneSetting { .. }
+                        | EditCommand::Entity { .. }
+                )
+            {
+                group.add(command);
+                self.changed = true;
+                return;
             }
         }
-    }
 
-    pub fn get_last_undo(&mut self) -> Option<Record> {
-        self.undo.pop()
+        let mut group = EditGroup::new(command.name());
+        group.add(command);
+        self.commit(group);
     }
 
-    pub fn set_redo_record(&mut self) {
-        if self.in_record {
-            return;
+    pub fn end_group(&mut self) {
+        if let Some(group) = self.current.take() {
+            self.commit(group);
         }
+    }
 
-        self.in_record = true;
-        let index = self.redo.len();
-        self.last_index = Some(index);
-        self.redo.push(Record {
-            changes: IndexMap::new(),
-        });
-    }
-
-    pub fn push_redo(
-        &mut self,
-        pos: Vec3,
-        record_type: RecordType,
-        id: i64,
-        data: Vec<InsertTypes>,
-    ) {
-        if !self.in_record {
-            return;
-        }
-        if self.redo.len() >= MAX_CHANGE {
+    fn commit(&mut self, mut group: EditGroup) {
+        group.commands.retain(|_, command| !command.is_unchanged());
+        if group.commands.is_empty() {
             return;
         }
+        self.push_undo(group);
+        self.redo.clear();
+    }
 
-        if let Some(index) = self.last_index {
-            let key_name = format!("{}_{}_{}", pos.x, pos.y, pos.z);
-            if !self.redo[index].changes.contains_key(&key_name) {
-                self.redo[index].changes.insert(
-                    key_name,
-                    ChangeData {
-                        record_type,
-                        pos,
-                        id,
-                        data,
-                    },
-                );
-            }
+    fn push_undo(&mut self, group: EditGroup) {
+        self.undo.push_back(group);
+        while self.undo.len() > MAX_HISTORY {
+            self.undo.pop_front();
         }
+        self.changed = true;
+    }
+
+    pub fn take_undo(&mut self) -> Option<EditGroup> {
+        self.end_group();
+        self.undo.pop_back()
     }
 
-    pub fn get_last_redo(&mut self) -> Option<Record> {
+    pub fn take_redo(&mut self) -> Option<EditGroup> {
+        self.end_group();
         self.redo.pop()
     }
 
-    pub fn clear_redo(&mut self) {
-        self.redo.clear();
+    pub fn done_undo(&mut self, group: EditGroup) {
+        self.redo.push(group);
+        self.changed = true;
     }
 
-    pub fn stop_record(&mut self) {
-        if !self.in_record {
-            return;
+    pub fn done_redo(&mut self, group: EditGroup) {
+        self.push_undo(group);
+    }
+
+    // The history position is the amount of groups that are currently applied
+    pub fn position(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.undo.len() + self.redo.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Get the group that will be applied when reaching the history position
+    pub fn get_group(&self, position: usize) -> Option<&EditGroup> {
+        if position == 0 || position > self.len() {
+            return None;
+        }
+        if position <= self.undo.len() {
+            self.undo.get(position - 1)
+        } else {
+            self.redo
+                .get(self.redo.len() - (position - self.undo.len()))
         }
-        self.in_record = false;
-        self.last_index = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.current = None;
+        self.changed = true;
     }
 }
@@ -0,0 +1,340 @@
+use graphics::*;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::{attributes::*, game_data::GameDataKind};
+
+const SCHEMA_PATH: &str = "./data/attributes.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FieldKind {
+    Int,
+    UInt,
+    String,
+    Bool,
+    // Name of one of the enums declared on the schema
+    Enum(String),
+    // ID of an item, npc or shop of the game data
+    GameData(GameDataKind),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttributeField {
+    pub name: String,
+    pub kind: FieldKind,
+    // String fields with more than 1 line will use a wrapping textbox
+    #[serde(default)]
+    pub lines: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttributeType {
+    pub name: String,
+    pub letter: String,
+    pub color: [u8; 4],
+    #[serde(default)]
+    pub fields: Vec<AttributeField>,
+    // Attribute types that can not be placed on the same tile as this one
+    #[serde(default)]
+    pub conflicts: Vec<String>,
+}
+
+impl AttributeType {
+    fn new(name: &str, letter: &str, color: [u8; 4]) -> Self {
+        Self {
+            name: name.to_string(),
+            letter: letter.to_string(),
+            color,
+            fields: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    fn with_conflicts(mut self, conflicts: &[&str]) -> Self {
+        self.conflicts =
+            conflicts.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    fn with_field(mut self, name: &str, kind: FieldKind, lines: u32) -> Self {
+        self.fields.push(AttributeField {
+            name: name.to_string(),
+            kind,
+            lines,
+        });
+        self
+    }
+
+    pub fn get_color(&self) -> Color {
+        Color::rgba(self.color[0], self.color[1], self.color[2], self.color[3])
+    }
+}
+
+// The attribute types that can be placed on the map, Walkable is not part of
+// the schema as it is the default value of every tile
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttributeSchema {
+    #[serde(default)]
+    pub enums: IndexMap<String, Vec<String>>,
+    pub attributes: Vec<AttributeType>,
+}
+
+impl AttributeSchema {
+    pub fn default() -> Self {
+        let mut enums = IndexMap::new();
+        enums.insert(
+            "TriggerKind".to_string(),
+            TriggerKind::KINDS
+                .iter()
+                .map(|kind| kind.as_str().to_string())
+                .collect(),
+        );
+
+        Self {
+            enums,
+            attributes: vec![
+                AttributeType::new("Blocked", "B", [200, 10, 10, 100])
+                    .with_conflicts(&["NpcBlocked", "Warp", "Item"]),
+                AttributeType::new("NpcBlocked", "N", [200, 50, 10, 100]),
+                AttributeType::new("Warp", "W", [10, 10, 200, 100])
+                    .with_conflicts(&["Sign", "Item", "Storage", "Shop"])
+                    .with_field("Map X", FieldKind::Int, 0)
+                    .with_field("Map Y", FieldKind::Int, 0)
+                    .with_field("Group", FieldKind::UInt, 0)
+                    .with_field("Tile X", FieldKind::UInt, 0)
+                    .with_field("Tile Y", FieldKind::UInt, 0),
+                AttributeType::new("Sign", "S", [10, 200, 10, 100])
+                    .with_field("Sign Text", FieldKind::String, 10)
+                    .with_field("Localization Key", FieldKind::Bool, 0),
+                AttributeType::new("Item", "I", [180, 180, 180, 100])
+                    .with_field(
+                        "Index",
+                        FieldKind::GameData(GameDataKind::Item),
+                        0,
+                    )
+                    .with_field("Value", FieldKind::UInt, 0)
+                    .with_field("Timer", FieldKind::UInt, 0),
+                AttributeType::new("Storage", "S", [160, 170, 20, 255])
+                    .with_conflicts(&["Shop"]),
+                AttributeType::new("Shop", "S", [200, 50, 100, 255])
+                    .with_field(
+                        "Index",
+                        FieldKind::GameData(GameDataKind::Shop),
+                        0,
+                    ),
+                AttributeType::new("Trigger", "T", [220, 120, 20, 100])
+                    .with_field(
+                        "Kind",
+                        FieldKind::Enum("TriggerKind".to_string()),
+                        0,
+                    )
+                    .with_field("Script", FieldKind::String, 0)
+                    .with_field("Radius", FieldKind::UInt, 0)
+                    .with_field("Parameters", FieldKind::String, 6),
+            ],
+        }
+    }
+
+    // The built-in attributes are stored with their fields in a fixed order,
+    // a schema can change how they look but not the fields that they have
+    fn keep_builtin_fields(&mut self) {
+        let builtin = AttributeSchema::default();
+        for attribute in self.attributes.iter_mut() {
+            let data = match builtin.get(&attribute.name) {
+                Some(data) => data,
+                None => continue,
+            };
+            let is_same = attribute.fields.len() == data.fields.len()
+                && attribute.fields.iter().zip(data.fields.iter()).all(
+                    |(field, builtin_field)| {
+                        field.name == builtin_field.name
+                            && field.kind == builtin_field.kind
+                    },
+                );
+            if !is_same {
+                println!(
+                    "Error the fields of the built-in attribute {} can not \
+                     be changed, the default fields are used",
+                    attribute.name
+                );
+                attribute.fields = data.fields.clone();
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AttributeType> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    pub fn get_index(&self, name: &str) -> Option<usize> {
+        self.attributes
+            .iter()
+            .position(|attribute| attribute.name == name)
+    }
+
+    pub fn get_enum(&self, name: &str) -> Vec<String> {
+        self.enums.get(name).cloned().unwrap_or_default()
+    }
+
+    // The value used when a field has not been set yet
+    pub fn default_value(&self, kind: &FieldKind) -> InsertTypes {
+        match kind {
+            FieldKind::Int => InsertTypes::Int(0),
+            FieldKind::UInt => InsertTypes::UInt(0),
+            FieldKind::String => InsertTypes::Str(String::new()),
+            FieldKind::Bool => InsertTypes::Bool(false),
+            FieldKind::GameData(_) => InsertTypes::UInt(0),
+            FieldKind::Enum(name) => InsertTypes::Str(
+                self.get_enum(name).first().cloned().unwrap_or_default(),
+            ),
+        }
+    }
+
+    // Two attributes of the same type can not share a tile, and neither can
+    // attributes that are listed as a conflict by one of them
+    pub fn can_combine(
+        &self,
+        first: &MapAttribute,
+        second: &MapAttribute,
+    ) -> bool {
+        let (first_name, second_name) = (first.name(), second.name());
+        if first_name == second_name {
+            return false;
+        }
+        let is_conflict = |name: &str, other: &str| {
+            self.get(name)
+                .map(|data| {
+                    data.conflicts.iter().any(|conflict| conflict == other)
+                })
+                .unwrap_or_default()
+        };
+        !is_conflict(first_name, second_name)
+            && !is_conflict(second_name, first_name)
+    }
+
+    // Place the attribute on the tile list, replacing the attributes
+    // that it can not be combined with
+    pub fn add_attribute(
+        &self,
+        list: &[MapAttribute],
+        attribute: MapAttribute,
+    ) -> Vec<MapAttribute> {
+        if attribute == MapAttribute::Walkable {
+            return Vec::new();
+        }
+        let mut result: Vec<MapAttribute> = list
+            .iter()
+            .filter(|data| self.can_combine(data, &attribute))
+            .cloned()
+            .collect();
+        result.push(attribute);
+        result
+    }
+
+    // Letters of all the attributes on the tile
+    pub fn get_tile_str(&self, list: &[MapAttribute]) -> String {
+        list.iter()
+            .map(|attribute| self.get_map_str(attribute))
+            .collect::<Vec<String>>()
+            .join("")
+    }
+
+    // The colors of the attributes on the tile are blended together
+    pub fn get_tile_color(&self, list: &[MapAttribute]) -> Color {
+        if list.is_empty() {
+            return Color::rgba(0, 0, 0, 0);
+        }
+        let mut total = [0u32; 4];
+        list.iter().for_each(|attribute| {
+            let color = self.get_color_data(attribute);
+            (0..3).for_each(|index| total[index] += color[index] as u32);
+            total[3] = total[3].max(color[3] as u32);
+        });
+        let count = list.len() as u32;
+        Color::rgba(
+            (total[0] / count) as u8,
+            (total[1] / count) as u8,
+            (total[2] / count) as u8,
+            total[3] as u8,
+        )
+    }
+
+    pub fn get_map_str(&self, attribute: &MapAttribute) -> String {
+        match self.get(attribute.name()) {
+            Some(data) => data.letter.clone(),
+            None => match attribute {
+                MapAttribute::Custom(_) => "?".to_string(),
+                _ => String::new(),
+            },
+        }
+    }
+
+    fn get_color_data(&self, attribute: &MapAttribute) -> [u8; 4] {
+        match self.get(attribute.name()) {
+            Some(data) => data.color,
+            None => match attribute {
+                MapAttribute::Custom(_) => [120, 120, 120, 100],
+                _ => [0, 0, 0, 0],
+            },
+        }
+    }
+}
+
+pub fn create_attribute_schema(
+    data: &AttributeSchema,
+) -> Result<(), AscendingError> {
+    match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(SCHEMA_PATH)
+    {
+        Ok(file) => {
+            if let Err(e) = serde_json::to_writer_pretty(&file, &data) {
+                Err(AscendingError::Other(OtherError::new(&format!(
+                    "Serdes File Error Err {:?}",
+                    e
+                ))))
+            } else {
+                Ok(())
+            }
+        }
+        Err(ref e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(()),
+        Err(e) => Err(AscendingError::Other(OtherError::new(&format!(
+            "Failed to open {}, Err {:?}",
+            SCHEMA_PATH, e
+        )))),
+    }
+}
+
+pub fn load_attribute_schema() -> AttributeSchema {
+    if !Path::new(SCHEMA_PATH).exists() {
+        let data = AttributeSchema::default();
+        if let Err(e) = create_attribute_schema(&data) {
+            println!("Error {:?}", e);
+        }
+        return data;
+    }
+
+    match OpenOptions::new().read(true).open(SCHEMA_PATH) {
+        Ok(file) => {
+            let reader = BufReader::new(file);
+
+            match serde_json::from_reader::<_, AttributeSchema>(reader) {
+                Ok(mut data) => {
+                    data.keep_builtin_fields();
+                    data
+                }
+                Err(e) => {
+                    println!("Error {:?}", e);
+                    AttributeSchema::default()
+                }
+            }
+        }
+        Err(_) => AttributeSchema::default(),
+    }
+}
@@ -0,0 +1,313 @@
+use indexmap::{IndexMap, IndexSet};
+use std::collections::VecDeque;
+
+use crate::{attributes::*, map_data::*};
+
+use super::get_dir_visible;
+
+// Up, Left, Down, Right, on the same order as the dir block directions
+const DIR_OFFSET: [(i32, i32); 4] = [(0, 1), (-1, 0), (0, -1), (1, 0)];
+
+// Analysis that is shown on the map view
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum WalkMode {
+    #[default]
+    Off,
+    // Flood fill from the clicked tile
+    FromTile,
+    // Flood fill from every tile that a warp leads to
+    FromWarps,
+    // Path between two clicked tiles
+    Path,
+}
+
+impl WalkMode {
+    pub const MODES: [WalkMode; 4] = [
+        WalkMode::Off,
+        WalkMode::FromTile,
+        WalkMode::FromWarps,
+        WalkMode::Path,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WalkMode::Off => "Off",
+            WalkMode::FromTile => "Reach From Tile",
+            WalkMode::FromWarps => "Reach From Warps",
+            WalkMode::Path => "Path Preview",
+        }
+    }
+}
+
+// Npcs are also stopped by the NpcBlocked attribute
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum WalkRule {
+    #[default]
+    Player,
+    Npc,
+}
+
+impl WalkRule {
+    pub const RULES: [WalkRule; 2] = [WalkRule::Player, WalkRule::Npc];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WalkRule::Player => "Player",
+            WalkRule::Npc => "Npc",
+        }
+    }
+}
+
+// A tile of one of the maps within the group
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WalkTile {
+    pub map_x: i32,
+    pub map_y: i32,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl WalkTile {
+    pub fn new(map: (i32, i32), x: i32, y: i32) -> Self {
+        Self {
+            map_x: map.0,
+            map_y: map.1,
+            x,
+            y,
+        }
+    }
+
+    pub fn tile_num(&self) -> usize {
+        get_tile_pos(self.x, self.y)
+    }
+}
+
+struct WalkMap {
+    blocked: Vec<bool>,
+    dir_block: Vec<u8>,
+    // Destination of the warps that stay within the group
+    warps: IndexMap<usize, WalkTile>,
+}
+
+// Movement data of every map within the group
+pub struct WalkGrid {
+    maps: IndexMap<(i32, i32), WalkMap>,
+    // Tiles that the warps of every group lead to
+    entries: Vec<WalkTile>,
+}
+
+impl WalkGrid {
+    // The loaded maps are used instead of their file so that unsaved
+    // changes are part of the analysis
+    pub fn new(
+        loaded_maps: &IndexMap<String, MapData>,
+        group: u64,
+        rule: WalkRule,
+    ) -> Self {
+        let mut maps = IndexMap::new();
+        let mut entries = Vec::new();
+
+        for (x, y, map_group) in get_all_maps() {
+            let key = format!("{}_{}_{}", x, y, map_group);
+            let loaded;
+            let mapdata = match loaded_maps.get(&key) {
+                Some(data) => data,
+                None => {
+                    loaded = match load_file(x, y, map_group) {
+                        Ok(data) => data,
+                        Err(_) => continue,
+                    };
+                    &loaded
+                }
+            };
+
+            let mut warps = IndexMap::new();
+            for (tile_num, attributes) in mapdata.attribute.iter().enumerate() {
+                for attribute in attributes.iter() {
+                    if let MapAttribute::Warp(data) = attribute {
+                        if data.map_group != group
+                            || data.tile_x >= 32
+                            || data.tile_y >= 32
+                        {
+                            continue;
+                        }
+                        let dest = WalkTile::new(
+                            (data.map_x, data.map_y),
+                            data.tile_x as i32,
+                            data.tile_y as i32,
+                        );
+                        entries.push(dest);
+                        warps.insert(tile_num, dest);
+                    }
+                }
+            }
+
+            if map_group != group {
+                continue;
+            }
+
+            let blocked = mapdata
+                .attribute
+                .iter()
+                .map(|attributes| {
+                    attributes.iter().any(|attribute| match attribute {
+                        MapAttribute::Blocked => true,
+                        MapAttribute::NpcBlocked => rule == WalkRule::Npc,
+                        _ => false,
+                    })
+                })
+                .collect();
+            maps.insert(
+                (x, y),
+                WalkMap {
+                    blocked,
+                    dir_block: mapdata.dir_block.clone(),
+                    warps,
+                },
+            );
+        }
+
+        Self { maps, entries }
+    }
+
+    pub fn is_walkable(&self, tile: WalkTile) -> bool {
+        self.maps
+            .get(&(tile.map_x, tile.map_y))
+            .map(|map| !map.blocked[tile.tile_num()])
+            .unwrap_or_default()
+    }
+
+    pub fn warp_entries(&self) -> Vec<WalkTile> {
+        self.entries
+            .iter()
+            .filter(|tile| self.is_walkable(**tile))
+            .copied()
+            .collect()
+    }
+
+    // Tiles that can be reached with a single step, stepping on a warp
+    // will move to its destination right away like it does in game
+    fn next_tiles(&self, tile: WalkTile) -> Vec<WalkTile> {
+        let map = match self.maps.get(&(tile.map_x, tile.map_y)) {
+            Some(map) => map,
+            None => return Vec::new(),
+        };
+        if let Some(dest) = map.warps.get(&tile.tile_num()) {
+            return if self.is_walkable(*dest) {
+                vec![*dest]
+            } else {
+                Vec::new()
+            };
+        }
+
+        let dir_block = get_dir_visible(map.dir_block[tile.tile_num()]);
+        let mut list = Vec::with_capacity(4);
+        for (dir, (offset_x, offset_y)) in DIR_OFFSET.iter().enumerate() {
+            if dir_block[dir] {
+                continue;
+            }
+            let (mut next_x, mut next_y) =
+                (tile.x + offset_x, tile.y + offset_y);
+            let (mut map_x, mut map_y) = (tile.map_x, tile.map_y);
+            // Walking out of the map will enter the neighbouring map
+            if !(0..32).contains(&next_x) {
+                map_x += offset_x;
+                next_x = next_x.rem_euclid(32);
+            }
+            if !(0..32).contains(&next_y) {
+                map_y += offset_y;
+                next_y = next_y.rem_euclid(32);
+            }
+            let next = WalkTile::new((map_x, map_y), next_x, next_y);
+            if !self.is_walkable(next) {
+                continue;
+            }
+            // The dir block of the next tile can also stop the entry
+            let next_block = get_dir_visible(
+                self.maps[&(map_x, map_y)].dir_block[next.tile_num()],
+            );
+            if next_block[(dir + 2) % 4] {
+                continue;
+            }
+            list.push(next);
+        }
+        list
+    }
+
+    fn flood_fill(&self, seeds: &[WalkTile]) -> IndexSet<WalkTile> {
+        let mut reached = IndexSet::new();
+        let mut queue = VecDeque::new();
+        for seed in seeds.iter() {
+            if self.is_walkable(*seed) && reached.insert(*seed) {
+                queue.push_back(*seed);
+            }
+        }
+        while let Some(tile) = queue.pop_front() {
+            for next in self.next_tiles(tile) {
+                if reached.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        reached
+    }
+
+    // Walkable tiles that could not be reached, listed for each map
+    pub fn find_unreachable(
+        &self,
+        seeds: &[WalkTile],
+    ) -> IndexMap<(i32, i32), Vec<usize>> {
+        let reached = self.flood_fill(seeds);
+        let mut result = IndexMap::new();
+        for (key, map) in self.maps.iter() {
+            let list: Vec<usize> = (0..1024)
+                .filter(|tile_num| {
+                    let tile = WalkTile::new(
+                        *key,
+                        (tile_num % 32) as i32,
+                        (tile_num / 32) as i32,
+                    );
+                    !map.blocked[*tile_num] && !reached.contains(&tile)
+                })
+                .collect();
+            if !list.is_empty() {
+                result.insert(*key, list);
+            }
+        }
+        result
+    }
+
+    // Shortest path between the two tiles, both of them included
+    pub fn find_path(
+        &self,
+        start: WalkTile,
+        end: WalkTile,
+    ) -> Option<Vec<WalkTile>> {
+        if !self.is_walkable(start) || !self.is_walkable(end) {
+            return None;
+        }
+        let mut came_from: IndexMap<WalkTile, WalkTile> = IndexMap::new();
+        let mut queue = VecDeque::new();
+        came_from.insert(start, start);
+        queue.push_back(start);
+        while let Some(tile) = queue.pop_front() {
+            if tile == end {
+                let mut path = vec![end];
+                let mut current = end;
+                while current != start {
+                    current = came_from[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.next_tiles(tile) {
+                if !came_from.contains_key(&next) {
+                    came_from.insert(next, tile);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,194 @@
+use graphics::*;
+
+use crate::{collection::*, map_data::*, DrawSetting};
+
+use super::MapCamera;
+
+// Size of a single map on the world view
+const WORLD_MAP_SIZE: f32 = (32 * TEXTURE_SIZE) as f32;
+
+pub struct WorldMap {
+    pub x: i32,
+    pub y: i32,
+    pub border: usize,
+}
+
+pub struct WorldView {
+    pub is_open: bool,
+    pub group: u64,
+    // Maps are kept and reused each time the world view is opened
+    pub maps: Vec<Map>,
+    pub map_list: Vec<WorldMap>,
+    pub hover: usize,
+    pub camera: MapCamera,
+}
+
+impl WorldView {
+    pub fn new(systems: &mut DrawSetting) -> Self {
+        let mut rect = Rect::new(&mut systems.renderer, 0);
+        rect.set_size(Vec2::new(WORLD_MAP_SIZE, WORLD_MAP_SIZE))
+            .set_position(Vec3::new(0.0, 0.0, ORDER_WORLD_MAP_HOVER))
+            .set_color(Color::rgba(255, 255, 255, 40))
+            .set_use_camera(true);
+        let hover = systems.gfx.add_rect(rect, RENDER_WORLDVIEW);
+        systems.gfx.set_visible(hover, false);
+
+        Self {
+            is_open: false,
+            group: 0,
+            maps: Vec::new(),
+            map_list: Vec::new(),
+            hover,
+            camera: MapCamera::with_limits(
+                MIN_WORLD_ZOOM,
+                MAX_WORLD_ZOOM,
+                WORLD_ZOOM_STEP,
+            ),
+        }
+    }
+
+    pub fn open(&mut self, systems: &mut DrawSetting, database: &EditorData) {
+        self.clear(systems);
+        self.is_open = true;
+        self.group = database.group;
+
+        let mut list = get_group_maps(database.group);
+        if !list.contains(&(database.x, database.y)) {
+            list.push((database.x, database.y));
+        }
+
+        let mut min_pos = Vec2::new(f32::MAX, f32::MAX);
+        let mut max_pos = Vec2::new(f32::MIN, f32::MIN);
+        let map_size = Vec2::new(WORLD_MAP_SIZE, WORLD_MAP_SIZE);
+        for (x, y) in list {
+            // Maps with unsaved changes are taken from the loaded maps
+            let key = format!("{}_{}_{}", x, y, database.group);
+            let loaded;
+            let mapdata = match database.maps.get(&key) {
+                Some(data) => data,
+                None => {
+                    loaded = match load_file(x, y, database.group) {
+                        Ok(data) => data,
+                        Err(_) => continue,
+                    };
+                    &loaded
+                }
+            };
+
+            let index = self.map_list.len();
+            if self.maps.len() <= index {
+                let mut map = Map::new(&mut systems.renderer, TEXTURE_SIZE);
+                map.can_render = true;
+                self.maps.push(map);
+            }
+            let pos = WorldView::get_world_pos(x, y);
+            self.maps[index].pos = pos;
+
+            (0..32).for_each(|tile_x| {
+                (0..32).for_each(|tile_y| {
+                    let tile_num = get_tile_pos(tile_x, tile_y);
+                    (0..9).for_each(|layer| {
+                        let id = mapdata.tile[layer].id[tile_num] as usize;
+                        self.maps[index].set_tile(
+                            (tile_x as u32, tile_y as u32, layer as u32),
+                            if id > 0 {
+                                TileData {
+                                    id,
+                                    color: Color::rgba(255, 255, 255, 255),
+                                }
+                            } else {
+                                TileData::default()
+                            },
+                        );
+                    });
+                });
+            });
+
+            // Highlight the current map and the maps that have not been saved
+            let is_current = x == database.x && y == database.y;
+            let did_change = database.did_change(x, y, database.group);
+            let mut rect = Rect::new(&mut systems.renderer, 0);
+            rect.set_size(map_size)
+                .set_position(Vec3::new(pos.x, pos.y, ORDER_WORLD_MAP_BORDER))
+                .set_color(if did_change {
+                    Color::rgba(230, 140, 30, 70)
+                } else {
+                    Color::rgba(0, 0, 0, 0)
+                })
+                .set_border_width(if is_current { 16.0 } else { 6.0 })
+                .set_border_color(if is_current {
+                    Color::rgba(240, 240, 240, 255)
+                } else if did_change {
+                    Color::rgba(230, 140, 30, 255)
+                } else {
+                    Color::rgba(20, 20, 20, 255)
+                })
+                .set_use_camera(true);
+            let border = systems.gfx.add_rect(rect, RENDER_WORLDVIEW);
+
+            self.map_list.push(WorldMap { x, y, border });
+
+            min_pos = min_pos.min(pos);
+            max_pos = max_pos.max(pos + map_size);
+        }
+
+        if !self.map_list.is_empty() {
+            self.camera.fit_area(min_pos, max_pos - min_pos);
+        }
+    }
+
+    pub fn close(&mut self, systems: &mut DrawSetting) {
+        self.clear(systems);
+        self.is_open = false;
+    }
+
+    fn clear(&mut self, systems: &mut DrawSetting) {
+        self.map_list.iter().for_each(|map| {
+            systems.gfx.remove_gfx(map.border);
+        });
+        self.map_list.clear();
+        systems.gfx.set_visible(self.hover, false);
+    }
+
+    pub fn get_world_pos(x: i32, y: i32) -> Vec2 {
+        Vec2::new(x as f32 * WORLD_MAP_SIZE, y as f32 * WORLD_MAP_SIZE)
+    }
+
+    pub fn find_map(&self, screen_pos: Vec2) -> Option<(i32, i32)> {
+        if !self.camera.in_view(screen_pos) {
+            return None;
+        }
+        let world_pos = self.camera.screen_to_world(screen_pos);
+        let (x, y) = (
+            (world_pos.x / WORLD_MAP_SIZE).floor() as i32,
+            (world_pos.y / WORLD_MAP_SIZE).floor() as i32,
+        );
+        self.map_list
+            .iter()
+            .find(|map| map.x == x && map.y == y)
+            .map(|map| (map.x, map.y))
+    }
+
+    pub fn hover_map(
+        &mut self,
+        systems: &mut DrawSetting,
+        screen_pos: Vec2,
+    ) -> Option<(i32, i32)> {
+        let found = self.find_map(screen_pos);
+        if let Some((x, y)) = found {
+            let pos = WorldView::get_world_pos(x, y);
+            systems.gfx.set_pos(
+                self.hover,
+                Vec3::new(pos.x, pos.y, ORDER_WORLD_MAP_HOVER),
+            );
+        }
+        systems.gfx.set_visible(self.hover, found.is_some());
+        found
+    }
+
+    // Only the maps that are part of the current world view are rendered
+    pub fn active_maps(&mut self) -> &mut [Map] {
+        let count = self.map_list.len().min(self.maps.len());
+        &mut self.maps[..count]
+    }
+}
@@ -1,13 +1,24 @@
 use graphics::*;
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::io::BufReader;
 use std::path::Path;
-use std::{fs::OpenOptions, io::Write};
+use std::{
+    fs::OpenOptions,
+    io::{Read, Write},
+};
 
 use bytey::{ByteBuffer, ByteBufferError, ByteBufferRead, ByteBufferWrite};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 
-use crate::{attributes::*, map::*, DrawSetting, Interface};
+use crate::{
+    attributes::*, config::load_config, map::*, map_merge::set_merge_conflicts,
+    ConfigData, DrawSetting, Interface,
+};
+
+// Header of the compressed map binary, the raw layout has no header
+const MAP_BIN_MAGIC: &[u8; 4] = b"AMAP";
+const MAP_BIN_VERSION: u16 = 1;
 
 #[derive(Debug)]
 pub enum Direction {
@@ -31,10 +42,15 @@ pub struct EditorData {
     pub current_index: String,
     pub maps: IndexMap<String, MapData>,
     pub did_map_change: IndexMap<String, bool>,
+    // Edit history of the loaded maps that are not on the map view
+    pub histories: IndexMap<String, EditHistory>,
+    // Layout used when writing the map files
+    pub file_layout: MapFileLayout,
+    pub bin_format: MapBinFormat,
 }
 
 impl EditorData {
-    pub fn new() -> Result<EditorData, AscendingError> {
+    pub fn new(config_data: &ConfigData) -> Result<EditorData, AscendingError> {
         let mut maps = IndexMap::new();
         let mut did_map_change = IndexMap::new();
 
@@ -50,10 +66,21 @@ impl EditorData {
             current_index,
             maps,
             did_map_change,
+            histories: IndexMap::new(),
+            file_layout: config_data.map_file_layout,
+            bin_format: config_data.map_bin_format,
         })
     }
 
-    pub fn init_map(&mut self, x: i32, y: i32, group: u64) {
+    pub fn init_map(
+        &mut self,
+        x: i32,
+        y: i32,
+        group: u64,
+        mapview: &mut MapView,
+    ) {
+        self.store_history(mapview);
+
         // Set position
         self.x = x;
         self.y = y;
@@ -74,7 +101,13 @@ impl EditorData {
         }
     }
 
-    pub fn move_map(&mut self, direction: Direction) -> Option<String> {
+    pub fn move_map(
+        &mut self,
+        direction: Direction,
+        mapview: &mut MapView,
+    ) -> Option<String> {
+        self.store_history(mapview);
+
         match direction {
             Direction::East => {
                 self.x += 1;
@@ -108,12 +141,12 @@ impl EditorData {
         let mut temp_key = None;
 
         // Check if the current map has changes
-        if let Some(change) = self.did_map_change.get(&self.current_index) {
+        if let Some(&change) = self.did_map_change.get(&self.current_index) {
             // We will remove the map on the collection when no change has been done
-            if !change {
-                self.did_map_change.shift_remove(&self.current_index);
-                self.maps.shift_remove(&self.current_index);
-            } else {
+            // The maps with an edit history are kept so that it can be undone
+            if !change && !self.histories.contains_key(&self.current_index) {
+                self.unload_map(&self.current_index.clone());
+            } else if change {
                 temp_key = Some(self.current_index.clone());
             }
         }
@@ -160,28 +193,23 @@ impl EditorData {
                             as u32;
                     });
                     mapdata.attribute[tile_num] =
-                        mapview.map_attributes[tile_num].attribute.clone();
+                        mapview.map_attributes[tile_num].attributes.clone();
                     mapdata.dir_block[tile_num] =
                         mapview.map_dir_block[tile_num].dir_data;
                 });
             });
-            for i in 0..5 {
-                mapdata.zonespawns[i] = Vec::new();
-                mapview.map_zone_loc[i].pos.iter().for_each(|zone_pos| {
-                    mapdata.zonespawns[i]
-                        .push((zone_pos.x as u16, zone_pos.y as u16));
-                });
-                mapdata.zones[i].0 = mapview.map_zone_setting[i].max_npc;
-                for npc_index in 0..5 {
-                    mapdata.zones[i].1[npc_index] =
-                        mapview.map_zone_setting[i].npc_id[npc_index];
-                }
-            }
+            mapdata.zones = mapview.get_zone_data();
+            mapdata.entities = mapview
+                .map_entities
+                .iter()
+                .map(|entity| entity.data.clone())
+                .collect();
             mapdata.weather = Weather::None; //ToDo mapview.fixed_weather;
             mapdata.music = mapview.music.clone();
             if should_save {
-                mapdata.save_file().unwrap();
-                mapdata.save_file_bin().unwrap();
+                mapdata.save_file(self.file_layout).unwrap();
+                mapdata.save_file_bin(self.bin_format).unwrap();
+                mapdata.resolve_merge_conflicts();
                 // Since we have saved the map, let's mark the map as 'no change'
                 if let Some(did_change) =
                     self.did_map_change.get_mut(&self.current_index)
@@ -214,18 +242,31 @@ impl EditorData {
                     }
                     self.save_map_data(mapview, None);
                 } else {
-                    mapdata.save_file().unwrap();
-                    mapdata.save_file_bin().unwrap();
+                    mapdata.save_file(self.file_layout).unwrap();
+                    mapdata.save_file_bin(self.bin_format).unwrap();
+                    mapdata.resolve_merge_conflicts();
+                    // The saved map stays loaded while it has an edit history
+                    if self.histories.contains_key(&key) {
+                        should_remove = false;
+                        self.did_map_change.insert(key.clone(), false);
+                    }
                 }
             }
             if should_remove {
-                self.maps.shift_remove(&key);
-                self.did_map_change.shift_remove(&key);
+                self.unload_map(&key);
             }
         }
     }
 
-    pub fn reset_all_map(&mut self) {
+    // The edit history is only dropped along with the map data
+    fn unload_map(&mut self, key: &str) {
+        self.maps.shift_remove(key);
+        self.did_map_change.shift_remove(key);
+        self.histories.shift_remove(key);
+    }
+
+    pub fn reset_all_map(&mut self, mapview: &mut MapView) {
+        let mut current_reset = false;
         let keys_to_reset: Vec<_> = self
             .did_map_change
             .keys()
@@ -247,12 +288,19 @@ impl EditorData {
                         *mapdata =
                             load_file(self.x, self.y, self.group).unwrap();
                     }
+                    // The history no longer matches the reloaded map
+                    self.histories.shift_remove(&key);
+                    current_reset = true;
                 } else {
-                    self.maps.shift_remove(&key);
-                    self.did_map_change.shift_remove(&key);
+                    self.unload_map(&key);
                 }
             }
         }
+        // The map view is loaded again after the reset, the current map
+        // keeps its history when it was not reset
+        if !current_reset {
+            self.store_history(mapview);
+        }
     }
 
     pub fn load_map_data(
@@ -262,6 +310,13 @@ impl EditorData {
     ) {
         // Clear the map before we start adding the tiles
         map.clear_map(0);
+        map.map_changed = false;
+        // Bring back the edit history of the map, if it has been edited before
+        map.history = self
+            .histories
+            .shift_remove(&self.current_index)
+            .unwrap_or_else(EditHistory::new);
+        map.history.changed = true;
         // Add the tiles
         if let Some(mapdata) = self.maps.get(&self.current_index) {
             (0..32).for_each(|x| {
@@ -279,7 +334,7 @@ impl EditorData {
                             );
                         }
                     });
-                    map.map_attributes[tile_num].set_attribute(
+                    map.map_attributes[tile_num].set_attributes(
                         systems,
                         mapdata.attribute[tile_num].clone(),
                     );
@@ -287,19 +342,27 @@ impl EditorData {
                         mapdata.dir_block[tile_num];
                 });
             });
-            for i in 0..5 {
-                map.map_zone_loc[i].pos = Vec::new();
-                mapdata.zonespawns[i].iter().for_each(|zone| {
-                    map.map_zone_loc[i]
-                        .pos
-                        .push(Vec2::new(zone.0 as f32, zone.1 as f32));
-                });
-                map.map_zone_setting[i].max_npc = mapdata.zones[i].0;
-                for npc_index in 0..5 {
-                    map.map_zone_setting[i].npc_id[npc_index] =
-                        mapdata.zones[i].1[npc_index];
-                }
-            }
+            map.map_zone_loc = mapdata
+                .zones
+                .iter()
+                .map(|zone| MapZone {
+                    pos: zone
+                        .spawns
+                        .iter()
+                        .map(|spawn| Vec2::new(spawn.0 as f32, spawn.1 as f32))
+                        .collect(),
+                })
+                .collect();
+            map.load_entities(systems, &mapdata.entities);
+            map.map_zone_setting = mapdata
+                .zones
+                .iter()
+                .map(|zone| MapZoneSetting {
+                    name: zone.name.clone(),
+                    max_npc: zone.max_npc,
+                    npcs: zone.npcs.clone(),
+                })
+                .collect();
             map.fixed_weather = 0; //ToDo mapdata.weather;
             map.music = mapdata.music.clone();
         }
@@ -309,71 +372,16 @@ impl EditorData {
         (0..8).for_each(|maplink| {
             // Clear the map before we start adding the tiles
             map.clear_map(maplink + 1);
+            map.link_map_changed[maplink] = false;
 
             // Set the map id, position for loading
-            let (start, size, x, y);
-            match maplink {
-                1 => {
-                    // Top
-                    x = self.x;
-                    y = self.y + 1;
-                    size = Vec2::new(32.0, 2.0);
-                    start = Vec2::new(0.0, 0.0);
-                }
-                2 => {
-                    // Top Right
-                    x = self.x + 1;
-                    y = self.y + 1;
-                    size = Vec2::new(2.0, 2.0);
-                    start = Vec2::new(0.0, 0.0);
-                }
-                3 => {
-                    // Left
-                    x = self.x - 1;
-                    y = self.y;
-                    size = Vec2::new(2.0, 32.0);
-                    start = Vec2::new(30.0, 0.0);
-                }
-                4 => {
-                    // Right
-                    x = self.x + 1;
-                    y = self.y;
-                    size = Vec2::new(2.0, 32.0);
-                    start = Vec2::new(0.0, 0.0);
-                }
-                5 => {
-                    // Bottom Left
-                    x = self.x - 1;
-                    y = self.y - 1;
-                    size = Vec2::new(2.0, 2.0);
-                    start = Vec2::new(30.0, 30.0);
-                }
-                6 => {
-                    // Bottom
-                    x = self.x;
-                    y = self.y - 1;
-                    size = Vec2::new(32.0, 2.0);
-                    start = Vec2::new(0.0, 30.0);
-                }
-                7 => {
-                    // Bottom Right
-                    x = self.x + 1;
-                    y = self.y - 1;
-                    size = Vec2::new(2.0, 2.0);
-                    start = Vec2::new(0.0, 30.0);
-                }
-                _ => {
-                    // Top Left
-                    x = self.x - 1;
-                    y = self.y + 1;
-                    size = Vec2::new(2.0, 2.0);
-                    start = Vec2::new(30.0, 0.0);
-                }
-            }
+            let (offset_x, offset_y, start, size) = get_link_map_info(maplink);
+            let (x, y) = (self.x + offset_x, self.y + offset_y);
             let key = format!("{}_{}_{}", x, y, self.group);
 
             // Let's check if map exist, and only load if map exist
-            if is_map_exist(x, y, self.group) {
+            map.link_map_exist[maplink] = is_map_exist(x, y, self.group);
+            if map.link_map_exist[maplink] {
                 // Check if map is already on our indexmap, otherwise we load it
                 let mapdata = if !self.maps.contains_key(&key) {
                     // Since the map is not loaded, we must load the file and add it on the loaded maps
@@ -407,12 +415,92 @@ impl EditorData {
         });
     }
 
-    pub fn set_map_change(&mut self, mapview: &mut MapView) {
+    // Copy the edited linked map strips back to the neighbouring map data
+    pub fn save_link_maps(&mut self, map: &mut MapView) {
+        (0..8).for_each(|maplink| {
+            if !map.link_map_changed[maplink] {
+                return;
+            }
+            map.link_map_changed[maplink] = false;
+
+            let (offset_x, offset_y, start, size) = get_link_map_info(maplink);
+            let (x, y) = (self.x + offset_x, self.y + offset_y);
+            let key = format!("{}_{}_{}", x, y, self.group);
+
+            if !self.maps.contains_key(&key) {
+                let mapdata = match load_file(x, y, self.group) {
+                    Ok(data) => data,
+                    Err(_) => return,
+                };
+                self.maps.insert(key.clone(), mapdata);
+            }
+
+            if let Some(mapdata) = self.maps.get_mut(&key) {
+                (0..size.x as i32).for_each(|x| {
+                    (0..size.y as i32).for_each(|y| {
+                        let tile_num = get_tile_pos(
+                            start.x as i32 + x,
+                            start.y as i32 + y,
+                        );
+                        (0..9).for_each(|layer| {
+                            mapdata.tile[layer].id[tile_num] = map.maps
+                                [maplink + 1]
+                                .get_tile((x as u32, y as u32, layer as u32))
+                                .id
+                                as u32;
+                        });
+                    });
+                });
+            }
+            self.did_map_change.insert(key, true);
+        });
+    }
+
+    // Change the attribute of a map that is not on the map view
+    pub fn set_map_attribute(
+        &mut self,
+        x: i32,
+        y: i32,
+        group: u64,
+        pos: Vec2,
+        attribute: MapAttribute,
+        schema: &AttributeSchema,
+    ) -> bool {
+        let key = format!("{}_{}_{}", x, y, group);
+        if !self.maps.contains_key(&key) {
+            if !is_map_exist(x, y, group) {
+                return false;
+            }
+            let mapdata = match load_file(x, y, group) {
+                Ok(data) => data,
+                Err(_) => return false,
+            };
+            self.maps.insert(key.clone(), mapdata);
+        }
+
+        if let Some(mapdata) = self.maps.get_mut(&key) {
+            let tile_num = get_tile_pos(pos.x as i32, pos.y as i32);
+            mapdata.attribute[tile_num] =
+                schema.add_attribute(&mapdata.attribute[tile_num], attribute);
+        }
+        self.did_map_change.insert(key, true);
+        true
+    }
+
+    // Keep the edit history of the current map until the map is shown again
+    fn store_history(&mut self, mapview: &mut MapView) {
+        let history =
+            std::mem::replace(&mut mapview.history, EditHistory::new());
+        if !history.is_empty() {
+            self.histories.insert(self.current_index.clone(), history);
+        }
+    }
+
+    pub fn set_map_change(&mut self) {
         if let Some(did_change) =
             self.did_map_change.get_mut(&self.current_index)
         {
             *did_change = true;
-            mapview.record.clear_redo();
         }
     }
 
@@ -471,20 +559,429 @@ pub struct Tile {
     pub id: Vec<u32>,
 }
 
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub struct ZoneNpc {
+    pub npc_id: u64,
+    // Chance of the npc to be picked, compared to the other npcs of the zone
+    pub weight: u32,
+    pub level_min: u32,
+    pub level_max: u32,
+    // Seconds before a defeated npc spawns again
+    pub respawn: u64,
+}
+
+impl ZoneNpc {
+    pub fn new(npc_id: u64) -> Self {
+        Self {
+            npc_id,
+            weight: 1,
+            level_min: 1,
+            level_max: 1,
+            respawn: 0,
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    ByteBufferRead,
+    ByteBufferWrite,
+)]
+pub struct MapZoneData {
+    pub name: String,
+    pub max_npc: u64,
+    pub npcs: Vec<ZoneNpc>,
+    pub spawns: Vec<(u16, u16)>,
+}
+
 #[derive(
     Clone, Debug, Serialize, Deserialize, ByteBufferRead, ByteBufferWrite,
 )]
+#[serde(from = "MapDataFile")]
 pub struct MapData {
     pub position: MapPosition,
     pub tile: Vec<Tile>,
     pub dir_block: Vec<u8>,
-    pub attribute: Vec<MapAttribute>,
-    pub zonespawns: [Vec<(u16, u16)>; 5],
-    pub zones: [(u64, [Option<u64>; 5]); 5],
+    pub attribute: Vec<Vec<MapAttribute>>,
+    pub zones: Vec<MapZoneData>,
+    pub entities: Vec<MapEntity>,
     pub music: Option<String>,
     pub weather: Weather,
 }
 
+// How the map json file is written, the compact layouts keep each row
+// of the map on a single line so that a change only touches its rows
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum MapFileLayout {
+    #[default]
+    Verbose,
+    Rows,
+    RunLength,
+}
+
+// How the map binary is written, the compressed format stores the layers
+// as runs of the same value and then deflates them
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum MapBinFormat {
+    #[default]
+    Raw = 0,
+    Compressed = 1,
+}
+
+impl MapBinFormat {
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(MapBinFormat::Raw),
+            1 => Some(MapBinFormat::Compressed),
+            _ => None,
+        }
+    }
+}
+
+// Body of the compressed map binary, before it is deflated
+#[derive(ByteBufferRead, ByteBufferWrite)]
+struct MapBinBody {
+    position: MapPosition,
+    tile: Vec<Vec<(u32, u32)>>,
+    dir_block: Vec<(u32, u8)>,
+    attribute: Vec<(u32, Vec<MapAttribute>)>,
+    zones: Vec<MapZoneData>,
+    entities: Vec<MapEntity>,
+    music: Option<String>,
+    weather: Weather,
+}
+
+impl From<&MapData> for MapBinBody {
+    fn from(data: &MapData) -> Self {
+        Self {
+            position: data.position.clone(),
+            tile: data.tile.iter().map(|tile| run_lengths(&tile.id)).collect(),
+            dir_block: run_lengths(&data.dir_block),
+            attribute: run_lengths(&data.attribute),
+            zones: data.zones.clone(),
+            entities: data.entities.clone(),
+            music: data.music.clone(),
+            weather: data.weather,
+        }
+    }
+}
+
+impl TryFrom<MapBinBody> for MapData {
+    type Error = AscendingError;
+
+    fn try_from(data: MapBinBody) -> Result<Self, AscendingError> {
+        Ok(Self {
+            position: data.position,
+            tile: data
+                .tile
+                .into_iter()
+                .map(|runs| {
+                    Ok(Tile {
+                        id: expand_runs(runs)?,
+                    })
+                })
+                .collect::<Result<Vec<Tile>, AscendingError>>()?,
+            dir_block: expand_runs(data.dir_block)?,
+            attribute: expand_runs(data.attribute)?,
+            zones: data.zones,
+            entities: data.entities,
+            music: data.music,
+            weather: data.weather,
+        })
+    }
+}
+
+fn run_lengths<T: PartialEq + Clone>(values: &[T]) -> Vec<(u32, T)> {
+    let mut runs: Vec<(u32, T)> = Vec::new();
+    for value in values {
+        match runs.last_mut() {
+            Some((count, last)) if last == value => *count += 1,
+            _ => runs.push((1, value.clone())),
+        }
+    }
+    runs
+}
+
+// Number of tiles that the runs hold, None when it overflows
+fn run_total<T>(runs: &[(u32, T)]) -> Option<u32> {
+    runs.iter()
+        .try_fold(0u32, |total, (count, _)| total.checked_add(*count))
+}
+
+// The counts are checked before anything is allocated, a broken file
+// could otherwise ask for any amount of memory
+fn expand_runs<T: Clone>(
+    runs: Vec<(u32, T)>,
+) -> Result<Vec<T>, AscendingError> {
+    if run_total(&runs) != Some(1024) {
+        return Err(AscendingError::Other(OtherError::new(
+            "The runs of the map binary do not hold 1024 tiles",
+        )));
+    }
+    let mut data = Vec::with_capacity(1024);
+    for (count, value) in runs {
+        data.extend(std::iter::repeat(value).take(count as usize));
+    }
+    Ok(data)
+}
+
+fn bin_error(msg: &str, e: impl std::fmt::Debug) -> AscendingError {
+    AscendingError::Other(OtherError::new(&format!("{} Err {:?}", msg, e)))
+}
+
+fn read_bin<T: ByteBufferRead>(data: &[u8]) -> Result<T, AscendingError> {
+    let mut buf = ByteBuffer::with_capacity(data.len())
+        .map_err(|e| bin_error("ByteBuffer Error", e))?;
+    buf.write_slice(data)
+        .map_err(|e| bin_error("ByteBuffer Error", e))?;
+    buf.move_cursor(0)
+        .map_err(|e| bin_error("ByteBuffer Error", e))?;
+    buf.read::<T>()
+        .map_err(|e| bin_error("ByteBuffer Error", e))
+}
+
+// Layout of the map file, older maps have 5 zones on fixed arrays
+// with their spawn tiles kept apart from the zone settings
+#[derive(Deserialize)]
+struct MapDataFile {
+    position: MapPosition,
+    #[serde(deserialize_with = "deserialize_tiles")]
+    tile: Vec<Tile>,
+    #[serde(deserialize_with = "deserialize_dir_blocks")]
+    dir_block: Vec<u8>,
+    #[serde(deserialize_with = "deserialize_attributes")]
+    attribute: Vec<Vec<MapAttribute>>,
+    #[serde(default)]
+    zonespawns: Vec<Vec<(u16, u16)>>,
+    #[serde(default)]
+    zones: MapZoneFormat,
+    #[serde(default)]
+    entities: Vec<MapEntity>,
+    music: Option<String>,
+    weather: Weather,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MapZoneFormat {
+    List(Vec<MapZoneData>),
+    Fixed(Vec<(u64, Vec<Option<u64>>)>),
+}
+
+impl Default for MapZoneFormat {
+    fn default() -> Self {
+        MapZoneFormat::List(Vec::new())
+    }
+}
+
+impl From<MapDataFile> for MapData {
+    fn from(data: MapDataFile) -> Self {
+        let zonespawns = data.zonespawns;
+        let zones = match data.zones {
+            MapZoneFormat::List(zones) => zones,
+            // Only the zones that were in use are kept
+            MapZoneFormat::Fixed(zones) => zones
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, (max_npc, npc_ids))| {
+                    let spawns =
+                        zonespawns.get(index).cloned().unwrap_or_default();
+                    let npcs: Vec<ZoneNpc> = npc_ids
+                        .into_iter()
+                        .flatten()
+                        .map(ZoneNpc::new)
+                        .collect();
+                    if spawns.is_empty() && npcs.is_empty() && max_npc == 0 {
+                        return None;
+                    }
+                    Some(MapZoneData {
+                        name: format!("Zone {}", index + 1),
+                        max_npc,
+                        npcs,
+                        spawns,
+                    })
+                })
+                .collect(),
+        };
+
+        Self {
+            position: data.position,
+            tile: data.tile,
+            dir_block: data.dir_block,
+            attribute: data.attribute,
+            zones,
+            entities: data.entities,
+            music: data.music,
+            weather: data.weather,
+        }
+    }
+}
+
+// Row of 32 tiles on the compact layouts, either the value of each tile
+// or runs of (count, value)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GridRow<T> {
+    Values(Vec<T>),
+    Runs(Vec<(u32, T)>),
+}
+
+// A row that is short or long would move the tiles of the rows that
+// follow it, so the map is only read when every row holds 32 tiles
+fn flatten_rows<T: Clone>(rows: Vec<GridRow<T>>) -> Result<Vec<T>, String> {
+    if rows.len() != 32 {
+        return Err(format!("The map has {} rows instead of 32", rows.len()));
+    }
+    let mut data = Vec::with_capacity(1024);
+    for (index, row) in rows.into_iter().enumerate() {
+        let runs = match row {
+            GridRow::Values(values) => {
+                values.into_iter().map(|value| (1, value)).collect()
+            }
+            GridRow::Runs(runs) => runs,
+        };
+        if run_total(&runs) != Some(32) {
+            return Err(format!("Row {} does not hold 32 tiles", index));
+        }
+        for (count, value) in runs {
+            data.extend(std::iter::repeat(value).take(count as usize));
+        }
+    }
+    Ok(data)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TileLayer {
+    Tiles(Tile),
+    Rows(Vec<GridRow<u32>>),
+}
+
+fn deserialize_tiles<'de, D>(deserializer: D) -> Result<Vec<Tile>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let data = Vec::<TileLayer>::deserialize(deserializer)?;
+    data.into_iter()
+        .map(|layer| match layer {
+            TileLayer::Tiles(tile) => Ok(tile),
+            TileLayer::Rows(rows) => flatten_rows(rows)
+                .map(|id| Tile { id })
+                .map_err(serde::de::Error::custom),
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DirBlockFormat {
+    Tiles(Vec<u8>),
+    Rows(Vec<GridRow<u8>>),
+}
+
+fn deserialize_dir_blocks<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match DirBlockFormat::deserialize(deserializer)? {
+        DirBlockFormat::Tiles(data) => data,
+        DirBlockFormat::Rows(rows) => flatten_rows(rows)
+            .map_err(<D::Error as serde::de::Error>::custom)?,
+    })
+}
+
+// Older maps hold a single attribute on each tile
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TileAttributes {
+    List(Vec<MapAttribute>),
+    Single(MapAttribute),
+}
+
+// The verbose layout is tried first, an attribute can't be read from
+// the rows of the compact layouts
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AttributeFormat {
+    Tiles(Vec<TileAttributes>),
+    Rows(Vec<GridRow<Vec<MapAttribute>>>),
+}
+
+fn deserialize_attributes<'de, D>(
+    deserializer: D,
+) -> Result<Vec<Vec<MapAttribute>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let data = match AttributeFormat::deserialize(deserializer)? {
+        AttributeFormat::Tiles(data) => data
+            .into_iter()
+            .map(|tile| match tile {
+                TileAttributes::List(list) => list,
+                TileAttributes::Single(attribute) => vec![attribute],
+            })
+            .collect(),
+        AttributeFormat::Rows(rows) => flatten_rows(rows)
+            .map_err(<D::Error as serde::de::Error>::custom)?,
+    };
+    Ok(data
+        .into_iter()
+        .map(|list| {
+            list.into_iter()
+                .filter(|attribute| *attribute != MapAttribute::Walkable)
+                .collect()
+        })
+        .collect())
+}
+
+// Each row of 32 tiles is written as one json line, starting from row 0
+fn grid_rows<T: Serialize + PartialEq + Clone>(
+    values: &[T],
+    layout: MapFileLayout,
+) -> Result<Vec<String>, serde_json::Error> {
+    values
+        .chunks(32)
+        .map(|row| match layout {
+            MapFileLayout::RunLength => {
+                serde_json::to_string(&run_lengths(row))
+            }
+            _ => serde_json::to_string(row),
+        })
+        .collect()
+}
+
+fn json_list(lines: &[String], indent: usize) -> String {
+    if lines.is_empty() {
+        return "[]".to_string();
+    }
+    let pad = " ".repeat(indent + 2);
+    format!(
+        "[\n{}{}\n{}]",
+        pad,
+        lines.join(&format!(",\n{}", pad)),
+        " ".repeat(indent)
+    )
+}
+
 impl MapData {
     pub fn default(x: i32, y: i32, group: u64) -> Self {
         Self {
@@ -495,23 +992,108 @@ impl MapData {
             },
             tile: vec![Tile { id: vec![0; 1024] }; 9],
             dir_block: vec![0; 1024],
-            attribute: vec![MapAttribute::Walkable; 1024],
-            zonespawns: Default::default(),
-            zones: Default::default(),
+            attribute: vec![Vec::new(); 1024],
+            zones: Vec::new(),
+            entities: Vec::new(),
             music: None,
             weather: Weather::default(),
         }
     }
 
-    pub fn save_file(&self) -> Result<(), AscendingError> {
+    // Saving the map from the editor marks its merge conflicts as reviewed
+    pub fn resolve_merge_conflicts(&self) {
+        let key =
+            (self.position.x, self.position.y, self.position.group as u64);
+        if let Err(e) = set_merge_conflicts(key, Vec::new()) {
+            println!("Error {:?}", e);
+        }
+    }
+
+    pub fn to_json(
+        &self,
+        layout: MapFileLayout,
+    ) -> Result<String, serde_json::Error> {
+        match layout {
+            MapFileLayout::Verbose => serde_json::to_string_pretty(self),
+            _ => self.to_compact_json(layout),
+        }
+    }
+
+    // Fields are written on the same order as the verbose layout
+    fn to_compact_json(
+        &self,
+        layout: MapFileLayout,
+    ) -> Result<String, serde_json::Error> {
+        let layers = self
+            .tile
+            .iter()
+            .map(|tile| Ok(json_list(&grid_rows(&tile.id, layout)?, 4)))
+            .collect::<Result<Vec<String>, serde_json::Error>>()?;
+        let zones = self
+            .zones
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<String>, serde_json::Error>>()?;
+        let entities = self
+            .entities
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<String>, serde_json::Error>>()?;
+
+        let fields = [
+            ("layout", serde_json::to_string(&layout)?),
+            ("position", serde_json::to_string(&self.position)?),
+            ("tile", json_list(&layers, 2)),
+            (
+                "dir_block",
+                json_list(&grid_rows(&self.dir_block, layout)?, 2),
+            ),
+            (
+                "attribute",
+                json_list(&grid_rows(&self.attribute, layout)?, 2),
+            ),
+            ("zones", json_list(&zones, 2)),
+            ("entities", json_list(&entities, 2)),
+            ("music", serde_json::to_string(&self.music)?),
+            ("weather", serde_json::to_string(&self.weather)?),
+        ];
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(key, value)| format!("  \"{}\": {}", key, value))
+            .collect();
+        Ok(format!("{{\n{}\n}}\n", fields.join(",\n")))
+    }
+
+    pub fn save_file(
+        &self,
+        layout: MapFileLayout,
+    ) -> Result<(), AscendingError> {
         let name = format!(
             "./data/maps/{}_{}_{}.json",
             self.position.x, self.position.y, self.position.group
         );
 
+        // Keep the previous save so that the map can be compared with it
+        if Path::new(&name).exists() {
+            let backup = get_backup_path(
+                self.position.x,
+                self.position.y,
+                self.position.group as u64,
+            );
+            if let Err(e) = std::fs::create_dir_all("./data/backups/")
+                .and_then(|_| std::fs::copy(&name, &backup))
+            {
+                println!("Error {:?}", e);
+            }
+        }
+
         match OpenOptions::new().truncate(true).write(true).open(&name) {
-            Ok(file) => {
-                if let Err(e) = serde_json::to_writer_pretty(&file, self) {
+            Ok(mut file) => {
+                let result = self.to_json(layout).and_then(|data| {
+                    file.write_all(data.as_bytes())
+                        .map_err(serde_json::Error::io)
+                });
+                if let Err(e) = result {
                     Err(AscendingError::Other(OtherError::new(&format!(
                         "Serdes File Error Err {:?}",
                         e
@@ -530,18 +1112,83 @@ impl MapData {
         }
     }
 
-    pub fn save_file_bin(&self) -> Result<(), AscendingError> {
+    pub fn to_bin(
+        &self,
+        format: MapBinFormat,
+    ) -> Result<Vec<u8>, AscendingError> {
+        let mut buf =
+            ByteBuffer::new().map_err(|e| bin_error("ByteBuffer Error", e))?;
+
+        match format {
+            MapBinFormat::Raw => {
+                buf.write(self)
+                    .map_err(|e| bin_error("ByteBuffer Error", e))?;
+                Ok(buf.as_slice().to_vec())
+            }
+            MapBinFormat::Compressed => {
+                buf.write(&MapBinBody::from(self))
+                    .map_err(|e| bin_error("ByteBuffer Error", e))?;
+
+                let mut data = MAP_BIN_MAGIC.to_vec();
+                data.extend_from_slice(&MAP_BIN_VERSION.to_le_bytes());
+                data.push(format as u8);
+                let mut encoder = ZlibEncoder::new(data, Compression::best());
+                encoder
+                    .write_all(buf.as_slice())
+                    .map_err(|e| bin_error("Compression Error", e))?;
+                encoder
+                    .finish()
+                    .map_err(|e| bin_error("Compression Error", e))
+            }
+        }
+    }
+
+    // The decoder is picked from the header, files without the header
+    // hold the raw layout. A raw map can't start with the magic as it
+    // would need a map x of over a billion
+    pub fn from_bin(data: &[u8]) -> Result<MapData, AscendingError> {
+        let (format, body) = match data.strip_prefix(MAP_BIN_MAGIC) {
+            Some(rest) if rest.len() >= 3 => {
+                let version = u16::from_le_bytes([rest[0], rest[1]]);
+                if version > MAP_BIN_VERSION {
+                    return Err(AscendingError::Other(OtherError::new(
+                        &format!("Unsupported map binary version {}", version),
+                    )));
+                }
+                match MapBinFormat::from_id(rest[2]) {
+                    Some(format) => (format, &rest[3..]),
+                    None => {
+                        return Err(AscendingError::Other(OtherError::new(
+                            &format!("Unknown map binary format {}", rest[2]),
+                        )))
+                    }
+                }
+            }
+            _ => (MapBinFormat::Raw, data),
+        };
+
+        match format {
+            MapBinFormat::Raw => read_bin::<MapData>(body),
+            MapBinFormat::Compressed => {
+                let mut raw = Vec::new();
+                ZlibDecoder::new(body)
+                    .read_to_end(&mut raw)
+                    .map_err(|e| bin_error("Decompression Error", e))?;
+                MapData::try_from(read_bin::<MapBinBody>(&raw)?)
+            }
+        }
+    }
+
+    pub fn save_file_bin(
+        &self,
+        format: MapBinFormat,
+    ) -> Result<(), AscendingError> {
         let name = format!(
             "./data/maps/{}_{}_{}.bin",
             self.position.x, self.position.y, self.position.group
         );
 
-        let mut buf = match ByteBuffer::new() {
-            Ok(data) => data,
-            Err(_) => return Ok(()),
-        };
-
-        buf.write(self).unwrap();
+        let data = self.to_bin(format)?;
 
         match OpenOptions::new()
             .truncate(true)
@@ -550,7 +1197,7 @@ impl MapData {
             .open(&name)
         {
             Ok(mut file) => {
-                if let Err(e) = file.write(buf.as_slice()) {
+                if let Err(e) = file.write(&data) {
                     Err(AscendingError::Other(OtherError::new(&format!(
                         "File Error Err {:?}",
                         e
@@ -572,12 +1219,17 @@ pub fn create_file(
     y: i32,
     group: u64,
     data: &MapData,
+    layout: MapFileLayout,
 ) -> Result<(), AscendingError> {
     let name = format!("./data/maps/{}_{}_{}.json", x, y, group);
 
     match OpenOptions::new().write(true).create_new(true).open(&name) {
-        Ok(file) => {
-            if let Err(e) = serde_json::to_writer_pretty(&file, &data) {
+        Ok(mut file) => {
+            let result = data.to_json(layout).and_then(|data| {
+                file.write_all(data.as_bytes())
+                    .map_err(serde_json::Error::io)
+            });
+            if let Err(e) = result {
                 Err(AscendingError::Other(OtherError::new(&format!(
                     "Serdes File Error Err {:?}",
                     e
@@ -594,6 +1246,21 @@ pub fn create_file(
     }
 }
 
+pub fn load_file_bin(
+    x: i32,
+    y: i32,
+    group: u64,
+) -> Result<MapData, AscendingError> {
+    let name = format!("./data/maps/{}_{}_{}.bin", x, y, group);
+    match std::fs::read(&name) {
+        Ok(data) => MapData::from_bin(&data),
+        Err(e) => Err(AscendingError::Other(OtherError::new(&format!(
+            "Failed to open {}, Err {:?}",
+            name, e
+        )))),
+    }
+}
+
 pub fn load_file(
     x: i32,
     y: i32,
@@ -601,7 +1268,8 @@ pub fn load_file(
 ) -> Result<MapData, AscendingError> {
     if !is_map_exist(x, y, group) {
         let data = MapData::default(x, y, group);
-        match create_file(x, y, group, &MapData::default(x, y, group)) {
+        let layout = load_config().map_file_layout;
+        match create_file(x, y, group, &data, layout) {
             Ok(()) => return Ok(data),
             Err(e) => return Err(e),
         }
@@ -627,11 +1295,91 @@ pub fn load_file(
     }
 }
 
+// Read a map json that is not on the map folder, like a backup
+pub fn read_map_file(path: &Path) -> Result<MapData, AscendingError> {
+    match OpenOptions::new().read(true).open(path) {
+        Ok(file) => {
+            let reader = BufReader::new(file);
+            serde_json::from_reader(reader).map_err(|e| {
+                AscendingError::Other(OtherError::new(&format!(
+                    "Serdes File Error Err {:?}",
+                    e
+                )))
+            })
+        }
+        Err(e) => Err(AscendingError::Other(OtherError::new(&format!(
+            "Failed to open {}, Err {:?}",
+            path.display(),
+            e
+        )))),
+    }
+}
+
+pub fn get_backup_path(x: i32, y: i32, group: u64) -> String {
+    format!("./data/backups/{}_{}_{}.json", x, y, group)
+}
+
+// Map offset, starting tile and size of the linked map strips
+pub fn get_link_map_info(maplink: usize) -> (i32, i32, Vec2, Vec2) {
+    match maplink {
+        1 => (0, 1, Vec2::new(0.0, 0.0), Vec2::new(32.0, 2.0)), // Top
+        2 => (1, 1, Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0)),  // Top Right
+        3 => (-1, 0, Vec2::new(30.0, 0.0), Vec2::new(2.0, 32.0)), // Left
+        4 => (1, 0, Vec2::new(0.0, 0.0), Vec2::new(2.0, 32.0)), // Right
+        5 => (-1, -1, Vec2::new(30.0, 30.0), Vec2::new(2.0, 2.0)), // Bottom Left
+        6 => (0, -1, Vec2::new(0.0, 30.0), Vec2::new(32.0, 2.0)),  // Bottom
+        7 => (1, -1, Vec2::new(0.0, 30.0), Vec2::new(2.0, 2.0)), // Bottom Right
+        _ => (-1, 1, Vec2::new(30.0, 0.0), Vec2::new(2.0, 2.0)), // Top Left
+    }
+}
+
 pub fn is_map_exist(x: i32, y: i32, group: u64) -> bool {
     let name = format!("./data/maps/{}_{}_{}.json", x, y, group);
     Path::new(&name).exists()
 }
 
+// List the position of every map file that belongs to the group
+pub fn get_group_maps(group: u64) -> Vec<(i32, i32)> {
+    get_all_maps()
+        .into_iter()
+        .filter(|(_, _, map_group)| *map_group == group)
+        .map(|(x, y, _)| (x, y))
+        .collect()
+}
+
+// List the position and group of every map file in the data directory
+pub fn get_all_maps() -> Vec<(i32, i32, u64)> {
+    let mut list = Vec::new();
+    let dir = match std::fs::read_dir("./data/maps") {
+        Ok(dir) => dir,
+        Err(_) => return list,
+    };
+
+    for entry in dir.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let name = match path.file_stem().and_then(|name| name.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let data: Vec<&str> = name.split('_').collect();
+        if data.len() != 3 {
+            continue;
+        }
+        if let (Ok(x), Ok(y), Ok(group)) = (
+            data[0].parse::<i32>(),
+            data[1].parse::<i32>(),
+            data[2].parse::<u64>(),
+        ) {
+            list.push((x, y, group));
+        }
+    }
+    list.sort_by_key(|(x, y, group)| (*group, *x, *y));
+    list
+}
+
 pub fn get_tile_pos(x: i32, y: i32) -> usize {
     (x + (y * 32_i32)) as usize
 }
@@ -648,3 +1396,106 @@ pub fn convert_to_dir(dir: usize) -> Direction {
         _ => Direction::NorthWest,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_map() -> MapData {
+        let mut mapdata = MapData::default(1, -2, 3);
+        for (index, id) in mapdata.tile[0].id.iter_mut().enumerate() {
+            *id = (index % 7) as u32;
+        }
+        mapdata.tile[4].id[get_tile_pos(31, 31)] = 12;
+        mapdata.dir_block[get_tile_pos(3, 4)] = 9;
+        mapdata.attribute[get_tile_pos(5, 6)] =
+            vec![MapAttribute::Blocked, MapAttribute::Shop(2)];
+        mapdata.attribute[get_tile_pos(0, 31)] =
+            vec![MapAttribute::Sign("Hello".to_string())];
+        mapdata.zones = vec![MapZoneData {
+            name: "Field".to_string(),
+            max_npc: 3,
+            npcs: vec![ZoneNpc::new(4)],
+            spawns: vec![(1, 2)],
+        }];
+        mapdata.music = Some("field.ogg".to_string());
+        mapdata.weather = Weather::Snow;
+        mapdata
+    }
+
+    fn assert_same_map(data: &MapData, expected: &MapData) {
+        assert_eq!(data.position.x, expected.position.x);
+        assert_eq!(data.position.y, expected.position.y);
+        assert_eq!(data.position.group, expected.position.group);
+        assert_eq!(data.tile.len(), expected.tile.len());
+        for (tile, expected_tile) in data.tile.iter().zip(expected.tile.iter())
+        {
+            assert_eq!(tile.id, expected_tile.id);
+        }
+        assert_eq!(data.dir_block, expected.dir_block);
+        assert_eq!(data.attribute, expected.attribute);
+        assert_eq!(data.zones, expected.zones);
+        assert_eq!(data.entities, expected.entities);
+        assert_eq!(data.music, expected.music);
+        assert_eq!(data.weather, expected.weather);
+    }
+
+    #[test]
+    fn json_layouts_round_trip() {
+        let mapdata = test_map();
+        for layout in [
+            MapFileLayout::Verbose,
+            MapFileLayout::Rows,
+            MapFileLayout::RunLength,
+        ] {
+            let text = mapdata.to_json(layout).unwrap();
+            let data: MapData = serde_json::from_str(&text).unwrap();
+            assert_same_map(&data, &mapdata);
+        }
+    }
+
+    #[test]
+    fn malformed_rows_are_rejected() {
+        for layout in [MapFileLayout::Rows, MapFileLayout::RunLength] {
+            let text = test_map().to_json(layout).unwrap();
+            let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+            // A tile is missing from one row of the first layer
+            let mut short_row = value.clone();
+            let row = short_row["tile"][0][5].as_array_mut().unwrap();
+            match layout {
+                MapFileLayout::RunLength => {
+                    let count = row[0][0].as_u64().unwrap();
+                    row[0][0] = (count - 1).into();
+                }
+                _ => {
+                    row.pop();
+                }
+            }
+            assert!(serde_json::from_value::<MapData>(short_row).is_err());
+
+            // The dir blocks are missing their last row
+            let mut missing_row = value;
+            missing_row["dir_block"].as_array_mut().unwrap().pop();
+            assert!(serde_json::from_value::<MapData>(missing_row).is_err());
+        }
+    }
+
+    #[test]
+    fn compressed_bin_round_trip() {
+        let mapdata = test_map();
+        let data = mapdata.to_bin(MapBinFormat::Compressed).unwrap();
+        assert_same_map(&MapData::from_bin(&data).unwrap(), &mapdata);
+    }
+
+    #[test]
+    fn runs_must_hold_the_whole_map() {
+        assert_eq!(
+            expand_runs(vec![(1000, 1u8), (24, 2)]).unwrap().len(),
+            1024
+        );
+        assert!(expand_runs(vec![(1000, 1u8)]).is_err());
+        assert!(expand_runs(vec![(1000, 1u8), (25, 2)]).is_err());
+        assert!(expand_runs(vec![(u32::MAX, 1u8), (1025, 2)]).is_err());
+    }
+}
@@ -0,0 +1,653 @@
+use graphics::*;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::{
+    config::load_config,
+    map::{match_zones, zone_label, MapAttribute, MapEntity},
+    map_data::*,
+    validation::ValidationIssue,
+};
+
+// Conflicts left by the merges, they are shown in the editor until the
+// map is saved again
+pub const MERGE_CONFLICTS_PATH: &str = "./data/merge_conflicts.json";
+
+// A tile or a setting of the map that was changed differently on both sides
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MergeConflict {
+    pub x: i32,
+    pub y: i32,
+    pub group: u64,
+    pub tile: Option<(u32, u32)>,
+    pub reason: String,
+}
+
+impl MergeConflict {
+    pub fn to_issue(&self) -> ValidationIssue {
+        ValidationIssue {
+            x: self.x,
+            y: self.y,
+            group: self.group,
+            tile: self.tile,
+            reason: format!("Merge conflict: {}", self.reason),
+        }
+    }
+}
+
+pub struct MapMerge {
+    pub map: MapData,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+// The side that changed is taken, ours is kept when both sides changed
+// the value differently
+fn merge_value<T: PartialEq + Clone>(
+    base: &T,
+    ours: &T,
+    theirs: &T,
+) -> (T, bool) {
+    if ours == theirs || theirs == base {
+        (ours.clone(), false)
+    } else if ours == base {
+        (theirs.clone(), false)
+    } else {
+        (ours.clone(), true)
+    }
+}
+
+fn attribute_names(list: &[MapAttribute]) -> String {
+    if list.is_empty() {
+        return "None".to_string();
+    }
+    list.iter()
+        .map(|attribute| attribute.name().to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn entity_names(list: &[MapEntity]) -> String {
+    if list.is_empty() {
+        return "None".to_string();
+    }
+    list.iter()
+        .map(|entity| format!("{} {}", entity.kind.as_str(), entity.index))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Zones are paired by their content like the diff does, as a zone that is
+// removed on one side moves the zones that follow it on the list. Each pair
+// is (base, ours, theirs), in the order of our zones with the zones that
+// we removed and the zones that only they added at the end
+fn pair_zones(
+    base: &MapData,
+    ours: &MapData,
+    theirs: &MapData,
+) -> Vec<(Option<usize>, Option<usize>, Option<usize>)> {
+    let ours_base = match_zones(&ours.zones, &base.zones);
+    let theirs_base = match_zones(&theirs.zones, &base.zones);
+    let theirs_index = |base_index: usize| {
+        theirs_base
+            .iter()
+            .position(|index| *index == Some(base_index))
+    };
+
+    // The zones that both sides added are paired with each other
+    let added = |data: &MapData, matches: &[Option<usize>]| -> Vec<usize> {
+        (0..data.zones.len())
+            .filter(|index| matches[*index].is_none())
+            .collect()
+    };
+    let (ours_added, theirs_added) =
+        (added(ours, &ours_base), added(theirs, &theirs_base));
+    let added_match = match_zones(
+        &ours_added
+            .iter()
+            .map(|index| ours.zones[*index].clone())
+            .collect::<Vec<MapZoneData>>(),
+        &theirs_added
+            .iter()
+            .map(|index| theirs.zones[*index].clone())
+            .collect::<Vec<MapZoneData>>(),
+    );
+
+    let mut pairs = Vec::new();
+    for (index, base_index) in ours_base.iter().enumerate() {
+        let other_index = match base_index {
+            Some(base_index) => theirs_index(*base_index),
+            None => ours_added
+                .iter()
+                .position(|added| *added == index)
+                .and_then(|position| added_match[position])
+                .map(|position| theirs_added[position]),
+        };
+        pairs.push((*base_index, Some(index), other_index));
+    }
+    for base_index in 0..base.zones.len() {
+        if !ours_base.contains(&Some(base_index)) {
+            pairs.push((Some(base_index), None, theirs_index(base_index)));
+        }
+    }
+    for (position, index) in theirs_added.iter().enumerate() {
+        if !added_match.contains(&Some(position)) {
+            pairs.push((None, None, Some(*index)));
+        }
+    }
+    pairs
+}
+
+// Merge the maps tile by tile, on each layer, dir block and attribute
+pub fn merge_maps(
+    base: &MapData,
+    ours: &MapData,
+    theirs: &MapData,
+) -> MapMerge {
+    let (x, y, group) =
+        (ours.position.x, ours.position.y, ours.position.group as u64);
+    let mut map = MapData::default(x, y, group);
+    let mut conflicts = Vec::new();
+    let mut add_conflict = |tile: Option<(u32, u32)>, reason: String| {
+        conflicts.push(MergeConflict {
+            x,
+            y,
+            group,
+            tile,
+            reason,
+        })
+    };
+
+    for tile_num in 0..1024 {
+        let tile = Some(((tile_num % 32) as u32, (tile_num / 32) as u32));
+
+        for (layer, merged) in map.tile.iter_mut().enumerate() {
+            let id = |data: &MapData| {
+                data.tile
+                    .get(layer)
+                    .and_then(|tile| tile.id.get(tile_num))
+                    .copied()
+                    .unwrap_or_default()
+            };
+            let (base_id, ours_id, theirs_id) =
+                (id(base), id(ours), id(theirs));
+            let (value, conflict) = merge_value(&base_id, &ours_id, &theirs_id);
+            merged.id[tile_num] = value;
+            if conflict {
+                add_conflict(
+                    tile,
+                    format!(
+                        "Layer {} tile {} / {} (was {})",
+                        layer + 1,
+                        ours_id,
+                        theirs_id,
+                        base_id
+                    ),
+                );
+            }
+        }
+
+        let dir_block = |data: &MapData| {
+            data.dir_block.get(tile_num).copied().unwrap_or_default()
+        };
+        let (base_dir, ours_dir, theirs_dir) =
+            (dir_block(base), dir_block(ours), dir_block(theirs));
+        let (value, conflict) = merge_value(&base_dir, &ours_dir, &theirs_dir);
+        map.dir_block[tile_num] = value;
+        if conflict {
+            add_conflict(
+                tile,
+                format!(
+                    "Dir block {} / {} (was {})",
+                    ours_dir, theirs_dir, base_dir
+                ),
+            );
+        }
+
+        let attribute = |data: &MapData| {
+            data.attribute.get(tile_num).cloned().unwrap_or_default()
+        };
+        let (base_list, ours_list, theirs_list) =
+            (attribute(base), attribute(ours), attribute(theirs));
+        let (value, conflict) =
+            merge_value(&base_list, &ours_list, &theirs_list);
+        map.attribute[tile_num] = value;
+        if conflict {
+            add_conflict(
+                tile,
+                format!(
+                    "Attributes {} / {} (was {})",
+                    attribute_names(&ours_list),
+                    attribute_names(&theirs_list),
+                    attribute_names(&base_list)
+                ),
+            );
+        }
+
+        // Entities are merged with the tile that they are placed on
+        let entities = |data: &MapData| -> Vec<MapEntity> {
+            data.entities
+                .iter()
+                .filter(|entity| {
+                    get_tile_pos(entity.x as i32, entity.y as i32) == tile_num
+                })
+                .cloned()
+                .collect()
+        };
+        let (base_list, ours_list, theirs_list) =
+            (entities(base), entities(ours), entities(theirs));
+        let (value, conflict) =
+            merge_value(&base_list, &ours_list, &theirs_list);
+        map.entities.extend(value);
+        if conflict {
+            add_conflict(
+                tile,
+                format!(
+                    "Entities {} / {} (was {})",
+                    entity_names(&ours_list),
+                    entity_names(&theirs_list),
+                    entity_names(&base_list)
+                ),
+            );
+        }
+    }
+
+    // The settings, npc entries and spawn tiles of the paired zones are
+    // merged one by one
+    for (base_index, ours_index, theirs_index) in pair_zones(base, ours, theirs)
+    {
+        let base_zone = base_index.map(|index| base.zones[index].clone());
+        let (ours_zone, theirs_zone) = match (ours_index, theirs_index) {
+            (Some(ours_index), Some(theirs_index)) => {
+                (&ours.zones[ours_index], &theirs.zones[theirs_index])
+            }
+            (ours_index, theirs_index) => {
+                // The zone is missing on at least one side
+                let ours_zone =
+                    ours_index.map(|index| ours.zones[index].clone());
+                let theirs_zone =
+                    theirs_index.map(|index| theirs.zones[index].clone());
+                let (value, conflict) =
+                    merge_value(&base_zone, &ours_zone, &theirs_zone);
+                // Only a zone of the base can be removed and changed
+                if let (true, Some(zone), Some(index)) =
+                    (conflict, &base_zone, base_index)
+                {
+                    add_conflict(
+                        None,
+                        format!(
+                            "{} was removed on one side and changed on the \
+                             other",
+                            zone_label(zone, index)
+                        ),
+                    );
+                }
+                map.zones.extend(value);
+                continue;
+            }
+        };
+        let label = zone_label(ours_zone, ours_index.unwrap_or_default());
+        let base_zone = base_zone.unwrap_or_default();
+        let mut zone = MapZoneData::default();
+
+        let (value, conflict) =
+            merge_value(&base_zone.name, &ours_zone.name, &theirs_zone.name);
+        zone.name = value;
+        if conflict {
+            add_conflict(
+                None,
+                format!(
+                    "{} name {} / {} (was {})",
+                    label, ours_zone.name, theirs_zone.name, base_zone.name
+                ),
+            );
+        }
+
+        let (value, conflict) = merge_value(
+            &base_zone.max_npc,
+            &ours_zone.max_npc,
+            &theirs_zone.max_npc,
+        );
+        zone.max_npc = value;
+        if conflict {
+            add_conflict(
+                None,
+                format!(
+                    "{} max npc {} / {} (was {})",
+                    label,
+                    ours_zone.max_npc,
+                    theirs_zone.max_npc,
+                    base_zone.max_npc
+                ),
+            );
+        }
+
+        let npc_count = base_zone
+            .npcs
+            .len()
+            .max(ours_zone.npcs.len())
+            .max(theirs_zone.npcs.len());
+        for npc in 0..npc_count {
+            let entry = |data: &MapZoneData| data.npcs.get(npc).cloned();
+            let (value, conflict) = merge_value(
+                &entry(&base_zone),
+                &entry(ours_zone),
+                &entry(theirs_zone),
+            );
+            zone.npcs.extend(value);
+            if conflict {
+                add_conflict(
+                    None,
+                    format!(
+                        "{} npc {} was changed on both sides",
+                        label,
+                        npc + 1
+                    ),
+                );
+            }
+        }
+
+        // A spawn tile is added or removed by either side, like a tile
+        for spawn in ours_zone.spawns.iter().chain(theirs_zone.spawns.iter()) {
+            if zone.spawns.contains(spawn) {
+                continue;
+            }
+            let has_spawn = |data: &MapZoneData| data.spawns.contains(spawn);
+            let (value, _) = merge_value(
+                &has_spawn(&base_zone),
+                &has_spawn(ours_zone),
+                &has_spawn(theirs_zone),
+            );
+            if value {
+                zone.spawns.push(*spawn);
+            }
+        }
+        map.zones.push(zone);
+    }
+
+    let (value, conflict) =
+        merge_value(&base.music, &ours.music, &theirs.music);
+    map.music = value;
+    if conflict {
+        add_conflict(
+            None,
+            format!(
+                "Music {} / {}",
+                ours.music.as_deref().unwrap_or("None"),
+                theirs.music.as_deref().unwrap_or("None")
+            ),
+        );
+    }
+
+    let (value, conflict) =
+        merge_value(&base.weather, &ours.weather, &theirs.weather);
+    map.weather = value;
+    if conflict {
+        add_conflict(
+            None,
+            format!("Weather {:?} / {:?}", ours.weather, theirs.weather),
+        );
+    }
+
+    MapMerge { map, conflicts }
+}
+
+pub fn load_merge_conflicts() -> Vec<MergeConflict> {
+    match OpenOptions::new().read(true).open(MERGE_CONFLICTS_PATH) {
+        Ok(file) => {
+            let reader = BufReader::new(file);
+            serde_json::from_reader(reader).unwrap_or_default()
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+// Replace the conflicts of the map, the file is removed once every
+// conflict has been resolved
+pub fn set_merge_conflicts(
+    key: (i32, i32, u64),
+    conflicts: Vec<MergeConflict>,
+) -> Result<(), AscendingError> {
+    let mut data = load_merge_conflicts();
+    let count = data.len();
+    data.retain(|conflict| (conflict.x, conflict.y, conflict.group) != key);
+    if data.len() == count && conflicts.is_empty() {
+        return Ok(());
+    }
+    data.extend(conflicts);
+
+    if data.is_empty() {
+        return std::fs::remove_file(MERGE_CONFLICTS_PATH).map_err(|e| {
+            AscendingError::Other(OtherError::new(&format!(
+                "Failed to remove {}, Err {:?}",
+                MERGE_CONFLICTS_PATH, e
+            )))
+        });
+    }
+    match OpenOptions::new()
+        .truncate(true)
+        .write(true)
+        .create(true)
+        .open(MERGE_CONFLICTS_PATH)
+    {
+        Ok(file) => {
+            if let Err(e) = serde_json::to_writer_pretty(&file, &data) {
+                Err(AscendingError::Other(OtherError::new(&format!(
+                    "Serdes File Error Err {:?}",
+                    e
+                ))))
+            } else {
+                Ok(())
+            }
+        }
+        Err(e) => Err(AscendingError::Other(OtherError::new(&format!(
+            "Failed to open {}, Err {:?}",
+            MERGE_CONFLICTS_PATH, e
+        )))),
+    }
+}
+
+// The merged map is written with the layout of the editor settings
+fn merge_files(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    output: &str,
+) -> Result<Vec<MergeConflict>, AscendingError> {
+    let merge = merge_maps(
+        &read_map_file(Path::new(base))?,
+        &read_map_file(Path::new(ours))?,
+        &read_map_file(Path::new(theirs))?,
+    );
+
+    let data =
+        merge
+            .map
+            .to_json(load_config().map_file_layout)
+            .map_err(|e| {
+                AscendingError::Other(OtherError::new(&format!(
+                    "Serdes File Error Err {:?}",
+                    e
+                )))
+            })?;
+    if let Err(e) = std::fs::write(output, data) {
+        return Err(AscendingError::Other(OtherError::new(&format!(
+            "Failed to write {}, Err {:?}",
+            output, e
+        ))));
+    }
+
+    let position = &merge.map.position;
+    let key = (position.x, position.y, position.group as u64);
+    set_merge_conflicts(key, merge.conflicts.clone())?;
+    Ok(merge.conflicts)
+}
+
+// Used when the editor is launched with --merge, it can be set as the
+// git merge driver of the map files: --merge %O %A %B %A
+pub fn run_headless_merge(args: &[String]) -> i32 {
+    if args.len() < 4 {
+        println!(
+            "Usage: --merge <base.json> <ours.json> <theirs.json> \
+             <output.json>"
+        );
+        return 1;
+    }
+
+    match merge_files(&args[0], &args[1], &args[2], &args[3]) {
+        Ok(conflicts) => {
+            conflicts.iter().for_each(|conflict| {
+                println!("{}", conflict.to_issue().description());
+            });
+            println!("Merge found {} conflict(s)", conflicts.len());
+            if conflicts.is_empty() {
+                0
+            } else {
+                1
+            }
+        }
+        Err(e) => {
+            println!("Merge Error {:?}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_zone(name: &str, spawns: &[(u16, u16)]) -> MapZoneData {
+        MapZoneData {
+            name: name.to_string(),
+            max_npc: 2,
+            npcs: vec![ZoneNpc::new(1)],
+            spawns: spawns.to_vec(),
+        }
+    }
+
+    fn test_map() -> MapData {
+        let mut mapdata = MapData::default(0, 0, 0);
+        mapdata.tile[0].id[get_tile_pos(1, 1)] = 5;
+        mapdata.zones = vec![
+            test_zone("Field", &[(1, 1), (2, 2)]),
+            test_zone("Cave", &[(9, 9)]),
+        ];
+        mapdata
+    }
+
+    #[test]
+    fn merge_value_takes_the_changed_side() {
+        assert_eq!(merge_value(&1, &1, &1), (1, false));
+        assert_eq!(merge_value(&1, &2, &1), (2, false));
+        assert_eq!(merge_value(&1, &1, &3), (3, false));
+        assert_eq!(merge_value(&1, &4, &4), (4, false));
+        assert_eq!(merge_value(&1, &2, &3), (2, true));
+    }
+
+    #[test]
+    fn merge_maps_without_changes() {
+        let base = test_map();
+        let merge = merge_maps(&base, &base, &base);
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(merge.map.tile[0].id, base.tile[0].id);
+        assert_eq!(merge.map.zones, base.zones);
+    }
+
+    #[test]
+    fn merge_maps_takes_both_sides() {
+        let base = test_map();
+        let (mut ours, mut theirs) = (test_map(), test_map());
+        ours.tile[0].id[get_tile_pos(3, 3)] = 7;
+        theirs.tile[2].id[get_tile_pos(4, 4)] = 8;
+        ours.attribute[get_tile_pos(5, 5)] = vec![MapAttribute::Blocked];
+        theirs.music = Some("cave.ogg".to_string());
+
+        // Both sides edit the same zone without touching the same values
+        ours.zones[0].spawns.push((3, 3));
+        theirs.zones[0].spawns.retain(|spawn| *spawn != (1, 1));
+        theirs.zones[0].max_npc = 6;
+        ours.zones[0].npcs.push(ZoneNpc::new(2));
+        theirs.zones.remove(1);
+
+        let merge = merge_maps(&base, &ours, &theirs);
+        assert!(merge.conflicts.is_empty(), "{:?}", merge.conflicts);
+        assert_eq!(merge.map.tile[0].id[get_tile_pos(3, 3)], 7);
+        assert_eq!(merge.map.tile[2].id[get_tile_pos(4, 4)], 8);
+        assert_eq!(
+            merge.map.attribute[get_tile_pos(5, 5)],
+            vec![MapAttribute::Blocked]
+        );
+        assert_eq!(merge.map.music, Some("cave.ogg".to_string()));
+        assert_eq!(
+            merge.map.zones,
+            vec![MapZoneData {
+                name: "Field".to_string(),
+                max_npc: 6,
+                npcs: vec![ZoneNpc::new(1), ZoneNpc::new(2)],
+                spawns: vec![(2, 2), (3, 3)],
+            }]
+        );
+    }
+
+    #[test]
+    fn merge_maps_pairs_the_zones_that_moved() {
+        let mut base = test_map();
+        base.zones.push(test_zone("Lake", &[(20, 20)]));
+
+        // They remove the first zone while we edit the ones after it
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        theirs.zones.remove(0);
+        theirs.zones[1].npcs.push(ZoneNpc::new(5));
+        ours.zones[1].spawns.push((10, 10));
+        ours.zones[2].max_npc = 8;
+
+        let merge = merge_maps(&base, &ours, &theirs);
+        assert!(merge.conflicts.is_empty(), "{:?}", merge.conflicts);
+        assert_eq!(
+            merge.map.zones,
+            vec![
+                test_zone("Cave", &[(9, 9), (10, 10)]),
+                MapZoneData {
+                    max_npc: 8,
+                    npcs: vec![ZoneNpc::new(1), ZoneNpc::new(5)],
+                    ..test_zone("Lake", &[(20, 20)])
+                },
+            ]
+        );
+
+        // We remove the middle zone while they edit the last one
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        ours.zones.remove(1);
+        theirs.zones[2].name = "Pond".to_string();
+        let merge = merge_maps(&base, &ours, &theirs);
+        assert!(merge.conflicts.is_empty(), "{:?}", merge.conflicts);
+        let names: Vec<&str> = merge
+            .map
+            .zones
+            .iter()
+            .map(|zone| zone.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Field", "Pond"]);
+    }
+
+    #[test]
+    fn merge_maps_keeps_ours_on_conflicts() {
+        let base = test_map();
+        let (mut ours, mut theirs) = (test_map(), test_map());
+        ours.tile[0].id[get_tile_pos(1, 1)] = 6;
+        theirs.tile[0].id[get_tile_pos(1, 1)] = 7;
+        ours.zones[0].name = "Meadow".to_string();
+        theirs.zones[0].name = "Plains".to_string();
+        ours.zones.remove(1);
+        theirs.zones[1].max_npc = 9;
+
+        let merge = merge_maps(&base, &ours, &theirs);
+        assert_eq!(merge.conflicts.len(), 3);
+        assert_eq!(merge.conflicts[0].tile, Some((1, 1)));
+        assert_eq!(merge.map.tile[0].id[get_tile_pos(1, 1)], 6);
+        assert_eq!(merge.map.zones.len(), 1);
+        assert_eq!(merge.map.zones[0].name, "Meadow");
+    }
+}
@@ -0,0 +1,686 @@
+use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping, SwashCache};
+use graphics::*;
+use image::{imageops, Rgba, RgbaImage};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::{
+    collection::TEXTURE_SIZE,
+    map::{
+        get_dir_visible, get_zone_color, load_attribute_schema, AttributeSchema,
+    },
+    map_data::*,
+    resource::TextureAllocation,
+};
+
+const TILE_INDEX_PATH: &str = "./data/tile_index.json";
+// Size of a map on the rendered image, in pixels
+pub const MAP_PIXELS: u32 = 32 * TEXTURE_SIZE;
+
+// The tile ids of the maps are given by the texture atlas when the editor
+// starts, this keeps where each of them came from for the renders that
+// are made without a window
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TileIndex {
+    pub tilesheets: Vec<String>,
+    pub tiles: IndexMap<usize, (u32, u32, u32)>,
+}
+
+impl TileIndex {
+    pub fn new(resource: &TextureAllocation) -> Self {
+        Self {
+            tilesheets: resource
+                .tilesheet
+                .iter()
+                .map(|sheet| sheet.name.clone())
+                .collect(),
+            tiles: resource.tile_location.clone(),
+        }
+    }
+}
+
+// Tilesheets are loaded from tile_0.png until a number is missing
+fn tilesheet_names() -> Vec<String> {
+    (0..)
+        .map(|count| format!("tile_{}.png", count))
+        .take_while(|name| {
+            Path::new(&format!("./images/tiles/{}", name)).exists()
+        })
+        .collect()
+}
+
+// The tiles are given their id in the same order as the texture atlas,
+// one tilesheet after another and row by row within the tilesheet. The
+// tiles that are fully transparent are empty and get no id
+fn build_tile_index() -> Result<TileIndex, AscendingError> {
+    let mut index = TileIndex {
+        tilesheets: tilesheet_names(),
+        tiles: IndexMap::new(),
+    };
+    let mut next_id = 1;
+    for (sheet, name) in index.tilesheets.iter().enumerate() {
+        let path = format!("./images/tiles/{}", name);
+        let image = image::open(&path)
+            .map_err(|e| {
+                AscendingError::Other(OtherError::new(&format!(
+                    "Failed to open {}, Err {:?}",
+                    path, e
+                )))
+            })?
+            .to_rgba8();
+        for row in 0..image.height() / TEXTURE_SIZE {
+            for column in 0..image.width() / TEXTURE_SIZE {
+                let (x, y) = (column * TEXTURE_SIZE, row * TEXTURE_SIZE);
+                let is_empty = (y..y + TEXTURE_SIZE).all(|pixel_y| {
+                    (x..x + TEXTURE_SIZE).all(|pixel_x| {
+                        image.get_pixel(pixel_x, pixel_y).0[3] == 0
+                    })
+                });
+                if !is_empty {
+                    index.tiles.insert(next_id, (x, y, sheet as u32));
+                    next_id += 1;
+                }
+            }
+        }
+    }
+    Ok(index)
+}
+
+fn write_tile_index(data: &TileIndex) -> Result<(), AscendingError> {
+    match OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(TILE_INDEX_PATH)
+    {
+        Ok(file) => serde_json::to_writer(&file, data).map_err(|e| {
+            AscendingError::Other(OtherError::new(&format!(
+                "Serdes File Error Err {:?}",
+                e
+            )))
+        }),
+        Err(e) => Err(AscendingError::Other(OtherError::new(&format!(
+            "Failed to open {}, Err {:?}",
+            TILE_INDEX_PATH, e
+        )))),
+    }
+}
+
+pub fn save_tile_index(
+    resource: &TextureAllocation,
+) -> Result<(), AscendingError> {
+    write_tile_index(&TileIndex::new(resource))
+}
+
+// The saved index is only used while it is newer than every tilesheet
+fn read_tile_index(tilesheets: &[String]) -> Option<TileIndex> {
+    let modified =
+        |path: &str| fs::metadata(path).and_then(|data| data.modified());
+    let saved = modified(TILE_INDEX_PATH).ok()?;
+    let is_current = tilesheets.iter().all(|name| {
+        modified(&format!("./images/tiles/{}", name))
+            .map(|time| time <= saved)
+            .unwrap_or_default()
+    });
+    if !is_current {
+        return None;
+    }
+    let file = OpenOptions::new().read(true).open(TILE_INDEX_PATH).ok()?;
+    let index: TileIndex =
+        serde_json::from_reader(BufReader::new(file)).ok()?;
+    (index.tilesheets == tilesheets).then_some(index)
+}
+
+// The tile index is made from the tile images, the file written by the
+// editor is a cache that saves reading every tile again
+pub fn load_tile_index() -> Result<TileIndex, AscendingError> {
+    if let Some(index) = read_tile_index(&tilesheet_names()) {
+        return Ok(index);
+    }
+    let index = build_tile_index()?;
+    if let Err(e) = write_tile_index(&index) {
+        println!("Failed to save the tile index: {:?}", e);
+    }
+    Ok(index)
+}
+
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    // Layers that are drawn, from the bottom to the top
+    pub layers: Vec<usize>,
+    pub attributes: bool,
+    pub zones: bool,
+    pub dir_blocks: bool,
+    pub grid: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            layers: (0..9).collect(),
+            attributes: false,
+            zones: false,
+            dir_blocks: false,
+            grid: false,
+        }
+    }
+}
+
+// Draws the maps with the tilesheet images, without the use of the GPU
+pub struct MapRenderer {
+    tilesheets: Vec<RgbaImage>,
+    tiles: IndexMap<usize, (u32, u32, u32)>,
+    attribute_schema: AttributeSchema,
+}
+
+impl MapRenderer {
+    pub fn new() -> Result<Self, AscendingError> {
+        let index = load_tile_index()?;
+        let mut tilesheets = Vec::with_capacity(index.tilesheets.len());
+        for name in index.tilesheets.iter() {
+            let path = format!("./images/tiles/{}", name);
+            let image = image::open(&path).map_err(|e| {
+                AscendingError::Other(OtherError::new(&format!(
+                    "Failed to open {}, Err {:?}",
+                    path, e
+                )))
+            })?;
+            tilesheets.push(image.to_rgba8());
+        }
+        Ok(Self {
+            tilesheets,
+            tiles: index.tiles,
+            attribute_schema: load_attribute_schema(),
+        })
+    }
+
+    // Draw the map on the image, the pos is the top left pixel of the map
+    pub fn draw_map(
+        &self,
+        image: &mut RgbaImage,
+        pos: (i64, i64),
+        mapdata: &MapData,
+        options: &RenderOptions,
+    ) {
+        // Map tiles start from the bottom while the image starts on the top
+        let tile_pos = |tile_num: usize| {
+            let (x, y) = (tile_num as u32 % 32, tile_num as u32 / 32);
+            (
+                pos.0 + (x * TEXTURE_SIZE) as i64,
+                pos.1 + ((31 - y) * TEXTURE_SIZE) as i64,
+            )
+        };
+
+        for layer in options.layers.iter() {
+            let tile = match mapdata.tile.get(*layer) {
+                Some(tile) => tile,
+                None => continue,
+            };
+            for (tile_num, id) in tile.id.iter().enumerate() {
+                let (x, y, sheet) = match self.tiles.get(&(*id as usize)) {
+                    Some(location) if *id > 0 => *location,
+                    _ => continue,
+                };
+                let tilesheet = match self.tilesheets.get(sheet as usize) {
+                    Some(tilesheet) => tilesheet,
+                    None => continue,
+                };
+                let texture = imageops::crop_imm(
+                    tilesheet,
+                    x,
+                    y,
+                    TEXTURE_SIZE,
+                    TEXTURE_SIZE,
+                );
+                let (pixel_x, pixel_y) = tile_pos(tile_num);
+                imageops::overlay(image, &*texture, pixel_x, pixel_y);
+            }
+        }
+
+        if options.zones {
+            for (index, zone) in mapdata.zones.iter().enumerate() {
+                let color = to_rgba(get_zone_color(index));
+                for spawn in zone.spawns.iter() {
+                    let tile_num = get_tile_pos(spawn.0 as i32, spawn.1 as i32);
+                    let (x, y) = tile_pos(tile_num);
+                    let size = TEXTURE_SIZE as i64;
+                    blend_rect(image, (x, y, size, size), color);
+                }
+            }
+        }
+
+        if options.attributes {
+            for (tile_num, attributes) in mapdata.attribute.iter().enumerate() {
+                if attributes.is_empty() {
+                    continue;
+                }
+                let color =
+                    to_rgba(self.attribute_schema.get_tile_color(attributes));
+                let (x, y) = tile_pos(tile_num);
+                let size = TEXTURE_SIZE as i64;
+                blend_rect(image, (x, y, size, size), color);
+            }
+        }
+
+        // A bar is drawn on each blocked side of the tile
+        if options.dir_blocks {
+            let color = Rgba([220, 30, 30, 220]);
+            let (size, bar) = (TEXTURE_SIZE as i64, 3);
+            for (tile_num, dir) in mapdata.dir_block.iter().enumerate() {
+                let (x, y) = tile_pos(tile_num);
+                let bars = [
+                    (x, y, size, bar),
+                    (x, y, bar, size),
+                    (x, y + size - bar, size, bar),
+                    (x + size - bar, y, bar, size),
+                ];
+                for (visible, rect) in get_dir_visible(*dir).iter().zip(bars) {
+                    if *visible {
+                        blend_rect(image, rect, color);
+                    }
+                }
+            }
+        }
+
+        if options.grid {
+            let color = Rgba([0, 0, 0, 60]);
+            let size = MAP_PIXELS as i64;
+            for index in 0..32 {
+                let offset = (index * TEXTURE_SIZE) as i64;
+                blend_rect(image, (pos.0 + offset, pos.1, 1, size), color);
+                blend_rect(image, (pos.0, pos.1 + offset, size, 1), color);
+            }
+        }
+    }
+
+    pub fn render_map(
+        &self,
+        mapdata: &MapData,
+        options: &RenderOptions,
+    ) -> RgbaImage {
+        let mut image = RgbaImage::new(MAP_PIXELS, MAP_PIXELS);
+        self.draw_map(&mut image, (0, 0), mapdata, options);
+        image
+    }
+}
+
+pub fn to_rgba(color: Color) -> Rgba<u8> {
+    Rgba([color.r(), color.g(), color.b(), color.a()])
+}
+
+fn image_error(path: &Path, e: image::ImageError) -> AscendingError {
+    AscendingError::Other(OtherError::new(&format!(
+        "Failed to save {:?}, Err {:?}",
+        path, e
+    )))
+}
+
+// Blend the color over the pixels of the rect, the parts outside of the
+// image are skipped
+pub fn blend_rect(
+    image: &mut RgbaImage,
+    rect: (i64, i64, i64, i64),
+    color: Rgba<u8>,
+) {
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    let alpha = color[3] as u32;
+    for y in rect.1.max(0)..(rect.1 + rect.3).min(height) {
+        for x in rect.0.max(0)..(rect.0 + rect.2).min(width) {
+            let pixel = image.get_pixel_mut(x as u32, y as u32);
+            for index in 0..3 {
+                pixel[index] = ((color[index] as u32 * alpha
+                    + pixel[index] as u32 * (255 - alpha))
+                    / 255) as u8;
+            }
+            pixel[3] = pixel[3].max(color[3]);
+        }
+    }
+}
+
+// Read the options that follow the map location on the command line
+pub fn parse_render_options(args: &[String]) -> RenderOptions {
+    let mut options = RenderOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--layers" => {
+                if let Some(list) = args.next() {
+                    options.layers = list
+                        .split(',')
+                        .filter_map(|layer| layer.trim().parse().ok())
+                        .filter(|layer| *layer < 9)
+                        .collect();
+                }
+            }
+            "--attributes" => options.attributes = true,
+            "--zones" => options.zones = true,
+            "--dir-blocks" => options.dir_blocks = true,
+            "--grid" => options.grid = true,
+            "--overlays" => {
+                options.attributes = true;
+                options.zones = true;
+                options.dir_blocks = true;
+                options.grid = true;
+            }
+            _ => println!("Unknown render option {}", arg),
+        }
+    }
+    options
+}
+
+// Used when the editor is launched with
+// --render <x> <y> <group> <output.png> [options]
+pub fn run_headless_render(args: &[String]) -> i32 {
+    if args.len() < 4 {
+        println!(
+            "Usage: --render <x> <y> <group> <output.png> [--layers 0,1,2] \
+             [--attributes] [--zones] [--dir-blocks] [--grid] [--overlays]"
+        );
+        return 1;
+    }
+    let (x, y, group) = match (
+        args[0].parse::<i32>(),
+        args[1].parse::<i32>(),
+        args[2].parse::<u64>(),
+    ) {
+        (Ok(x), Ok(y), Ok(group)) => (x, y, group),
+        _ => {
+            println!("The map location must be numbers");
+            return 1;
+        }
+    };
+    if !is_map_exist(x, y, group) {
+        println!("Map {}_{}_{} does not exist", x, y, group);
+        return 1;
+    }
+    let options = parse_render_options(&args[4..]);
+
+    let result = MapRenderer::new().and_then(|renderer| {
+        let mapdata = load_file(x, y, group)?;
+        renderer
+            .render_map(&mapdata, &options)
+            .save(Path::new(&args[3]))
+            .map_err(|e| image_error(Path::new(&args[3]), e))
+    });
+    match result {
+        Ok(()) => {
+            println!("Map {}_{}_{} saved to {}", x, y, group, args[3]);
+            0
+        }
+        Err(e) => {
+            println!("Render Error {:?}", e);
+            1
+        }
+    }
+}
+
+// World //
+const MAP_NAMES_PATH: &str = "./data/map_names.json";
+// Size of the images of the tile pyramid
+const PYRAMID_TILE: u32 = 256;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum WorldLabel {
+    #[default]
+    None,
+    Coordinates,
+    // Names of the maps from ./data/map_names.json, keyed by x_y_group
+    Names,
+}
+
+#[derive(Clone, Debug)]
+pub struct WorldOptions {
+    pub render: RenderOptions,
+    // Size of the maps compared to their full size
+    pub scale: f32,
+    pub label: WorldLabel,
+}
+
+impl Default for WorldOptions {
+    fn default() -> Self {
+        Self {
+            render: RenderOptions::default(),
+            scale: 1.0,
+            label: WorldLabel::None,
+        }
+    }
+}
+
+fn load_map_names() -> IndexMap<String, String> {
+    match OpenOptions::new().read(true).open(MAP_NAMES_PATH) {
+        Ok(file) => {
+            serde_json::from_reader(BufReader::new(file)).unwrap_or_default()
+        }
+        Err(_) => IndexMap::new(),
+    }
+}
+
+// Write the text on the image with the fonts of the system, the labels
+// are skipped when there are no fonts to be found
+fn draw_label(
+    image: &mut RgbaImage,
+    font: &mut (FontSystem, SwashCache),
+    pos: (i64, i64),
+    text: &str,
+) {
+    let (font_system, swash_cache) = font;
+    let mut buffer = Buffer::new(font_system, Metrics::new(16.0, 20.0));
+    buffer.set_size(font_system, MAP_PIXELS as f32, 20.0);
+    buffer.set_text(font_system, text, Attrs::new(), Shaping::Advanced);
+
+    let width = buffer
+        .layout_runs()
+        .map(|run| run.line_w)
+        .fold(0.0, f32::max)
+        .ceil() as i64;
+    if width == 0 {
+        return;
+    }
+    blend_rect(image, (pos.0, pos.1, width + 8, 24), Rgba([0, 0, 0, 150]));
+    buffer.draw(
+        font_system,
+        swash_cache,
+        Color::rgba(255, 255, 255, 255),
+        |x, y, w, h, color| {
+            let rect = (pos.0 + 4 + x as i64, pos.1 + 2 + y as i64, w, h);
+            blend_rect(
+                image,
+                (rect.0, rect.1, rect.2 as i64, rect.3 as i64),
+                to_rgba(color),
+            );
+        },
+    );
+}
+
+impl MapRenderer {
+    // Every map of the group is placed at its position, north of a map
+    // is a map with a higher y
+    pub fn render_world(
+        &self,
+        group: u64,
+        options: &WorldOptions,
+    ) -> Result<RgbaImage, AscendingError> {
+        let maps = get_group_maps(group);
+        if maps.is_empty() {
+            return Err(AscendingError::Other(OtherError::new(&format!(
+                "There are no maps on group {}",
+                group
+            ))));
+        }
+        let min_x = maps.iter().map(|(x, _)| *x).min().unwrap_or_default();
+        let max_x = maps.iter().map(|(x, _)| *x).max().unwrap_or_default();
+        let min_y = maps.iter().map(|(_, y)| *y).min().unwrap_or_default();
+        let max_y = maps.iter().map(|(_, y)| *y).max().unwrap_or_default();
+
+        let map_size =
+            ((MAP_PIXELS as f32 * options.scale).round() as u32).max(1);
+        let mut image = RgbaImage::new(
+            (max_x - min_x + 1) as u32 * map_size,
+            (max_y - min_y + 1) as u32 * map_size,
+        );
+        let filter = if map_size < MAP_PIXELS {
+            imageops::FilterType::Triangle
+        } else {
+            imageops::FilterType::Nearest
+        };
+        let names = match options.label {
+            WorldLabel::Names => load_map_names(),
+            _ => IndexMap::new(),
+        };
+        let mut font = match options.label {
+            WorldLabel::None => None,
+            _ => Some((FontSystem::new(), SwashCache::new())),
+        };
+
+        for (x, y) in maps {
+            let mapdata = load_file(x, y, group)?;
+            let mut map_image = self.render_map(&mapdata, &options.render);
+            if map_size != MAP_PIXELS {
+                map_image =
+                    imageops::resize(&map_image, map_size, map_size, filter);
+            }
+            let pos = (
+                (x - min_x) as i64 * map_size as i64,
+                (max_y - y) as i64 * map_size as i64,
+            );
+            imageops::replace(&mut image, &map_image, pos.0, pos.1);
+
+            if let Some(font) = &mut font {
+                let key = format!("{}_{}_{}", x, y, group);
+                let text = match names.get(&key) {
+                    Some(name) => name.clone(),
+                    None => format!("X: {} Y: {}", x, y),
+                };
+                draw_label(&mut image, font, (pos.0 + 4, pos.1 + 4), &text);
+            }
+        }
+        Ok(image)
+    }
+}
+
+// Split the image on tiles for a web map viewer, the last zoom level has
+// the image on its full size and every level before it is half the size
+// of the next one. The tiles are saved as <zoom>/<x>/<y>.png
+pub fn save_tile_pyramid(
+    image: &RgbaImage,
+    dir: &Path,
+) -> Result<u32, AscendingError> {
+    let largest = image.width().max(image.height()) as f32;
+    let max_zoom =
+        (largest / PYRAMID_TILE as f32).log2().ceil().max(0.0) as u32;
+
+    let mut level = image.clone();
+    for zoom in (0..=max_zoom).rev() {
+        let count_x = level.width().div_ceil(PYRAMID_TILE);
+        let count_y = level.height().div_ceil(PYRAMID_TILE);
+        for tile_x in 0..count_x {
+            let tile_dir = dir.join(zoom.to_string()).join(tile_x.to_string());
+            fs::create_dir_all(&tile_dir)?;
+            for tile_y in 0..count_y {
+                // The tiles on the edge are filled up with transparent pixels
+                let mut tile = RgbaImage::new(PYRAMID_TILE, PYRAMID_TILE);
+                let part = imageops::crop_imm(
+                    &level,
+                    tile_x * PYRAMID_TILE,
+                    tile_y * PYRAMID_TILE,
+                    PYRAMID_TILE,
+                    PYRAMID_TILE,
+                );
+                imageops::replace(&mut tile, &*part, 0, 0);
+                let path = tile_dir.join(format!("{}.png", tile_y));
+                tile.save(&path).map_err(|e| image_error(&path, e))?;
+            }
+        }
+        if zoom > 0 {
+            level = imageops::resize(
+                &level,
+                level.width().div_ceil(2).max(1),
+                level.height().div_ceil(2).max(1),
+                imageops::FilterType::Triangle,
+            );
+        }
+    }
+
+    // Settings that the viewer needs to show the pyramid
+    let info = serde_json::json!({
+        "width": image.width(),
+        "height": image.height(),
+        "tile_size": PYRAMID_TILE,
+        "max_zoom": max_zoom,
+    });
+    fs::write(dir.join("pyramid.json"), info.to_string())?;
+    Ok(max_zoom)
+}
+
+// Used when the editor is launched with
+// --world <group> <output.png> [--scale 0.5] [--labels coords|names]
+// [--pyramid <dir>] [render options]
+pub fn run_headless_world(args: &[String]) -> i32 {
+    if args.len() < 2 {
+        println!(
+            "Usage: --world <group> <output.png> [--scale 0.5] \
+             [--labels coords|names] [--pyramid <dir>] [render options]"
+        );
+        return 1;
+    }
+    let group = match args[0].parse::<u64>() {
+        Ok(group) => group,
+        Err(_) => {
+            println!("The group must be a number");
+            return 1;
+        }
+    };
+    let output = Path::new(&args[1]);
+
+    let mut options = WorldOptions::default();
+    let mut pyramid = None;
+    let mut render_args = Vec::new();
+    let mut list = args[2..].iter();
+    while let Some(arg) = list.next() {
+        match arg.as_str() {
+            "--scale" => {
+                options.scale = list
+                    .next()
+                    .and_then(|value| value.parse::<f32>().ok())
+                    .filter(|value| *value > 0.0)
+                    .unwrap_or(1.0);
+            }
+            "--labels" => {
+                options.label = match list.next().map(|value| value.as_str()) {
+                    Some("names") => WorldLabel::Names,
+                    _ => WorldLabel::Coordinates,
+                };
+            }
+            "--pyramid" => pyramid = list.next().map(Path::new),
+            _ => render_args.push(arg.clone()),
+        }
+    }
+    options.render = parse_render_options(&render_args);
+
+    let result = MapRenderer::new().and_then(|renderer| {
+        let image = renderer.render_world(group, &options)?;
+        image.save(output).map_err(|e| image_error(output, e))?;
+        println!(
+            "Group {} saved to {} ({}x{})",
+            group,
+            args[1],
+            image.width(),
+            image.height()
+        );
+        if let Some(dir) = pyramid {
+            let max_zoom = save_tile_pyramid(&image, dir)?;
+            println!("Tile pyramid saved to {:?} up to zoom {}", dir, max_zoom);
+        }
+        Ok(())
+    });
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            println!("Render Error {:?}", e);
+            1
+        }
+    }
+}
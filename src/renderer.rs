@@ -1,9 +1,11 @@
+use camera::controls::FlatControls;
 use graphics::*;
 use winit::dpi::PhysicalSize;
 
 use crate::{
-    gfx_collection::*, interface::*, AudioCollection, ConfigData, MapView,
-    TextureAllocation, Tileset,
+    collection::*, gfx_collection::*, interface::*, AttributeSchema,
+    AudioCollection, ConfigData, GameDatabase, MapCamera, MapView,
+    TextureAllocation, Tileset, MAPVIEW_POS, MAPVIEW_SIZE,
 };
 
 pub struct DrawSetting {
@@ -13,6 +15,8 @@ pub struct DrawSetting {
     pub scale: f64,
     pub resource: TextureAllocation,
     pub audio_list: AudioCollection,
+    pub attribute_schema: AttributeSchema,
+    pub game_data: GameDatabase,
 }
 
 pub struct Graphics<Controls>
@@ -21,6 +25,8 @@ where
 {
     /// World Camera Controls and time. Deturmines how the world is looked at.
     pub system: System<Controls>,
+    /// Camera used by the map view, this handles the zooming and panning of the maps.
+    pub map_system: System<FlatControls>,
     /// Atlas Groups for Textures in GPU
     pub image_atlas: AtlasSet,
     pub map_atlas: AtlasSet,
@@ -84,6 +90,55 @@ where
             wgpu::IndexFormat::Uint32,
         );
 
+        // Map view, this is clipped to the map view area so that zooming
+        // and panning will never draw the map over the interface
+        let size = renderer.size();
+        let view_pos = MAPVIEW_POS * ZOOM_LEVEL;
+        let view_size = MAPVIEW_SIZE * ZOOM_LEVEL;
+        let scissor_y = (size.height - (view_pos.y + view_size.y)).max(0.0);
+        pass.set_scissor_rect(
+            view_pos.x as u32,
+            scissor_y as u32,
+            (view_size.x as u32)
+                .min((size.width as u32).saturating_sub(view_pos.x as u32)),
+            (view_size.y as u32)
+                .min((size.height as u32).saturating_sub(scissor_y as u32)),
+        );
+        pass.set_bind_group(0, self.map_system.bind_group(), &[]);
+        pass.render_map(
+            renderer,
+            &self.map_renderer,
+            &self.map_atlas,
+            RENDER_MAPVIEW_MAP,
+        );
+        pass.render_image(
+            renderer,
+            &self.image_renderer,
+            &self.image_atlas,
+            RENDER_MAPVIEW,
+        );
+        pass.render_rects(
+            renderer,
+            &self.ui_renderer,
+            &self.ui_atlas,
+            RENDER_MAPVIEW,
+        );
+        pass.render_text(
+            renderer,
+            &self.text_renderer,
+            &self.text_atlas,
+            RENDER_MAPVIEW_TEXT,
+        );
+        pass.render_rects(
+            renderer,
+            &self.ui_renderer,
+            &self.ui_atlas,
+            RENDER_WORLDVIEW,
+        );
+
+        // Interface
+        pass.set_scissor_rect(0, 0, size.width as u32, size.height as u32);
+        pass.set_bind_group(0, self.system.bind_group(), &[]);
         pass.render_map(renderer, &self.map_renderer, &self.map_atlas, 0);
         pass.render_image(renderer, &self.image_renderer, &self.image_atlas, 0);
         pass.render_rects(renderer, &self.ui_renderer, &self.ui_atlas, 0);
@@ -108,8 +163,13 @@ pub fn add_image_to_buffer<Controls>(
 ) where
     Controls: camera::controls::Controls,
 {
+    // The map view overlays are replaced by the world view while it is open
+    let world_open = mapview.world.is_open;
     systems.gfx.collection.iter_mut().for_each(|data| {
-        if data.1.visible {
+        let hidden_layer = world_open
+            && (data.1.layer == RENDER_MAPVIEW
+                || data.1.layer == RENDER_MAPVIEW_TEXT);
+        if data.1.visible && !hidden_layer {
             match &mut data.1.gfx {
                 GfxType::Image(image) => {
                     graphics.image_renderer.image_update(
@@ -142,12 +202,17 @@ pub fn add_image_to_buffer<Controls>(
         }
     });
 
-    mapview.maps.iter_mut().for_each(|map| {
+    let maps = if world_open {
+        mapview.world.active_maps()
+    } else {
+        &mut mapview.maps[..]
+    };
+    maps.iter_mut().for_each(|map| {
         graphics.map_renderer.map_update(
             map,
             &mut systems.renderer,
             &mut graphics.map_atlas,
-            [0, 0],
+            [RENDER_MAPVIEW_MAP, RENDER_MAPVIEW_MAP],
         );
     });
     if gui.current_tab == TAB_LAYER {
@@ -159,3 +224,20 @@ pub fn add_image_to_buffer<Controls>(
         ); // Tileset
     }
 }
+
+// Apply the map view zoom and panning to the map camera
+pub fn update_map_camera<Controls>(
+    graphics: &mut Graphics<Controls>,
+    camera: &mut MapCamera,
+) where
+    Controls: camera::controls::Controls,
+{
+    if !camera.changed {
+        return;
+    }
+    camera.changed = false;
+
+    let controls = graphics.map_system.controls_mut();
+    controls.settings_mut().zoom = camera.zoom * ZOOM_LEVEL;
+    controls.inputs_mut().translation = camera.translation() * ZOOM_LEVEL;
+}
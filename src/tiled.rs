@@ -0,0 +1,1423 @@
+use flate2::read::{GzDecoder, ZlibDecoder};
+use graphics::*;
+use indexmap::IndexMap;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Deserialize;
+use serde_json::json;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::{
+    attributes::*,
+    collection::TEXTURE_SIZE,
+    map::AttributeSchema,
+    map_data::*,
+    map_render::TileIndex,
+    tileset::{MAX_TILE_X, MAX_TILE_Y},
+};
+
+// Flags that Tiled stores on the highest bits of the gid for flipped tiles
+const GID_FLAGS: u32 = 0xF000_0000;
+
+#[derive(Clone, Debug, Default)]
+pub struct TiledTileset {
+    pub first_gid: u32,
+    pub name: String,
+    pub image: String,
+    pub columns: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TiledLayer {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u32>,
+    pub properties: IndexMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TiledObject {
+    pub name: String,
+    // Type of the object, this is the class on the newer versions of Tiled
+    pub kind: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub properties: IndexMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TiledObjectGroup {
+    pub name: String,
+    pub objects: Vec<TiledObject>,
+    pub properties: IndexMap<String, String>,
+}
+
+// The parts of a Tiled map that can be used by the editor
+#[derive(Clone, Debug, Default)]
+pub struct TiledMap {
+    pub width: u32,
+    pub height: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub infinite: bool,
+    pub tilesets: Vec<TiledTileset>,
+    pub layers: Vec<TiledLayer>,
+    pub object_groups: Vec<TiledObjectGroup>,
+}
+
+impl TiledMap {
+    // Both the TMX and the JSON format are supported, external tilesets
+    // are looked up next to the map file
+    pub fn load(path: &Path) -> Result<TiledMap, AscendingError> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            tiled_error(format!("Failed to open {:?}, Err {:?}", path, e))
+        })?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "json" | "tmj" => parse_json_map(&text, dir),
+            _ => parse_xml(&text, dir),
+        }
+    }
+
+    fn has_group(&self, name: &str) -> bool {
+        self.object_groups.iter().any(|group| group.name == name)
+    }
+
+    // Objects of the given type within the object layer
+    fn find_objects<'a>(
+        &'a self,
+        group: &'a str,
+        kind: &'a str,
+    ) -> impl Iterator<Item = &'a TiledObject> {
+        self.object_groups
+            .iter()
+            .filter(move |data| data.name == group)
+            .flat_map(|data| data.objects.iter())
+            .filter(move |object| object.kind == kind)
+    }
+
+    fn tile_size(&self) -> (f32, f32) {
+        (
+            self.tile_width.max(1) as f32,
+            self.tile_height.max(1) as f32,
+        )
+    }
+
+    // Tiles that are covered by the object, a point covers a single tile
+    fn object_tiles(&self, object: &TiledObject) -> Vec<(u32, u32)> {
+        let (tile_width, tile_height) = self.tile_size();
+        let start_x = (object.x / tile_width).floor().max(0.0) as u32;
+        let start_y = (object.y / tile_height).floor().max(0.0) as u32;
+        let end_x = ((object.x + object.width) / tile_width)
+            .ceil()
+            .max(start_x as f32 + 1.0) as u32;
+        let end_y = ((object.y + object.height) / tile_height)
+            .ceil()
+            .max(start_y as f32 + 1.0) as u32;
+        (start_y..end_y.min(self.height))
+            .flat_map(|tile_y| {
+                (start_x..end_x.min(self.width))
+                    .map(move |tile_x| (tile_x, tile_y))
+            })
+            .collect()
+    }
+
+    // Map that holds the center of the object
+    fn object_map(&self, object: &TiledObject) -> (i32, i32) {
+        let (tile_width, tile_height) = self.tile_size();
+        let center_x = (object.x + object.width * 0.5) / tile_width;
+        let center_y = (object.y + object.height * 0.5) / tile_height;
+        ((center_x as u32 / 32) as i32, (center_y as u32 / 32) as i32)
+    }
+}
+
+fn get_property(object: &TiledObject, name: &str) -> i64 {
+    object
+        .properties
+        .get(name)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+fn tiled_error(msg: String) -> AscendingError {
+    AscendingError::Other(OtherError::new(&msg))
+}
+
+fn load_external_tileset(
+    dir: &Path,
+    source: &str,
+    first_gid: u32,
+) -> Result<TiledTileset, AscendingError> {
+    let path = dir.join(source);
+    let text = fs::read_to_string(&path).map_err(|e| {
+        tiled_error(format!("Failed to open {:?}, Err {:?}", path, e))
+    })?;
+    let tile_dir = path.parent().unwrap_or(Path::new("."));
+    let mut tileset = if source.ends_with(".json") || source.ends_with(".tsj") {
+        let data: JsonTileset = serde_json::from_str(&text).map_err(|e| {
+            tiled_error(format!("Serdes File Error Err {:?}", e))
+        })?;
+        data.into_tileset(tile_dir)?
+    } else {
+        parse_xml(&text, tile_dir)?
+            .tilesets
+            .into_iter()
+            .next()
+            .ok_or_else(|| tiled_error(format!("{:?} has no tileset", path)))?
+    };
+    tileset.first_gid = first_gid;
+    Ok(tileset)
+}
+
+// Layer data can be csv or base64, the base64 data can be compressed with
+// zlib or gzip
+fn decode_layer_data(
+    text: &str,
+    encoding: &str,
+    compression: &str,
+) -> Result<Vec<u32>, AscendingError> {
+    match encoding {
+        "csv" => Ok(text
+            .split(',')
+            .map(|value| value.trim().parse::<u32>().unwrap_or_default())
+            .collect()),
+        "base64" => {
+            let bytes = decompress_layer_data(
+                decode_base64(text.trim())?,
+                compression,
+            )?;
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+                .collect())
+        }
+        _ => Err(tiled_error(format!(
+            "Layer encoding {} is not supported",
+            encoding
+        ))),
+    }
+}
+
+fn decompress_layer_data(
+    bytes: Vec<u8>,
+    compression: &str,
+) -> Result<Vec<u8>, AscendingError> {
+    let mut data = Vec::new();
+    let result = match compression {
+        "" => return Ok(bytes),
+        "zlib" => ZlibDecoder::new(bytes.as_slice()).read_to_end(&mut data),
+        "gzip" => GzDecoder::new(bytes.as_slice()).read_to_end(&mut data),
+        _ => {
+            return Err(tiled_error(format!(
+                "Compressed layer data ({}) is not supported, \
+                 save the map with zlib, gzip or no compression",
+                compression
+            )))
+        }
+    };
+    result.map_err(|e| {
+        tiled_error(format!(
+            "Failed to decompress the {} layer data, Err {:?}",
+            compression, e
+        ))
+    })?;
+    Ok(data)
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>, AscendingError> {
+    let value = |char: u8| -> Result<u32, AscendingError> {
+        match char {
+            b'A'..=b'Z' => Ok((char - b'A') as u32),
+            b'a'..=b'z' => Ok((char - b'a' + 26) as u32),
+            b'0'..=b'9' => Ok((char - b'0' + 52) as u32),
+            b'+' => Ok(62),
+            b'/' => Ok(63),
+            _ => Err(tiled_error("Invalid base64 layer data".to_string())),
+        }
+    };
+    let chars: Vec<u8> = text
+        .bytes()
+        .filter(|char| !char.is_ascii_whitespace() && *char != b'=')
+        .collect();
+    let mut bytes = Vec::with_capacity(chars.len() * 3 / 4);
+    for chunk in chars.chunks(4) {
+        let mut data = 0;
+        for (index, char) in chunk.iter().enumerate() {
+            data |= value(*char)? << (18 - index * 6);
+        }
+        let data = data.to_be_bytes();
+        bytes.extend_from_slice(&data[1..chunk.len()]);
+    }
+    Ok(bytes)
+}
+
+// TMX //
+#[derive(PartialEq, Eq)]
+enum XmlParent {
+    Map,
+    Tileset,
+    Layer,
+    Data,
+    ObjectGroup,
+    Object,
+    Other,
+}
+
+fn get_xml_attributes(data: &BytesStart) -> IndexMap<String, String> {
+    data.attributes()
+        .flatten()
+        .map(|attribute| {
+            (
+                String::from_utf8_lossy(attribute.key.as_ref()).to_string(),
+                attribute
+                    .unescape_value()
+                    .map(|value| value.to_string())
+                    .unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+fn parse_xml(text: &str, dir: &Path) -> Result<TiledMap, AscendingError> {
+    let mut reader = Reader::from_str(text);
+    reader.trim_text(true);
+
+    let mut map = TiledMap::default();
+    let mut parents: Vec<XmlParent> = Vec::new();
+    // Encoding and compression of the layer data that is being read
+    let mut data_format = (String::new(), String::new());
+
+    loop {
+        let (data, is_empty) = match reader.read_event() {
+            Ok(Event::Start(data)) => (data, false),
+            Ok(Event::Empty(data)) => (data, true),
+            Ok(Event::End(_)) => {
+                parents.pop();
+                continue;
+            }
+            Ok(Event::Text(data)) => {
+                if parents.last() == Some(&XmlParent::Data) {
+                    let text = data.unescape().map_err(|e| {
+                        tiled_error(format!("TMX Error Err {:?}", e))
+                    })?;
+                    let gids = decode_layer_data(
+                        &text,
+                        &data_format.0,
+                        &data_format.1,
+                    )?;
+                    if let Some(layer) = map.layers.last_mut() {
+                        layer.data = gids;
+                    }
+                }
+                continue;
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(tiled_error(format!("TMX Error Err {:?}", e)))
+            }
+            _ => continue,
+        };
+
+        let attributes = get_xml_attributes(&data);
+        let get =
+            |name: &str| attributes.get(name).cloned().unwrap_or_default();
+        let get_num = |name: &str| get(name).parse::<f32>().unwrap_or_default();
+        let parent = match data.name().as_ref() {
+            b"map" => {
+                map.width = get_num("width") as u32;
+                map.height = get_num("height") as u32;
+                map.tile_width = get_num("tilewidth") as u32;
+                map.tile_height = get_num("tileheight") as u32;
+                map.infinite = get("infinite") == "1";
+                XmlParent::Map
+            }
+            b"tileset" => {
+                let first_gid = get_num("firstgid") as u32;
+                let source = get("source");
+                map.tilesets.push(if source.is_empty() {
+                    TiledTileset {
+                        first_gid,
+                        name: get("name"),
+                        image: String::new(),
+                        columns: get_num("columns") as u32,
+                    }
+                } else {
+                    load_external_tileset(dir, &source, first_gid)?
+                });
+                XmlParent::Tileset
+            }
+            b"image" if parents.last() == Some(&XmlParent::Tileset) => {
+                if let Some(tileset) = map.tilesets.last_mut() {
+                    tileset.image = get("source");
+                    if tileset.columns == 0 && map.tile_width > 0 {
+                        tileset.columns =
+                            get_num("width") as u32 / map.tile_width.max(1);
+                    }
+                }
+                XmlParent::Other
+            }
+            b"layer" => {
+                map.layers.push(TiledLayer {
+                    name: get("name"),
+                    width: get_num("width") as u32,
+                    height: get_num("height") as u32,
+                    data: Vec::new(),
+                    properties: IndexMap::new(),
+                });
+                XmlParent::Layer
+            }
+            b"data" if parents.last() == Some(&XmlParent::Layer) => {
+                data_format = (get("encoding"), get("compression"));
+                XmlParent::Data
+            }
+            // Layer data without encoding is listed as tile elements
+            b"tile" if parents.last() == Some(&XmlParent::Data) => {
+                if let Some(layer) = map.layers.last_mut() {
+                    layer.data.push(get_num("gid") as u32);
+                }
+                XmlParent::Other
+            }
+            b"objectgroup" => {
+                map.object_groups.push(TiledObjectGroup {
+                    name: get("name"),
+                    objects: Vec::new(),
+                    properties: IndexMap::new(),
+                });
+                XmlParent::ObjectGroup
+            }
+            b"object" => {
+                let kind = if attributes.contains_key("class") {
+                    get("class")
+                } else {
+                    get("type")
+                };
+                if let Some(group) = map.object_groups.last_mut() {
+                    group.objects.push(TiledObject {
+                        name: get("name"),
+                        kind,
+                        x: get_num("x"),
+                        y: get_num("y"),
+                        width: get_num("width"),
+                        height: get_num("height"),
+                        properties: IndexMap::new(),
+                    });
+                }
+                XmlParent::Object
+            }
+            b"property" => {
+                let (name, value) = (get("name"), get("value"));
+                // Properties belong to the closest layer or object
+                let owner = parents.iter().rev().find(|parent| {
+                    matches!(
+                        parent,
+                        XmlParent::Layer
+                            | XmlParent::ObjectGroup
+                            | XmlParent::Object
+                    )
+                });
+                let properties = match owner {
+                    Some(XmlParent::Layer) => {
+                        map.layers.last_mut().map(|layer| &mut layer.properties)
+                    }
+                    Some(XmlParent::ObjectGroup) => map
+                        .object_groups
+                        .last_mut()
+                        .map(|group| &mut group.properties),
+                    Some(XmlParent::Object) => map
+                        .object_groups
+                        .last_mut()
+                        .and_then(|group| group.objects.last_mut())
+                        .map(|object| &mut object.properties),
+                    _ => None,
+                };
+                if let Some(properties) = properties {
+                    properties.insert(name, value);
+                }
+                XmlParent::Other
+            }
+            _ => XmlParent::Other,
+        };
+        if !is_empty {
+            parents.push(parent);
+        }
+    }
+
+    Ok(map)
+}
+
+// JSON //
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    value: serde_json::Value,
+}
+
+fn json_properties(list: &[JsonProperty]) -> IndexMap<String, String> {
+    list.iter()
+        .map(|property| {
+            let value = match &property.value {
+                serde_json::Value::String(text) => text.clone(),
+                value => value.to_string(),
+            };
+            (property.name.clone(), value)
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct JsonTileset {
+    #[serde(default)]
+    firstgid: u32,
+    source: Option<String>,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    columns: u32,
+}
+
+impl JsonTileset {
+    fn into_tileset(self, dir: &Path) -> Result<TiledTileset, AscendingError> {
+        match self.source {
+            Some(source) => load_external_tileset(dir, &source, self.firstgid),
+            None => Ok(TiledTileset {
+                first_gid: self.firstgid,
+                name: self.name,
+                image: self.image,
+                columns: self.columns,
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    #[serde(default)]
+    x: f32,
+    #[serde(default)]
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    width: u32,
+    #[serde(default)]
+    height: u32,
+    data: Option<serde_json::Value>,
+    #[serde(default)]
+    encoding: String,
+    #[serde(default)]
+    compression: String,
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+    // Layers of a group layer
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonMap {
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    #[serde(default)]
+    infinite: bool,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+}
+
+fn add_json_layers(
+    map: &mut TiledMap,
+    layers: Vec<JsonLayer>,
+) -> Result<(), AscendingError> {
+    for layer in layers {
+        match layer.kind.as_str() {
+            "tilelayer" => {
+                let data = match layer.data {
+                    Some(serde_json::Value::Array(list)) => list
+                        .iter()
+                        .map(|gid| gid.as_u64().unwrap_or_default() as u32)
+                        .collect(),
+                    Some(serde_json::Value::String(text)) => decode_layer_data(
+                        &text,
+                        &layer.encoding,
+                        &layer.compression,
+                    )?,
+                    _ => Vec::new(),
+                };
+                map.layers.push(TiledLayer {
+                    name: layer.name,
+                    width: layer.width,
+                    height: layer.height,
+                    data,
+                    properties: json_properties(&layer.properties),
+                });
+            }
+            "objectgroup" => {
+                map.object_groups.push(TiledObjectGroup {
+                    name: layer.name,
+                    objects: layer
+                        .objects
+                        .into_iter()
+                        .map(|object| TiledObject {
+                            name: object.name,
+                            kind: if object.class.is_empty() {
+                                object.kind
+                            } else {
+                                object.class
+                            },
+                            x: object.x,
+                            y: object.y,
+                            width: object.width,
+                            height: object.height,
+                            properties: json_properties(&object.properties),
+                        })
+                        .collect(),
+                    properties: json_properties(&layer.properties),
+                });
+            }
+            "group" => add_json_layers(map, layer.layers)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn parse_json_map(text: &str, dir: &Path) -> Result<TiledMap, AscendingError> {
+    let data: JsonMap = serde_json::from_str(text)
+        .map_err(|e| tiled_error(format!("Serdes File Error Err {:?}", e)))?;
+    let mut map = TiledMap {
+        width: data.width,
+        height: data.height,
+        tile_width: data.tilewidth,
+        tile_height: data.tileheight,
+        infinite: data.infinite,
+        ..Default::default()
+    };
+    for tileset in data.tilesets {
+        map.tilesets.push(tileset.into_tileset(dir)?);
+    }
+    add_json_layers(&mut map, data.layers)?;
+    Ok(map)
+}
+
+// Import //
+pub struct TiledImport {
+    pub maps: Vec<MapData>,
+    pub warnings: Vec<String>,
+}
+
+// Layer of the map that the Tiled layer is placed on, by the layer property,
+// then by the name of the layer, and then by the order of the layers
+fn find_target_layer(layer: &TiledLayer, next_index: usize) -> Option<usize> {
+    let find_name = |name: &str| {
+        MapLayers::LAYERS
+            .iter()
+            .position(|layer| layer.as_str().eq_ignore_ascii_case(name))
+    };
+    let index = match layer.properties.get("layer") {
+        Some(value) => value.parse::<usize>().ok().or_else(|| find_name(value)),
+        None => find_name(layer.name.trim()).or(Some(next_index)),
+    };
+    index.filter(|index| *index < MapLayers::LAYERS.len())
+}
+
+// Object layers named attributes, or with the attributes property, are
+// turned into map attributes
+fn is_attribute_group(group: &TiledObjectGroup) -> bool {
+    group.name.eq_ignore_ascii_case("attributes")
+        || group
+            .properties
+            .get("attributes")
+            .map(|value| value == "true")
+            .unwrap_or_default()
+}
+
+// Value of the property as it is read back from the Tiled map
+fn property_text(value: &InsertTypes) -> String {
+    match value {
+        InsertTypes::Int(data) => data.to_string(),
+        InsertTypes::UInt(data) => data.to_string(),
+        InsertTypes::Bool(data) => data.to_string(),
+        InsertTypes::Str(data) => data.clone(),
+    }
+}
+
+fn create_attribute(
+    schema: &AttributeSchema,
+    name: &str,
+    properties: &IndexMap<String, String>,
+) -> Option<MapAttribute> {
+    // The exported attributes keep their whole data, it is used unless one
+    // of the fields was edited on Tiled
+    let saved = properties
+        .get("data")
+        .and_then(|text| serde_json::from_str::<MapAttribute>(text).ok());
+    if let Some(saved) = saved {
+        let fields = schema
+            .get(saved.name())
+            .map(|data| data.fields.as_slice())
+            .unwrap_or_default();
+        let is_edited =
+            fields.iter().zip(saved.get_data()).any(|(field, value)| {
+                properties
+                    .get(&field.name)
+                    .map(|text| *text != property_text(&value))
+                    .unwrap_or_default()
+            });
+        if !is_edited {
+            return Some(saved);
+        }
+    }
+
+    let attribute = schema.get(name)?;
+    let data: Vec<InsertTypes> = attribute
+        .fields
+        .iter()
+        .map(|field| {
+            let default = schema.default_value(&field.kind);
+            match properties.get(&field.name) {
+                Some(value) => match default {
+                    InsertTypes::Int(_) => {
+                        InsertTypes::Int(value.parse().unwrap_or_default())
+                    }
+                    InsertTypes::UInt(_) => {
+                        InsertTypes::UInt(value.parse().unwrap_or_default())
+                    }
+                    InsertTypes::Bool(_) => InsertTypes::Bool(value == "true"),
+                    InsertTypes::Str(_) => InsertTypes::Str(value.clone()),
+                },
+                None => default,
+            }
+        })
+        .collect();
+    Some(MapAttribute::from_data(name, &data))
+}
+
+// Tiled maps that are larger than a map are split across the maps that
+// follow the start map, toward the right and the bottom
+pub fn import_tiled(
+    tile_index: &TileIndex,
+    schema: &AttributeSchema,
+    path: &Path,
+    start: (i32, i32, u64),
+    loaded_maps: &IndexMap<String, MapData>,
+) -> Result<TiledImport, AscendingError> {
+    let tiled = TiledMap::load(path)?;
+    if tiled.infinite {
+        return Err(tiled_error(
+            "Infinite Tiled maps are not supported".to_string(),
+        ));
+    }
+    let mut warnings = Vec::new();
+
+    // Tile id of each column and row of our tilesheets, the rows are
+    // counted from the top of the image like Tiled does
+    let mut tile_ids = IndexMap::new();
+    for (id, (x, y, sheet)) in tile_index.tiles.iter() {
+        let tile = (x / TEXTURE_SIZE, y / TEXTURE_SIZE);
+        tile_ids.insert((*sheet as usize, tile.0, tile.1), *id);
+    }
+    // Tilesets are matched with our tilesheets by their image name
+    let tilesheets: Vec<Option<usize>> = tiled
+        .tilesets
+        .iter()
+        .map(|tileset| {
+            let image = Path::new(&tileset.image)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| format!("{}.png", tileset.name));
+            let index =
+                tile_index.tilesheets.iter().position(|name| *name == image);
+            if index.is_none() {
+                warnings.push(format!(
+                    "Tileset {} is not in ./images/tiles",
+                    tileset.name
+                ));
+            } else if tileset.columns == 0 {
+                warnings
+                    .push(format!("Tileset {} has no columns", tileset.name));
+            }
+            index
+        })
+        .collect();
+    let get_tile_id = |gid: u32| -> usize {
+        let gid = gid & !GID_FLAGS;
+        if gid == 0 {
+            return 0;
+        }
+        let found = tiled
+            .tilesets
+            .iter()
+            .enumerate()
+            .filter(|(_, tileset)| tileset.first_gid <= gid)
+            .max_by_key(|(_, tileset)| tileset.first_gid);
+        let (index, tileset) = match found {
+            Some(data) => data,
+            None => return 0,
+        };
+        let sheet = match tilesheets[index] {
+            Some(sheet) if tileset.columns > 0 => sheet,
+            _ => return 0,
+        };
+        let local = gid - tileset.first_gid;
+        let tile = (local % tileset.columns, local / tileset.columns);
+        tile_ids.get(&(sheet, tile.0, tile.1)).copied().unwrap_or(0)
+    };
+
+    let (map_count_x, map_count_y) = (
+        tiled.width.div_ceil(32) as i32,
+        tiled.height.div_ceil(32) as i32,
+    );
+    let mut maps = IndexMap::new();
+    for count_y in 0..map_count_y {
+        for count_x in 0..map_count_x {
+            let (x, y) = (start.0 + count_x, start.1 - count_y);
+            let key = format!("{}_{}_{}", x, y, start.2);
+            let mut mapdata = match loaded_maps.get(&key) {
+                Some(data) => data.clone(),
+                None if is_map_exist(x, y, start.2) => {
+                    load_file(x, y, start.2)?
+                }
+                None => MapData::default(x, y, start.2),
+            };
+            // The imported area replaces the tiles and attributes
+            let empty = MapData::default(x, y, start.2);
+            mapdata.tile = empty.tile;
+            mapdata.attribute = empty.attribute;
+            mapdata.dir_block = empty.dir_block;
+            maps.insert((count_x, count_y), mapdata);
+        }
+    }
+    // Position of the Tiled tile on the maps, Tiled rows go down
+    let locate = |tile_x: u32, tile_y: u32| {
+        let map = ((tile_x / 32) as i32, (tile_y / 32) as i32);
+        let tile_num =
+            get_tile_pos((tile_x % 32) as i32, 31 - (tile_y % 32) as i32);
+        (map, tile_num)
+    };
+
+    let mut next_index = 0;
+    for layer in tiled.layers.iter() {
+        // Layers with the attribute property are placed as attributes
+        let attribute = layer.properties.get("attribute").map(|name| {
+            let attribute = create_attribute(schema, name, &layer.properties);
+            if attribute.is_none() {
+                warnings
+                    .push(format!("Attribute {} is not on the schema", name));
+            }
+            attribute
+        });
+        let target = if attribute.is_some() {
+            None
+        } else {
+            let target = find_target_layer(layer, next_index);
+            match target {
+                Some(index) => next_index = index + 1,
+                None => warnings.push(format!(
+                    "Layer {} has no map layer left to be placed on",
+                    layer.name
+                )),
+            }
+            target
+        };
+
+        for (index, gid) in layer.data.iter().enumerate() {
+            if *gid == 0 || layer.width == 0 {
+                continue;
+            }
+            let (tile_x, tile_y) =
+                (index as u32 % layer.width, index as u32 / layer.width);
+            let (map, tile_num) = locate(tile_x, tile_y);
+            let mapdata = match maps.get_mut(&map) {
+                Some(mapdata) => mapdata,
+                None => continue,
+            };
+            if let Some(Some(attribute)) = &attribute {
+                mapdata.attribute[tile_num] = schema.add_attribute(
+                    &mapdata.attribute[tile_num],
+                    attribute.clone(),
+                );
+            } else if let Some(layer_index) = target {
+                mapdata.tile[layer_index].id[tile_num] =
+                    get_tile_id(*gid) as u32;
+            }
+        }
+    }
+
+    for group in tiled
+        .object_groups
+        .iter()
+        .filter(|group| is_attribute_group(group))
+    {
+        for object in group.objects.iter() {
+            let attribute = match create_attribute(
+                schema,
+                &object.kind,
+                &object.properties,
+            ) {
+                Some(attribute) => attribute,
+                None => {
+                    warnings.push(format!(
+                        "Object {} has no attribute type",
+                        object.name
+                    ));
+                    continue;
+                }
+            };
+            // Every tile that is covered by the object gets the attribute
+            for (tile_x, tile_y) in tiled.object_tiles(object) {
+                let (map, tile_num) = locate(tile_x, tile_y);
+                if let Some(mapdata) = maps.get_mut(&map) {
+                    mapdata.attribute[tile_num] = schema.add_attribute(
+                        &mapdata.attribute[tile_num],
+                        attribute.clone(),
+                    );
+                }
+            }
+        }
+    }
+
+    // Layers written by the export keep the data that Tiled has no use for
+    for object in tiled.find_objects("dir_blocks", "DirBlock") {
+        let dir = get_property(object, "dir") as u8;
+        for (tile_x, tile_y) in tiled.object_tiles(object) {
+            let (map, tile_num) = locate(tile_x, tile_y);
+            if let Some(mapdata) = maps.get_mut(&map) {
+                mapdata.dir_block[tile_num] = dir;
+            }
+        }
+    }
+    if tiled.has_group("zones") {
+        maps.values_mut().for_each(|mapdata| mapdata.zones.clear());
+        let mut zones: Vec<(i32, &TiledObject)> = tiled
+            .find_objects("zones", "Zone")
+            .map(|object| (get_property(object, "index") as i32, object))
+            .collect();
+        zones.sort_by_key(|(index, _)| *index);
+        for (_, object) in zones {
+            let mapdata = match maps.get_mut(&tiled.object_map(object)) {
+                Some(mapdata) => mapdata,
+                None => continue,
+            };
+            let npcs = object
+                .properties
+                .get("npcs")
+                .and_then(|text| serde_json::from_str(text).ok())
+                .unwrap_or_default();
+            mapdata.zones.push(MapZoneData {
+                name: object.name.clone(),
+                max_npc: get_property(object, "max_npc") as u64,
+                npcs,
+                spawns: Vec::new(),
+            });
+        }
+        for object in tiled.find_objects("zones", "Spawn") {
+            let zone = get_property(object, "zone") as usize;
+            for (tile_x, tile_y) in tiled.object_tiles(object) {
+                let (map, _) = locate(tile_x, tile_y);
+                let spawn = ((tile_x % 32) as u16, 31 - (tile_y % 32) as u16);
+                if let Some(data) = maps
+                    .get_mut(&map)
+                    .and_then(|mapdata| mapdata.zones.get_mut(zone))
+                {
+                    data.spawns.push(spawn);
+                }
+            }
+        }
+    }
+    // Only the maps that were part of the export are brought back
+    if tiled.has_group("maps") {
+        let mut found = IndexMap::new();
+        for object in tiled.find_objects("maps", "Map") {
+            let key = tiled.object_map(object);
+            if let Some(mut mapdata) = maps.shift_remove(&key) {
+                mapdata.music = object
+                    .properties
+                    .get("music")
+                    .filter(|music| !music.is_empty())
+                    .cloned();
+                mapdata.weather = object
+                    .properties
+                    .get("weather")
+                    .and_then(|weather| {
+                        serde_json::from_value(serde_json::Value::String(
+                            weather.clone(),
+                        ))
+                        .ok()
+                    })
+                    .unwrap_or_default();
+                mapdata.entities = object
+                    .properties
+                    .get("entities")
+                    .and_then(|text| serde_json::from_str(text).ok())
+                    .unwrap_or_default();
+                found.insert(key, mapdata);
+            }
+        }
+        maps = found;
+    }
+
+    Ok(TiledImport {
+        maps: maps.into_values().collect(),
+        warnings,
+    })
+}
+
+// Export //
+fn json_property(name: &str, value: &InsertTypes) -> serde_json::Value {
+    match value {
+        InsertTypes::Int(data) => {
+            json!({ "name": name, "type": "int", "value": data })
+        }
+        InsertTypes::UInt(data) => {
+            json!({ "name": name, "type": "int", "value": data })
+        }
+        InsertTypes::Bool(data) => {
+            json!({ "name": name, "type": "bool", "value": data })
+        }
+        InsertTypes::Str(data) => {
+            json!({ "name": name, "type": "string", "value": data })
+        }
+    }
+}
+
+// Tiled objects are placed with pixels, counting from the top left
+fn json_object(
+    id: &mut usize,
+    kind: &str,
+    name: &str,
+    rect: (u32, u32, u32, u32),
+    properties: Vec<serde_json::Value>,
+) -> serde_json::Value {
+    *id += 1;
+    json!({
+        "id": *id,
+        "name": name,
+        "type": kind,
+        "x": rect.0 * TEXTURE_SIZE,
+        "y": rect.1 * TEXTURE_SIZE,
+        "width": rect.2 * TEXTURE_SIZE,
+        "height": rect.3 * TEXTURE_SIZE,
+        "rotation": 0,
+        "visible": true,
+        "properties": properties,
+    })
+}
+
+// Write the maps of the rectangle that starts on the top left map as a
+// single Tiled map, the import will split it back to the same maps
+pub fn export_tiled(
+    tile_index: &TileIndex,
+    schema: &AttributeSchema,
+    path: &Path,
+    start: (i32, i32, u64),
+    size: (u32, u32),
+    loaded_maps: &IndexMap<String, MapData>,
+) -> Result<usize, AscendingError> {
+    let (map_count_x, map_count_y) = (size.0.max(1), size.1.max(1));
+    let (width, height) = (map_count_x * 32, map_count_y * 32);
+    let mut maps = IndexMap::new();
+    for count_y in 0..map_count_y {
+        for count_x in 0..map_count_x {
+            let (x, y) = (start.0 + count_x as i32, start.1 - count_y as i32);
+            let key = format!("{}_{}_{}", x, y, start.2);
+            let mapdata = match loaded_maps.get(&key) {
+                Some(data) => data.clone(),
+                None if is_map_exist(x, y, start.2) => {
+                    load_file(x, y, start.2)?
+                }
+                None => continue,
+            };
+            maps.insert((count_x, count_y), mapdata);
+        }
+    }
+    if maps.is_empty() {
+        return Err(tiled_error("There are no maps to export".to_string()));
+    }
+
+    // Every tilesheet is a tileset, placed one after another
+    let tile_count = MAX_TILE_X * MAX_TILE_Y;
+    let tilesets: Vec<serde_json::Value> = tile_index
+        .tilesheets
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let image = format!("./images/tiles/{}", name);
+            let image = fs::canonicalize(&image)
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or(image);
+            json!({
+                "firstgid": 1 + index as u32 * tile_count,
+                "name": name.trim_end_matches(".png"),
+                "image": image,
+                "imagewidth": MAX_TILE_X * TEXTURE_SIZE,
+                "imageheight": MAX_TILE_Y * TEXTURE_SIZE,
+                "columns": MAX_TILE_X,
+                "tilecount": tile_count,
+                "tilewidth": TEXTURE_SIZE,
+                "tileheight": TEXTURE_SIZE,
+                "margin": 0,
+                "spacing": 0,
+            })
+        })
+        .collect();
+    let get_gid = |id: u32| -> u32 {
+        match tile_index.tiles.get(&(id as usize)) {
+            Some((x, y, sheet)) => {
+                let (column, row) = (x / TEXTURE_SIZE, y / TEXTURE_SIZE);
+                1 + sheet * tile_count + row * MAX_TILE_X + column
+            }
+            None => 0,
+        }
+    };
+    // Position of the map tile within the Tiled map
+    let tiled_pos = |map: (u32, u32), tile_num: usize| {
+        let (x, y) = (tile_num as u32 % 32, tile_num as u32 / 32);
+        (map.0 * 32 + x, map.1 * 32 + (31 - y))
+    };
+
+    let mut layers = Vec::new();
+    for (index, layer) in MapLayers::LAYERS.iter().enumerate() {
+        let mut data = vec![0; (width * height) as usize];
+        for (map, mapdata) in maps.iter() {
+            for (tile_num, id) in mapdata.tile[index].id.iter().enumerate() {
+                let (x, y) = tiled_pos(*map, tile_num);
+                data[(y * width + x) as usize] = get_gid(*id);
+            }
+        }
+        layers.push(json!({
+            "id": index + 1,
+            "name": layer.as_str(),
+            "type": "tilelayer",
+            "width": width,
+            "height": height,
+            "x": 0,
+            "y": 0,
+            "opacity": 1,
+            "visible": true,
+            "data": data,
+        }));
+    }
+
+    let mut object_id = 0;
+    let (mut attributes, mut dir_blocks) = (Vec::new(), Vec::new());
+    let (mut zones, mut map_objects) = (Vec::new(), Vec::new());
+    for (map, mapdata) in maps.iter() {
+        for tile_num in 0..1024 {
+            let (x, y) = tiled_pos(*map, tile_num);
+            for attribute in mapdata.attribute[tile_num].iter() {
+                let name = attribute.name();
+                let fields = schema
+                    .get(name)
+                    .map(|data| data.fields.clone())
+                    .unwrap_or_default();
+                let mut properties: Vec<serde_json::Value> = fields
+                    .iter()
+                    .zip(attribute.get_data())
+                    .map(|(field, value)| json_property(&field.name, &value))
+                    .collect();
+                // The fields are for the edits on Tiled, custom attributes
+                // that are not on the schema are only kept by the data
+                let data = serde_json::to_string(attribute).map_err(|e| {
+                    tiled_error(format!("Serdes File Error Err {:?}", e))
+                })?;
+                properties.push(json_property("data", &InsertTypes::Str(data)));
+                attributes.push(json_object(
+                    &mut object_id,
+                    name,
+                    "",
+                    (x, y, 1, 1),
+                    properties,
+                ));
+            }
+            if mapdata.dir_block[tile_num] > 0 {
+                let dir = mapdata.dir_block[tile_num] as i64;
+                dir_blocks.push(json_object(
+                    &mut object_id,
+                    "DirBlock",
+                    "",
+                    (x, y, 1, 1),
+                    vec![json_property("dir", &InsertTypes::Int(dir))],
+                ));
+            }
+        }
+
+        let map_rect = (map.0 * 32, map.1 * 32, 32, 32);
+        for (index, zone) in mapdata.zones.iter().enumerate() {
+            let npcs = serde_json::to_string(&zone.npcs).unwrap_or_default();
+            zones.push(json_object(
+                &mut object_id,
+                "Zone",
+                &zone.name,
+                map_rect,
+                vec![
+                    json_property("index", &InsertTypes::UInt(index as u64)),
+                    json_property("max_npc", &InsertTypes::UInt(zone.max_npc)),
+                    json_property("npcs", &InsertTypes::Str(npcs)),
+                ],
+            ));
+            for spawn in zone.spawns.iter() {
+                let tile_num = get_tile_pos(spawn.0 as i32, spawn.1 as i32);
+                let (x, y) = tiled_pos(*map, tile_num);
+                zones.push(json_object(
+                    &mut object_id,
+                    "Spawn",
+                    "",
+                    (x, y, 1, 1),
+                    vec![json_property(
+                        "zone",
+                        &InsertTypes::UInt(index as u64),
+                    )],
+                ));
+            }
+        }
+
+        let weather = serde_json::to_value(mapdata.weather)
+            .ok()
+            .and_then(|value| value.as_str().map(|name| name.to_string()))
+            .unwrap_or_default();
+        let entities =
+            serde_json::to_string(&mapdata.entities).unwrap_or_default();
+        map_objects.push(json_object(
+            &mut object_id,
+            "Map",
+            &format!(
+                "{}_{}_{}",
+                mapdata.position.x, mapdata.position.y, mapdata.position.group
+            ),
+            map_rect,
+            vec![
+                json_property(
+                    "music",
+                    &InsertTypes::Str(
+                        mapdata.music.clone().unwrap_or_default(),
+                    ),
+                ),
+                json_property("weather", &InsertTypes::Str(weather)),
+                json_property("entities", &InsertTypes::Str(entities)),
+            ],
+        ));
+    }
+    for (name, objects) in [
+        ("attributes", attributes),
+        ("dir_blocks", dir_blocks),
+        ("zones", zones),
+        ("maps", map_objects),
+    ] {
+        layers.push(json!({
+            "id": layers.len() + 1,
+            "name": name,
+            "type": "objectgroup",
+            "draworder": "topdown",
+            "x": 0,
+            "y": 0,
+            "opacity": 1,
+            "visible": name != "maps",
+            "objects": objects,
+        }));
+    }
+
+    let data = json!({
+        "type": "map",
+        "version": "1.10",
+        "orientation": "orthogonal",
+        "renderorder": "right-down",
+        "infinite": false,
+        "width": width,
+        "height": height,
+        "tilewidth": TEXTURE_SIZE,
+        "tileheight": TEXTURE_SIZE,
+        "nextlayerid": layers.len() + 1,
+        "nextobjectid": object_id + 1,
+        "tilesets": tilesets,
+        "layers": layers,
+    });
+    let text = serde_json::to_string(&data)
+        .map_err(|e| tiled_error(format!("Serdes File Error Err {:?}", e)))?;
+    fs::write(path, text).map_err(|e| {
+        tiled_error(format!("Failed to write {:?}, Err {:?}", path, e))
+    })?;
+    Ok(maps.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{EntityKind, MapEntity};
+
+    fn test_tile_index() -> TileIndex {
+        TileIndex {
+            tilesheets: vec![
+                "tile_0.png".to_string(),
+                "tile_1.png".to_string(),
+            ],
+            tiles: IndexMap::from([
+                (1, (0, 0, 0)),
+                (2, (TEXTURE_SIZE * 3, 0, 0)),
+                (3, (TEXTURE_SIZE, TEXTURE_SIZE * 2, 1)),
+            ]),
+        }
+    }
+
+    fn test_map(x: i32) -> MapData {
+        let mut mapdata = MapData::default(x, 0, 0);
+        mapdata.tile[0].id[get_tile_pos(0, 0)] = 1;
+        mapdata.tile[3].id[get_tile_pos(5, 31)] = 2;
+        mapdata.tile[8].id[get_tile_pos(31, 7)] = 3;
+        mapdata.attribute[get_tile_pos(1, 1)] =
+            vec![MapAttribute::Blocked, MapAttribute::Sign("Hi".to_string())];
+        mapdata.attribute[get_tile_pos(2, 1)] =
+            vec![MapAttribute::LocalizedSign("sign.key".to_string())];
+        mapdata.attribute[get_tile_pos(3, 1)] =
+            vec![MapAttribute::Trigger(TriggerData {
+                kind: TriggerKind::Proximity,
+                script: "open_door".to_string(),
+                radius: 3,
+                params: vec![TriggerParam {
+                    key: "door".to_string(),
+                    value: "2".to_string(),
+                }],
+            })];
+        mapdata.attribute[get_tile_pos(4, 1)] =
+            vec![MapAttribute::Custom(CustomAttribute {
+                name: "Fishing".to_string(),
+                data: vec![
+                    InsertTypes::UInt(7),
+                    InsertTypes::Int(-2),
+                    InsertTypes::Bool(true),
+                    InsertTypes::Str("lake".to_string()),
+                ],
+            })];
+        mapdata.attribute[get_tile_pos(5, 1)] = vec![
+            MapAttribute::Warp(WarpData {
+                map_x: -1,
+                map_y: 2,
+                map_group: 0,
+                tile_x: 30,
+                tile_y: 4,
+            }),
+            MapAttribute::NpcBlocked,
+        ];
+        mapdata.dir_block[get_tile_pos(6, 2)] = 5;
+        mapdata.zones = vec![
+            MapZoneData {
+                name: "Forest".to_string(),
+                max_npc: 4,
+                npcs: vec![ZoneNpc::new(12)],
+                spawns: vec![(3, 4), (10, 20)],
+            },
+            MapZoneData {
+                name: "Cave".to_string(),
+                max_npc: 1,
+                npcs: Vec::new(),
+                spawns: vec![(31, 31)],
+            },
+        ];
+        mapdata.entities = vec![MapEntity {
+            kind: EntityKind::Object,
+            index: 9,
+            x: 8,
+            y: 9,
+            ..Default::default()
+        }];
+        mapdata.music = Some("town.ogg".to_string());
+        mapdata.weather = Weather::Rain;
+        mapdata
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let (tile_index, schema) =
+            (test_tile_index(), AttributeSchema::default());
+        let mut loaded_maps = IndexMap::new();
+        for x in 0..2 {
+            loaded_maps.insert(format!("{}_0_0", x), test_map(x));
+        }
+        let path = std::env::temp_dir().join("tiled_round_trip.json");
+
+        let count = export_tiled(
+            &tile_index,
+            &schema,
+            &path,
+            (0, 0, 0),
+            (2, 1),
+            &loaded_maps,
+        )
+        .unwrap();
+        assert_eq!(count, 2);
+
+        // Importing on top of empty maps has to bring back everything
+        let empty_maps: IndexMap<String, MapData> = (0..2)
+            .map(|x| (format!("{}_0_0", x), MapData::default(x, 0, 0)))
+            .collect();
+        let import =
+            import_tiled(&tile_index, &schema, &path, (0, 0, 0), &empty_maps)
+                .unwrap();
+        let _ = fs::remove_file(&path);
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert_eq!(import.maps.len(), 2);
+
+        for mapdata in import.maps.iter() {
+            let expected = test_map(mapdata.position.x);
+            for (layer, tile) in expected.tile.iter().enumerate() {
+                assert_eq!(mapdata.tile[layer].id, tile.id, "layer {}", layer);
+            }
+            assert_eq!(mapdata.attribute, expected.attribute);
+            assert_eq!(mapdata.dir_block, expected.dir_block);
+            assert_eq!(mapdata.zones, expected.zones);
+            assert_eq!(mapdata.entities, expected.entities);
+            assert_eq!(mapdata.music, expected.music);
+            assert_eq!(mapdata.weather, expected.weather);
+        }
+    }
+
+    #[test]
+    fn edited_fields_replace_the_saved_data() {
+        let schema = AttributeSchema::default();
+        let saved = MapAttribute::Shop(3);
+        let mut properties = IndexMap::from([
+            ("Index".to_string(), "3".to_string()),
+            ("data".to_string(), serde_json::to_string(&saved).unwrap()),
+        ]);
+        assert_eq!(create_attribute(&schema, "Shop", &properties), Some(saved));
+
+        properties.insert("Index".to_string(), "8".to_string());
+        assert_eq!(
+            create_attribute(&schema, "Shop", &properties),
+            Some(MapAttribute::Shop(8))
+        );
+    }
+}
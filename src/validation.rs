@@ -0,0 +1,518 @@
+use graphics::*;
+use indexmap::IndexMap;
+use std::path::Path;
+
+use crate::{
+    attributes::*, map::get_dir_visible, map_data::*,
+    map_merge::load_merge_conflicts, resource::AudioCollection,
+};
+
+// A broken reference found on one of the maps
+#[derive(Clone, Debug)]
+pub struct ValidationIssue {
+    pub x: i32,
+    pub y: i32,
+    pub group: u64,
+    pub tile: Option<(u32, u32)>,
+    pub reason: String,
+}
+
+impl ValidationIssue {
+    fn new(
+        key: (i32, i32, u64),
+        tile: Option<(u32, u32)>,
+        reason: String,
+    ) -> Self {
+        Self {
+            x: key.0,
+            y: key.1,
+            group: key.2,
+            tile,
+            reason,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self.tile {
+            Some((tile_x, tile_y)) => format!(
+                "Map [ X: {} Y: {} Group: {} ] Tile [ X: {} Y: {} ] {}",
+                self.x, self.y, self.group, tile_x, tile_y, self.reason
+            ),
+            None => format!(
+                "Map [ X: {} Y: {} Group: {} ] {}",
+                self.x, self.y, self.group, self.reason
+            ),
+        }
+    }
+
+    // Shorter text used on the validation list
+    pub fn short_description(&self) -> String {
+        match self.tile {
+            Some((tile_x, tile_y)) => format!(
+                "{},{},{} ({},{}) {}",
+                self.x, self.y, self.group, tile_x, tile_y, self.reason
+            ),
+            None => {
+                format!("{},{},{} {}", self.x, self.y, self.group, self.reason)
+            }
+        }
+    }
+}
+
+fn read_map(x: i32, y: i32, group: u64) -> Result<MapData, AscendingError> {
+    let name = format!("./data/maps/{}_{}_{}.json", x, y, group);
+    read_map_file(Path::new(&name))
+}
+
+// Check every map of the data directory, the loaded maps are used
+// instead of their file so that unsaved changes are validated too. The
+// music of the maps is looked up on the given audio list
+pub fn validate_maps(
+    loaded_maps: &IndexMap<String, MapData>,
+    audio: &[String],
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let mut maps = IndexMap::new();
+    for (x, y, group) in get_all_maps() {
+        // The binary files are read by the game, so they must be decodable
+        let name = format!("./data/maps/{}_{}_{}.bin", x, y, group);
+        if Path::new(&name).exists() {
+            if let Err(e) = load_file_bin(x, y, group) {
+                issues.push(ValidationIssue::new(
+                    (x, y, group),
+                    None,
+                    format!("Failed to read map binary: {:?}", e),
+                ));
+            }
+        }
+
+        let key = format!("{}_{}_{}", x, y, group);
+        if let Some(mapdata) = loaded_maps.get(&key) {
+            maps.insert((x, y, group), mapdata.clone());
+            continue;
+        }
+        match read_map(x, y, group) {
+            Ok(mapdata) => {
+                maps.insert((x, y, group), mapdata);
+            }
+            Err(e) => issues.push(ValidationIssue::new(
+                (x, y, group),
+                None,
+                format!("Failed to read map: {:?}", e),
+            )),
+        }
+    }
+
+    issues.extend(check_maps(&maps, audio));
+    issues
+}
+
+// Checks of the tiles and settings of each map
+fn check_maps(
+    maps: &IndexMap<(i32, i32, u64), MapData>,
+    audio: &[String],
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for (key, mapdata) in maps.iter() {
+        for (tile_num, attributes) in mapdata.attribute.iter().enumerate() {
+            let tile = ((tile_num % 32) as u32, (tile_num / 32) as u32);
+            for attribute in attributes.iter() {
+                let reason = match attribute {
+                    MapAttribute::Warp(data) => {
+                        check_warp(*key, tile, data, maps)
+                    }
+                    MapAttribute::Trigger(data)
+                        if data.script.trim().is_empty() =>
+                    {
+                        Some("Trigger has no script".to_string())
+                    }
+                    _ => None,
+                };
+                if let Some(reason) = reason {
+                    issues.push(ValidationIssue::new(*key, Some(tile), reason));
+                }
+            }
+        }
+
+        for zone in mapdata.zones.iter() {
+            for (x, y) in zone.spawns.iter() {
+                let tile_num = get_tile_pos(*x as i32, *y as i32);
+                if is_blocked(mapdata, tile_num) {
+                    issues.push(ValidationIssue::new(
+                        *key,
+                        Some((*x as u32, *y as u32)),
+                        format!("{} spawns on a blocked tile", zone.name),
+                    ));
+                }
+            }
+            if zone.npcs.iter().any(|npc| npc.level_min > npc.level_max) {
+                issues.push(ValidationIssue::new(
+                    *key,
+                    None,
+                    format!(
+                        "{} has an npc with an invalid level range",
+                        zone.name
+                    ),
+                ));
+            }
+        }
+
+        for entity in mapdata.entities.iter() {
+            let tile_num = get_tile_pos(entity.x as i32, entity.y as i32);
+            if is_blocked(mapdata, tile_num) {
+                issues.push(ValidationIssue::new(
+                    *key,
+                    Some((entity.x as u32, entity.y as u32)),
+                    format!(
+                        "{} is placed on a blocked tile",
+                        entity.kind.as_str()
+                    ),
+                ));
+            }
+        }
+
+        if let Some(music) = &mapdata.music {
+            if !audio.contains(music) {
+                issues.push(ValidationIssue::new(
+                    *key,
+                    None,
+                    format!("Music {} is not in ./audio", music),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+fn is_blocked(mapdata: &MapData, tile_num: usize) -> bool {
+    mapdata
+        .attribute
+        .get(tile_num)
+        .map(|attributes| attributes.contains(&MapAttribute::Blocked))
+        .unwrap_or_default()
+}
+
+fn check_warp(
+    key: (i32, i32, u64),
+    tile: (u32, u32),
+    data: &WarpData,
+    maps: &IndexMap<(i32, i32, u64), MapData>,
+) -> Option<String> {
+    let target = (data.map_x, data.map_y, data.map_group);
+    let mapdata = match maps.get(&target) {
+        Some(mapdata) => mapdata,
+        None => {
+            return Some(format!(
+                "Warp to missing map [ X: {} Y: {} Group: {} ]",
+                data.map_x, data.map_y, data.map_group
+            ))
+        }
+    };
+    if data.tile_x >= 32 || data.tile_y >= 32 {
+        return Some(format!(
+            "Warp to tile outside the map [ X: {} Y: {} ]",
+            data.tile_x, data.tile_y
+        ));
+    }
+    if target == key && (data.tile_x, data.tile_y) == tile {
+        return Some("Warp leads to itself".to_string());
+    }
+    let tile_num = get_tile_pos(data.tile_x as i32, data.tile_y as i32);
+    if is_blocked(mapdata, tile_num) {
+        return Some("Warp to a blocked tile".to_string());
+    }
+    None
+}
+
+// Side of the map that touches the neighbouring map
+#[derive(Copy, Clone)]
+enum Seam {
+    Right,
+    Top,
+}
+
+impl Seam {
+    fn offset(&self) -> (i32, i32) {
+        match self {
+            Seam::Right => (1, 0),
+            Seam::Top => (0, 1),
+        }
+    }
+
+    // Tile on this map and on the neighbouring map
+    fn tiles(&self, index: i32) -> ((i32, i32), (i32, i32)) {
+        match self {
+            Seam::Right => ((31, index), (0, index)),
+            Seam::Top => ((index, 31), (index, 0)),
+        }
+    }
+
+    // Dir block that stops the movement toward the neighbouring map,
+    // and the one that stops it on the other way
+    fn dir_index(&self) -> (usize, usize) {
+        // Up, Left, Down, Right
+        match self {
+            Seam::Right => (3, 1),
+            Seam::Top => (0, 2),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Seam::Right => "Right edge column",
+            Seam::Top => "Top edge row",
+        }
+    }
+}
+
+// Compare the edges of every pair of neighbouring maps within the group,
+// tiles are optional as some borders are meant to change
+pub fn check_seams(
+    loaded_maps: &IndexMap<String, MapData>,
+    group: u64,
+    check_tiles: bool,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let mut maps = IndexMap::new();
+    for (x, y) in get_group_maps(group) {
+        let key = format!("{}_{}_{}", x, y, group);
+        if let Some(mapdata) = loaded_maps.get(&key) {
+            maps.insert((x, y), mapdata.clone());
+            continue;
+        }
+        match read_map(x, y, group) {
+            Ok(mapdata) => {
+                maps.insert((x, y), mapdata);
+            }
+            Err(e) => issues.push(ValidationIssue::new(
+                (x, y, group),
+                None,
+                format!("Failed to read map: {:?}", e),
+            )),
+        }
+    }
+
+    for ((x, y), mapdata) in maps.iter() {
+        for seam in [Seam::Right, Seam::Top] {
+            let (offset_x, offset_y) = seam.offset();
+            let other_pos = (x + offset_x, y + offset_y);
+            let other = match maps.get(&other_pos) {
+                Some(other) => other,
+                None => continue,
+            };
+            let reasons: Vec<Option<String>> = (0..32)
+                .map(|index| {
+                    compare_seam(
+                        mapdata,
+                        other,
+                        other_pos,
+                        seam,
+                        index,
+                        check_tiles,
+                    )
+                })
+                .collect();
+
+            // Neighbouring tiles with the same problem are reported once
+            let mut start = 0;
+            while start < 32 {
+                let reason = match &reasons[start] {
+                    Some(reason) => reason,
+                    None => {
+                        start += 1;
+                        continue;
+                    }
+                };
+                let mut end = start;
+                while end + 1 < 32 && reasons[end + 1].as_ref() == Some(reason)
+                {
+                    end += 1;
+                }
+                let ((tile_x, tile_y), _) = seam.tiles(start as i32);
+                let range = if start == end {
+                    start.to_string()
+                } else {
+                    format!("{}-{}", start, end)
+                };
+                issues.push(ValidationIssue::new(
+                    (*x, *y, group),
+                    Some((tile_x as u32, tile_y as u32)),
+                    format!("{} {}: {}", seam.description(), range, reason),
+                ));
+                start = end + 1;
+            }
+        }
+    }
+
+    issues
+}
+
+fn compare_seam(
+    mapdata: &MapData,
+    other: &MapData,
+    other_pos: (i32, i32),
+    seam: Seam,
+    index: i32,
+    check_tiles: bool,
+) -> Option<String> {
+    let ((x, y), (other_x, other_y)) = seam.tiles(index);
+    let (tile_num, other_num) =
+        (get_tile_pos(x, y), get_tile_pos(other_x, other_y));
+    let this_map = format!("map {},{}", mapdata.position.x, mapdata.position.y);
+    let other_map = format!("map {},{}", other_pos.0, other_pos.1);
+    let mut reasons = Vec::new();
+
+    for attribute in [MapAttribute::Blocked, MapAttribute::NpcBlocked] {
+        let is_set = |data: &MapData, tile_num: usize| {
+            data.attribute[tile_num].contains(&attribute)
+        };
+        match (is_set(mapdata, tile_num), is_set(other, other_num)) {
+            (true, false) => reasons.push(format!(
+                "{} on {} only",
+                attribute.name(),
+                this_map
+            )),
+            (false, true) => reasons.push(format!(
+                "{} on {} only",
+                attribute.name(),
+                other_map
+            )),
+            _ => {}
+        }
+    }
+
+    let (dir, other_dir) = seam.dir_index();
+    let dir_block = get_dir_visible(mapdata.dir_block[tile_num])[dir];
+    let other_block = get_dir_visible(other.dir_block[other_num])[other_dir];
+    match (dir_block, other_block) {
+        (true, false) => {
+            reasons.push(format!("Dir block on {} only", this_map))
+        }
+        (false, true) => {
+            reasons.push(format!("Dir block on {} only", other_map))
+        }
+        _ => {}
+    }
+
+    if check_tiles {
+        for layer in 0..mapdata.tile.len().min(other.tile.len()) {
+            let has_tile = mapdata.tile[layer].id[tile_num] > 0;
+            let other_tile = other.tile[layer].id[other_num] > 0;
+            if has_tile != other_tile {
+                reasons.push(format!("Layer {} stops at the edge", layer + 1));
+            }
+        }
+    }
+
+    if reasons.is_empty() {
+        None
+    } else {
+        Some(reasons.join(", "))
+    }
+}
+
+// Used when the editor is launched with --validate, the merge conflicts
+// are listed on their own as they are not a problem of the map files
+pub fn run_headless_validation() -> i32 {
+    let issues = validate_maps(&IndexMap::new(), &AudioCollection::new().audio);
+    issues.iter().for_each(|issue| {
+        println!("{}", issue.description());
+    });
+    println!("Validation found {} problem(s)", issues.len());
+
+    let conflicts = load_merge_conflicts();
+    if !conflicts.is_empty() {
+        conflicts.iter().for_each(|conflict| {
+            println!("{}", conflict.to_issue().description());
+        });
+        println!("There are {} merge conflict(s) to review", conflicts.len());
+    }
+
+    if issues.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{EntityKind, MapEntity};
+
+    fn reasons(issues: &[ValidationIssue]) -> Vec<(Option<(u32, u32)>, &str)> {
+        issues
+            .iter()
+            .map(|issue| (issue.tile, issue.reason.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn valid_maps_have_no_issues() {
+        let mut mapdata = MapData::default(0, 0, 0);
+        mapdata.attribute[get_tile_pos(1, 1)] =
+            vec![MapAttribute::Warp(WarpData {
+                map_x: 0,
+                map_y: 0,
+                map_group: 0,
+                tile_x: 2,
+                tile_y: 2,
+            })];
+        mapdata.music = Some("town.ogg".to_string());
+        let maps = IndexMap::from([((0, 0, 0), mapdata)]);
+        assert!(check_maps(&maps, &["town.ogg".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn broken_tiles_are_reported() {
+        let warp = |map_x: i32, tile_x: u32| {
+            MapAttribute::Warp(WarpData {
+                map_x,
+                map_y: 0,
+                map_group: 0,
+                tile_x,
+                tile_y: 0,
+            })
+        };
+        let mut mapdata = MapData::default(0, 0, 0);
+        mapdata.attribute[get_tile_pos(0, 0)] = vec![warp(5, 0)];
+        mapdata.attribute[get_tile_pos(1, 0)] = vec![warp(1, 40)];
+        mapdata.attribute[get_tile_pos(2, 0)] = vec![warp(1, 3)];
+        mapdata.attribute[get_tile_pos(3, 0)] =
+            vec![MapAttribute::Trigger(TriggerData::default())];
+        mapdata.attribute[get_tile_pos(4, 4)] = vec![MapAttribute::Blocked];
+        mapdata.zones = vec![MapZoneData {
+            name: "Field".to_string(),
+            spawns: vec![(4, 4)],
+            ..Default::default()
+        }];
+        mapdata.entities = vec![MapEntity {
+            kind: EntityKind::Npc,
+            x: 4,
+            y: 4,
+            ..Default::default()
+        }];
+        mapdata.music = Some("missing.ogg".to_string());
+
+        let mut other = MapData::default(1, 0, 0);
+        other.attribute[get_tile_pos(3, 0)] = vec![MapAttribute::Blocked];
+
+        let maps = IndexMap::from([((0, 0, 0), mapdata), ((1, 0, 0), other)]);
+        let issues = check_maps(&maps, &[]);
+        assert_eq!(
+            reasons(&issues),
+            vec![
+                (Some((0, 0)), "Warp to missing map [ X: 5 Y: 0 Group: 0 ]"),
+                (Some((1, 0)), "Warp to tile outside the map [ X: 40 Y: 0 ]"),
+                (Some((2, 0)), "Warp to a blocked tile"),
+                (Some((3, 0)), "Trigger has no script"),
+                (Some((4, 4)), "Field spawns on a blocked tile"),
+                (Some((4, 4)), "Npc is placed on a blocked tile"),
+                (None, "Music missing.ogg is not in ./audio"),
+            ]
+        );
+    }
+}